@@ -4,11 +4,10 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 
 use multi_party_ecdsa::protocols::gg_2020::state_machine::keygen::Keygen;
+use multi_party_ecdsa::transport::sse::{join_computation, ClientConfig, PartyIndex};
+use multi_party_ecdsa::utilities::session_id::SessionId;
 use round_based::async_runtime::AsyncProtocol;
 
-mod gg20_sm_client;
-use gg20_sm_client::join_computation;
-
 #[derive(Debug, StructOpt)]
 struct Cli {
     #[structopt(short, long, default_value = "http://localhost:8000/")]
@@ -36,20 +35,31 @@ async fn main() -> Result<()> {
         .await
         .context("cannot create output file")?;
 
-    let (_i, incoming, outgoing) = join_computation(args.address, &args.room)
-        .await
-        .context("join computation")?;
+    let (_i, incoming, outgoing) = join_computation(
+        &args.address,
+        &args.room,
+        PartyIndex::Explicit(args.index),
+        ClientConfig::default(),
+    )
+    .await
+    .context("join computation")?;
 
     let incoming = incoming.fuse();
     tokio::pin!(incoming);
     tokio::pin!(outgoing);
 
-    let keygen = Keygen::new(args.index, args.threshold, args.number_of_parties)?;
+    let session_id = SessionId::new(format!("keygen/{}", args.room).as_bytes());
+    let keygen = Keygen::new(
+        args.index,
+        args.threshold,
+        args.number_of_parties,
+        session_id,
+    )?;
     let output = AsyncProtocol::new(keygen, incoming, outgoing)
         .run()
         .await
         .map_err(|e| anyhow!("protocol execution terminated with error: {}", e))?;
-    let output = serde_json::to_vec_pretty(&output).context("serialize output")?;
+    let output = output.to_versioned_bytes().context("serialize output")?;
     tokio::io::copy(&mut output.as_slice(), &mut output_file)
         .await
         .context("save output to file")?;
@@ -1,22 +1,23 @@
+use std::convert::TryFrom;
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Context, Result};
 use futures::{SinkExt, StreamExt, TryStreamExt};
 use structopt::StructOpt;
 
-use curv::arithmetic::Converter;
-use curv::BigInt;
+use curv::elliptic::curves::Secp256k1;
 
+use multi_party_ecdsa::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
 use multi_party_ecdsa::protocols::gg_2020::state_machine::sign::{
     stages::offline_stage::OfflineStage,
     stages::sign_manual::SignManual
 };
+use multi_party_ecdsa::utilities::message_digest::MessageDigest;
+use multi_party_ecdsa::transport::sse::{join_computation, ClientConfig, PartyIndex};
+use multi_party_ecdsa::utilities::session_id::SessionId;
 use round_based::async_runtime::AsyncProtocol;
 use round_based::Msg;
 
-mod gg20_sm_client;
-use gg20_sm_client::join_computation;
-
 #[derive(Debug, StructOpt)]
 struct Cli {
     #[structopt(short, long, default_value = "http://localhost:8000/")]
@@ -28,6 +29,7 @@ struct Cli {
 
     #[structopt(short, long, use_delimiter(true))]
     parties: Vec<u16>,
+    /// Message to sign, it's hashed with SHA-256
     #[structopt(short, long)]
     data_to_sign: String,
 }
@@ -38,33 +40,56 @@ async fn main() -> Result<()> {
     let local_share = tokio::fs::read(args.local_share)
         .await
         .context("cannot read local share")?;
-    let local_share = serde_json::from_slice(&local_share).context("parse local share")?;
+    let local_share = LocalKey::<Secp256k1>::from_versioned_bytes(&local_share)
+        .context("parse local share")?;
+    local_share
+        .validate()
+        .into_result()
+        .context("local share is corrupted")?;
     let number_of_parties = args.parties.len();
 
-    let (i, incoming, outgoing) =
-        join_computation(args.address.clone(), &format!("{}-offline", args.room))
-            .await
-            .context("join offline computation")?;
+    // Index of the party in signing is its position in the list of parties
+    let i = args
+        .parties
+        .iter()
+        .position(|&p| usize::from(p) == local_share.own_party_index)
+        .context("local share doesn't belong to any of signing parties")?;
+    let i = u16::try_from(i + 1).context("too many parties")?;
+
+    let (i, incoming, outgoing) = join_computation(
+        &args.address,
+        &format!("{}-offline", args.room),
+        PartyIndex::Explicit(i),
+        ClientConfig::default(),
+    )
+    .await
+    .context("join offline computation")?;
 
     let incoming = incoming.fuse();
     tokio::pin!(incoming);
     tokio::pin!(outgoing);
 
-    let signing = OfflineStage::new(i, args.parties, local_share)?;
+    let session_id = SessionId::new(format!("signing/{}", args.room).as_bytes());
+    let signing = OfflineStage::new(i, args.parties, local_share, session_id)?;
     let completed_offline_stage = AsyncProtocol::new(signing, incoming, outgoing)
         .run()
         .await
         .map_err(|e| anyhow!("protocol execution terminated with error: {}", e))?;
 
-    let (_i, incoming, outgoing) = join_computation(args.address, &format!("{}-online", args.room))
-        .await
-        .context("join online computation")?;
+    let (_i, incoming, outgoing) = join_computation(
+        &args.address,
+        &format!("{}-online", args.room),
+        PartyIndex::Explicit(i),
+        ClientConfig::default(),
+    )
+    .await
+    .context("join online computation")?;
 
     tokio::pin!(incoming);
     tokio::pin!(outgoing);
 
     let (signing, partial_signature) = SignManual::new(
-        BigInt::from_bytes(args.data_to_sign.as_bytes()),
+        MessageDigest::sha256(args.data_to_sign.as_bytes()),
         completed_offline_stage,
     )?;
 
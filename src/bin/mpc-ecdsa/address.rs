@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use curv::elliptic::curves::{Point, Secp256k1};
+
+use multi_party_ecdsa::integrations::{bitcoin, ethereum};
+
+/// Format of exported public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PubkeyFormat {
+    /// Hex of compressed SEC1 encoding
+    Sec1,
+    /// Hex of uncompressed SEC1 encoding
+    Sec1Uncompressed,
+    /// Bitcoin native segwit (P2WPKH) mainnet address
+    BitcoinP2wpkh,
+    /// Bitcoin legacy (P2PKH) mainnet address
+    BitcoinP2pkh,
+    /// EIP-55 checksummed Ethereum address
+    Ethereum,
+}
+
+impl PubkeyFormat {
+    pub const VARIANTS: &'static [&'static str] = &[
+        "sec1",
+        "sec1-uncompressed",
+        "bitcoin-p2wpkh",
+        "bitcoin-p2pkh",
+        "ethereum",
+    ];
+
+    pub fn encode(self, public_key: &Point<Secp256k1>) -> String {
+        match self {
+            PubkeyFormat::Sec1 => hex::encode(public_key.to_bytes(true)),
+            PubkeyFormat::Sec1Uncompressed => hex::encode(public_key.to_bytes(false)),
+            PubkeyFormat::BitcoinP2wpkh => bitcoin_p2wpkh_address(public_key),
+            PubkeyFormat::BitcoinP2pkh => bitcoin_p2pkh_address(public_key),
+            PubkeyFormat::Ethereum => ethereum_address(public_key),
+        }
+    }
+}
+
+impl FromStr for PubkeyFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "sec1" => PubkeyFormat::Sec1,
+            "sec1-uncompressed" => PubkeyFormat::Sec1Uncompressed,
+            "bitcoin-p2wpkh" => PubkeyFormat::BitcoinP2wpkh,
+            "bitcoin-p2pkh" => PubkeyFormat::BitcoinP2pkh,
+            "ethereum" => PubkeyFormat::Ethereum,
+            _ => bail!("unknown public key format {:?}", s),
+        })
+    }
+}
+
+pub fn bitcoin_p2wpkh_address(public_key: &Point<Secp256k1>) -> String {
+    bitcoin::p2wpkh_address(public_key, bitcoin::Network::Mainnet)
+}
+
+pub fn bitcoin_p2pkh_address(public_key: &Point<Secp256k1>) -> String {
+    bitcoin::p2pkh_address(public_key, bitcoin::Network::Mainnet)
+}
+
+pub fn ethereum_address(public_key: &Point<Secp256k1>) -> String {
+    ethereum::to_checksum_address(&ethereum::public_key_to_address(public_key))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,33 @@
+use curv::elliptic::curves::{Point, Secp256k1};
+
+use super::PubkeyFormat;
+
+#[test]
+fn addresses_of_generator_match_known_values() {
+    // public key of secret key 1
+    let public_key = Point::<Secp256k1>::generator().to_point();
+    assert_eq!(
+        PubkeyFormat::Sec1.encode(&public_key),
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+    );
+    assert_eq!(
+        PubkeyFormat::BitcoinP2wpkh.encode(&public_key),
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    );
+    assert_eq!(
+        PubkeyFormat::BitcoinP2pkh.encode(&public_key),
+        "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+    );
+    assert_eq!(
+        PubkeyFormat::Ethereum.encode(&public_key),
+        "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+    );
+}
+
+#[test]
+fn every_format_can_be_parsed() {
+    for format in PubkeyFormat::VARIANTS {
+        assert!(format.parse::<PubkeyFormat>().is_ok());
+    }
+    assert!("base64".parse::<PubkeyFormat>().is_err());
+}
@@ -0,0 +1,448 @@
+//! Command-line tool for threshold ECDSA (GG20)
+//!
+//! Every party runs `mpc-ecdsa` on its own machine. Parties exchange protocol messages either
+//! via relay server (`--relay`, see `gg20_sm_manager` example), or directly over TCP secured by
+//! Noise (`--peers` and `--noise-key`).
+//!
+//! If `MPC_ECDSA_PASSPHRASE` environment variable is set, key shares and presignatures are
+//! sealed under it (see [storage](multi_party_ecdsa::storage)), otherwise they're stored in
+//! plaintext. Plaintext presignature is refused while the passphrase is set.
+
+mod address;
+mod transport;
+
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context, Result};
+use curv::elliptic::curves::{Point, Secp256k1};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use structopt::StructOpt;
+use zeroize::Zeroizing;
+
+use multi_party_ecdsa::protocols::gg_2020::party_i::{verify, SignatureRecid};
+use multi_party_ecdsa::protocols::gg_2020::state_machine::keygen::{local_key::LocalKey, Keygen};
+use multi_party_ecdsa::protocols::gg_2020::state_machine::sign::{
+    stages::{offline_stage::OfflineStage, sign_manual::SignManual},
+    CompletedOfflineStage,
+};
+use multi_party_ecdsa::storage::{SealedLocalKey, SealedPresignature};
+use multi_party_ecdsa::transport::tcp::NoiseKeypair;
+use multi_party_ecdsa::utilities::message_digest::MessageDigest;
+use multi_party_ecdsa::utilities::session_id::SessionId;
+
+use address::PubkeyFormat;
+use transport::TransportOpts;
+
+const PASSPHRASE_ENV: &str = "MPC_ECDSA_PASSPHRASE";
+
+#[derive(StructOpt, Debug)]
+#[structopt(name = "mpc-ecdsa", about = "Threshold ECDSA command-line tool")]
+enum Cli {
+    /// Generates a key share jointly with other parties
+    Keygen {
+        #[structopt(flatten)]
+        transport: TransportOpts,
+        /// Unique identifier of this keygen, shared by all the parties
+        #[structopt(long)]
+        session: String,
+        /// Index of this party in range [1; n]
+        #[structopt(short, long)]
+        index: u16,
+        #[structopt(short, long)]
+        threshold: u16,
+        #[structopt(short, long)]
+        number_of_parties: u16,
+        /// File to write the key share to
+        #[structopt(short, long)]
+        output: PathBuf,
+    },
+    /// Runs offline stage of signing, producing a presignature for signing exactly one message
+    Presign {
+        #[structopt(flatten)]
+        transport: TransportOpts,
+        /// Unique identifier of this signing, shared by all the signers
+        #[structopt(long)]
+        session: String,
+        #[structopt(short, long)]
+        key: PathBuf,
+        /// Keygen indexes of signers
+        #[structopt(short, long, use_delimiter = true)]
+        parties: Vec<u16>,
+        /// File to write the presignature to
+        #[structopt(short, long)]
+        output: PathBuf,
+    },
+    /// Signs a message, running offline stage first unless presignature is given
+    Sign {
+        #[structopt(flatten)]
+        transport: TransportOpts,
+        /// Unique identifier of this signing, shared by all the signers
+        #[structopt(long, required_unless = "presignature")]
+        session: Option<String>,
+        #[structopt(short, long, required_unless = "presignature")]
+        key: Option<PathBuf>,
+        /// Keygen indexes of signers
+        #[structopt(short, long, use_delimiter = true, required_unless = "presignature")]
+        parties: Vec<u16>,
+        /// Presignature produced by `presign`. It's deleted once loaded, so it can't be reused.
+        #[structopt(long, conflicts_with_all = &["key", "session"])]
+        presignature: Option<PathBuf>,
+        #[structopt(flatten)]
+        message: MessageOpts,
+        /// File to write the signature to, printed to stdout if not set
+        #[structopt(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Verifies a signature
+    Verify {
+        /// Hex of SEC1-encoded public key
+        #[structopt(long)]
+        public_key: String,
+        /// File with signature produced by `sign`
+        #[structopt(long)]
+        signature: PathBuf,
+        #[structopt(flatten)]
+        message: MessageOpts,
+    },
+    /// Prints public information about a key share: public key, indexes and parameters
+    InspectKey {
+        #[structopt(short, long)]
+        key: PathBuf,
+    },
+    /// Prints the public key in given format
+    ExportPubkey {
+        #[structopt(short, long)]
+        key: PathBuf,
+        #[structopt(short, long, default_value = "sec1", possible_values = PubkeyFormat::VARIANTS)]
+        format: PubkeyFormat,
+    },
+    /// Generates a static key for TCP transport, prints its public part
+    GenerateNoiseKey {
+        #[structopt(short, long)]
+        output: PathBuf,
+    },
+}
+
+/// Message to be signed or verified
+#[derive(StructOpt, Debug)]
+struct MessageOpts {
+    /// Message, it's hashed with SHA-256
+    #[structopt(short, long, required_unless = "digest", conflicts_with = "digest")]
+    data: Option<String>,
+    /// Hex of 32 bytes digest of the message
+    #[structopt(long)]
+    digest: Option<String>,
+}
+
+impl MessageOpts {
+    fn to_digest(&self) -> Result<MessageDigest> {
+        match (&self.data, &self.digest) {
+            (Some(data), None) => Ok(MessageDigest::sha256(data.as_bytes())),
+            (None, Some(digest)) => {
+                let digest = hex::decode(digest).context("digest is not valid hex")?;
+                Ok(MessageDigest::from_slice(&digest)?)
+            }
+            _ => bail!("exactly one of --data and --digest must be given"),
+        }
+    }
+}
+
+/// Output of `presign`, consumed by online stage of `sign`
+struct Presignature {
+    session_id: SessionId,
+    /// Index of this party in signing
+    index: u16,
+    /// Keygen indexes of signers
+    parties: Vec<u16>,
+    offline_stage: CompletedOfflineStage,
+}
+
+/// Presignature file
+#[derive(Serialize, Deserialize)]
+struct PresignatureFile {
+    session_id: SessionId,
+    index: u16,
+    parties: Vec<u16>,
+    offline_stage: StoredOfflineStage,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredOfflineStage {
+    Sealed(SealedPresignature),
+    Plaintext(CompletedOfflineStage),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct KeyInfo {
+    public_key: String,
+    own_party_index: u16,
+    threshold: u16,
+    share_count: u16,
+    sealed: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    match Cli::from_args() {
+        Cli::Keygen {
+            transport,
+            session,
+            index,
+            threshold,
+            number_of_parties,
+            output,
+        } => {
+            ensure!(!output.exists(), "{} already exists", output.display());
+            let session_id = SessionId::new(format!("keygen/{}", session).as_bytes());
+            let mut keygen = Keygen::new(index, threshold, number_of_parties, session_id)?;
+            if let Some(timeout) = transport.round_timeout() {
+                keygen = keygen.with_round_timeout(timeout);
+            }
+            let parties: Vec<u16> = (1..=number_of_parties).collect();
+            let local_key = transport
+                .run(session_id, "keygen", index, &parties, keygen)
+                .await?;
+            write_key(&output, &local_key)?;
+            println!("{}", PubkeyFormat::Sec1.encode(&local_key.public_key));
+        }
+        Cli::Presign {
+            transport,
+            session,
+            key,
+            parties,
+            output,
+        } => {
+            ensure!(!output.exists(), "{} already exists", output.display());
+            let presignature = presign(&transport, &session, &key, parties).await?;
+            write_presignature(&output, &presignature)?;
+        }
+        Cli::Sign {
+            transport,
+            session,
+            key,
+            parties,
+            presignature,
+            message,
+            output,
+        } => {
+            let message = message.to_digest()?;
+            let presignature = match (presignature, session, key) {
+                (Some(path), _, _) => read_presignature(&path)?,
+                (None, Some(session), Some(key)) => {
+                    presign(&transport, &session, &key, parties).await?
+                }
+                _ => bail!("either --presignature, or --session and --key must be given"),
+            };
+
+            let (signing, partial_signature) =
+                SignManual::new(message, presignature.offline_stage)?;
+            let partial_signatures = transport
+                .exchange(
+                    presignature.session_id,
+                    "online",
+                    presignature.index,
+                    &presignature.parties,
+                    partial_signature,
+                )
+                .await?;
+            let signature = signing
+                .complete(&partial_signatures)
+                .context("online stage failed")?;
+            let signature = serde_json::to_string_pretty(&signature)?;
+            match output {
+                Some(output) => write_new_file(&output, signature.as_bytes())?,
+                None => println!("{}", signature),
+            }
+        }
+        Cli::Verify {
+            public_key,
+            signature,
+            message,
+        } => {
+            let public_key = hex::decode(public_key).context("public key is not valid hex")?;
+            let public_key =
+                Point::<Secp256k1>::from_bytes(&public_key).context("public key is invalid")?;
+            let signature: SignatureRecid =
+                serde_json::from_slice(&fs::read(signature).context("read signature")?)
+                    .context("parse signature")?;
+            verify(&signature, &public_key, &message.to_digest()?)
+                .map_err(|_| anyhow::anyhow!("signature is invalid"))?;
+            println!("signature is valid");
+        }
+        Cli::InspectKey { key } => {
+            let bytes = Zeroizing::new(fs::read(&key).context("read key")?);
+            let info = match SealedLocalKey::from_bytes(&bytes) {
+                Ok(sealed) => KeyInfo {
+                    public_key: sealed.metadata.public_key,
+                    own_party_index: sealed.metadata.own_party_index,
+                    threshold: sealed.metadata.threshold,
+                    share_count: sealed.metadata.share_count,
+                    sealed: true,
+                },
+                Err(_) => {
+                    let local_key = LocalKey::<Secp256k1>::from_versioned_bytes(&bytes)
+                        .context("parse key")?;
+                    KeyInfo {
+                        public_key: PubkeyFormat::Sec1.encode(&local_key.public_key),
+                        own_party_index: u16::try_from(local_key.own_party_index)
+                            .context("party index doesn't fit into u16")?,
+                        threshold: local_key.key_params.threshold,
+                        share_count: local_key.key_params.share_count,
+                        sealed: false,
+                    }
+                }
+            };
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        }
+        Cli::ExportPubkey { key, format } => {
+            let public_key = read_public_key(&key)?;
+            println!("{}", format.encode(&public_key));
+        }
+        Cli::GenerateNoiseKey { output } => {
+            let mut secret = Zeroizing::new([0u8; 32]);
+            OsRng.fill_bytes(&mut secret[..]);
+            let keypair = NoiseKeypair::from_secret(*secret);
+            write_new_file(&output, hex::encode(&secret[..]).as_bytes())?;
+            println!("{}", hex::encode(keypair.public_key()));
+        }
+    }
+    Ok(())
+}
+
+/// Runs offline stage among `parties`, returns the presignature
+async fn presign(
+    transport: &TransportOpts,
+    session: &str,
+    key: &Path,
+    parties: Vec<u16>,
+) -> Result<Presignature> {
+    let local_key = read_key(key)?;
+    let index = parties
+        .iter()
+        .position(|&p| usize::from(p) == local_key.own_party_index)
+        .context("key share doesn't belong to any of the signers")?;
+    let index = u16::try_from(index + 1).context("too many signers")?;
+
+    let session_id = SessionId::new(format!("signing/{}", session).as_bytes());
+    let mut offline_stage = OfflineStage::new(index, parties.clone(), local_key, session_id)?;
+    if let Some(timeout) = transport.round_timeout() {
+        offline_stage = offline_stage.with_round_timeout(timeout);
+    }
+    let offline_stage = transport
+        .run(session_id, "offline", index, &parties, offline_stage)
+        .await?;
+    Ok(Presignature {
+        session_id,
+        index,
+        parties,
+        offline_stage,
+    })
+}
+
+fn write_presignature(path: &Path, presignature: &Presignature) -> Result<()> {
+    let offline_stage = match passphrase() {
+        Some(passphrase) => StoredOfflineStage::Sealed(
+            presignature
+                .offline_stage
+                .seal(passphrase.as_bytes())
+                .context("seal presignature")?,
+        ),
+        None => StoredOfflineStage::Plaintext(presignature.offline_stage.clone()),
+    };
+    let file = PresignatureFile {
+        session_id: presignature.session_id,
+        index: presignature.index,
+        parties: presignature.parties.clone(),
+        offline_stage,
+    };
+    write_new_file(path, &Zeroizing::new(serde_json::to_vec(&file)?))
+}
+
+/// Reads presignature and deletes the file, so the presignature can't be used twice
+fn read_presignature(path: &Path) -> Result<Presignature> {
+    let bytes = Zeroizing::new(fs::read(path).context("read presignature")?);
+    let file: PresignatureFile = serde_json::from_slice(&bytes).context("parse presignature")?;
+    let offline_stage = match (file.offline_stage, passphrase()) {
+        (StoredOfflineStage::Sealed(sealed), Some(passphrase)) => sealed
+            .open(passphrase.as_bytes())
+            .context("open presignature")?,
+        (StoredOfflineStage::Sealed(_), None) => {
+            bail!("presignature is sealed, set {} to open it", PASSPHRASE_ENV)
+        }
+        (StoredOfflineStage::Plaintext(_), Some(_)) => bail!(
+            "presignature is stored in plaintext while {} is set",
+            PASSPHRASE_ENV
+        ),
+        (StoredOfflineStage::Plaintext(offline_stage), None) => offline_stage,
+    };
+    fs::remove_file(path).context("remove presignature")?;
+    Ok(Presignature {
+        session_id: file.session_id,
+        index: file.index,
+        parties: file.parties,
+        offline_stage,
+    })
+}
+
+fn passphrase() -> Option<Zeroizing<String>> {
+    std::env::var(PASSPHRASE_ENV).ok().map(Zeroizing::new)
+}
+
+fn read_key(path: &Path) -> Result<LocalKey<Secp256k1>> {
+    let bytes = Zeroizing::new(fs::read(path).context("read key")?);
+    let local_key = match SealedLocalKey::from_bytes(&bytes) {
+        Ok(sealed) => {
+            let passphrase = passphrase()
+                .with_context(|| format!("key is sealed, set {} to open it", PASSPHRASE_ENV))?;
+            sealed.open(passphrase.as_bytes()).context("open key")?
+        }
+        Err(_) => LocalKey::from_versioned_bytes(&bytes).context("parse key")?,
+    };
+    local_key
+        .validate()
+        .into_result()
+        .context("key share is corrupted")?;
+    Ok(local_key)
+}
+
+fn read_public_key(path: &Path) -> Result<Point<Secp256k1>> {
+    let bytes = Zeroizing::new(fs::read(path).context("read key")?);
+    match SealedLocalKey::from_bytes(&bytes) {
+        Ok(sealed) => Ok(sealed.public_key()?),
+        Err(_) => Ok(LocalKey::<Secp256k1>::from_versioned_bytes(&bytes)
+            .context("parse key")?
+            .public_key
+            .clone()),
+    }
+}
+
+fn write_key(path: &Path, local_key: &LocalKey<Secp256k1>) -> Result<()> {
+    let bytes = match passphrase() {
+        Some(passphrase) => local_key.seal(passphrase.as_bytes())?.to_bytes()?,
+        None => local_key.to_versioned_bytes()?,
+    };
+    write_new_file(path, &Zeroizing::new(bytes))
+}
+
+/// Writes a file readable only by the owner, fails if it already exists
+fn write_new_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("create {}", path.display()))?;
+    file.write_all(bytes)
+        .with_context(|| format!("write {}", path.display()))?;
+    Ok(())
+}
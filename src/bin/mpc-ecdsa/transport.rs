@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use futures::{Sink, SinkExt, Stream, StreamExt, TryStreamExt};
+use round_based::async_runtime::AsyncProtocol;
+use round_based::{IsCritical, Msg, StateMachine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use structopt::StructOpt;
+
+use multi_party_ecdsa::transport::sse::{self, ClientConfig, PartyIndex, Url};
+use multi_party_ecdsa::transport::tcp::{self, NoiseKeypair, Peer, TcpConfig};
+use multi_party_ecdsa::utilities::session_id::SessionId;
+
+/// How parties exchange protocol messages: either via relay server, or directly over TCP
+#[derive(StructOpt, Debug)]
+pub struct TransportOpts {
+    /// Address of relay server
+    #[structopt(long)]
+    relay: Option<Url>,
+    /// Relay room prefix, session id and name of protocol stage are appended to it
+    #[structopt(long, default_value = "default")]
+    room: String,
+    /// Bearer token of relay rooms
+    #[structopt(long)]
+    token: Option<String>,
+    /// JSON file mapping keygen index of every party to its address and static Noise public
+    /// key. Enables direct TCP transport.
+    #[structopt(long, conflicts_with = "relay", required_unless = "relay")]
+    peers: Option<PathBuf>,
+    /// File with static Noise secret key of this party, see `generate-noise-key`
+    #[structopt(long, required_unless = "relay")]
+    noise_key: Option<PathBuf>,
+    /// Abort if a round isn't completed in given number of seconds
+    #[structopt(long)]
+    round_timeout: Option<u64>,
+}
+
+/// Entry of peers file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PeerEntry {
+    pub address: SocketAddr,
+    /// Hex of static Noise public key
+    pub public_key: String,
+}
+
+impl PeerEntry {
+    fn to_peer(&self) -> Result<Peer> {
+        let key = hex::decode(&self.public_key).context("public key is not valid hex")?;
+        ensure!(key.len() == 32, "public key must be 32 bytes long");
+        let mut static_public_key = [0u8; 32];
+        static_public_key.copy_from_slice(&key);
+        Ok(Peer {
+            address: self.address,
+            static_public_key,
+        })
+    }
+}
+
+type Incoming<'a, M> = Pin<Box<dyn Stream<Item = Result<Msg<M>>> + 'a>>;
+type Outgoing<'a, M> = Pin<Box<dyn Sink<Msg<M>, Error = anyhow::Error> + 'a>>;
+
+/// Transport resolved for a particular stage of the protocol
+enum Resolved {
+    Relay {
+        address: Url,
+        room: String,
+        config: ClientConfig,
+    },
+    Tcp {
+        keypair: NoiseKeypair,
+        peers: BTreeMap<u16, Peer>,
+    },
+}
+
+impl TransportOpts {
+    pub fn round_timeout(&self) -> Option<Duration> {
+        self.round_timeout.map(Duration::from_secs)
+    }
+
+    /// Runs state machine of party `i` in the protocol `stage` of session `session_id` among
+    /// `parties`
+    ///
+    /// `parties[j - 1]` is keygen index of party `j` of the protocol.
+    pub async fn run<SM>(
+        &self,
+        session_id: SessionId,
+        stage: &str,
+        i: u16,
+        parties: &[u16],
+        party: SM,
+    ) -> Result<SM::Output>
+    where
+        SM: StateMachine + fmt::Debug + Send + 'static,
+        SM::MessageBody: Serialize + DeserializeOwned + Send + 'static,
+        SM::Err: IsCritical + fmt::Display + fmt::Debug + Send,
+        SM::Output: Send,
+    {
+        let resolved = self.resolve(session_id, stage, parties)?;
+        let (incoming, outgoing) = resolved.connect(i).await?;
+        let incoming = incoming.fuse();
+        AsyncProtocol::new(party, incoming, outgoing)
+            .run()
+            .await
+            .map_err(|e| anyhow!("{} failed: {}", stage, e))
+    }
+
+    /// Sends `msg` to every other party in the `stage` of session `session_id` among `parties`,
+    /// and receives their messages
+    pub async fn exchange<M>(
+        &self,
+        session_id: SessionId,
+        stage: &str,
+        i: u16,
+        parties: &[u16],
+        msg: M,
+    ) -> Result<Vec<M>>
+    where
+        M: Serialize + DeserializeOwned + Send + 'static,
+    {
+        let resolved = self.resolve(session_id, stage, parties)?;
+        let (incoming, mut outgoing) = resolved.connect(i).await?;
+        outgoing
+            .send(Msg {
+                sender: i,
+                receiver: None,
+                body: msg,
+            })
+            .await?;
+        let received: Vec<Msg<M>> = incoming.take(parties.len() - 1).try_collect().await?;
+        ensure!(
+            received.len() == parties.len() - 1,
+            "connection closed before all parties sent their messages"
+        );
+        Ok(received.into_iter().map(|msg| msg.body).collect())
+    }
+
+    fn resolve(&self, session_id: SessionId, stage: &str, parties: &[u16]) -> Result<Resolved> {
+        if let Some(address) = &self.relay {
+            return Ok(Resolved::Relay {
+                address: address.clone(),
+                room: format!(
+                    "{}-{}-{}",
+                    self.room,
+                    hex::encode(session_id.as_bytes()),
+                    stage
+                ),
+                config: ClientConfig {
+                    token: self.token.clone(),
+                    ..Default::default()
+                },
+            });
+        }
+
+        let peers_file = self
+            .peers
+            .as_ref()
+            .context("either --relay or --peers must be given")?;
+        let all_peers: BTreeMap<u16, PeerEntry> = serde_json::from_slice(
+            &std::fs::read(peers_file).context("read peers file")?,
+        )
+        .context("parse peers file")?;
+        let keypair = read_noise_key(
+            self.noise_key
+                .as_ref()
+                .context("--noise-key is required for TCP transport")?,
+        )?;
+
+        let mut peers = BTreeMap::new();
+        for (j, keygen_i) in (1..).zip(parties) {
+            let entry = all_peers
+                .get(keygen_i)
+                .with_context(|| format!("party {} is missing in peers file", keygen_i))?;
+            peers.insert(j, entry.to_peer()?);
+        }
+        Ok(Resolved::Tcp { keypair, peers })
+    }
+}
+
+impl Resolved {
+    async fn connect<M>(&self, i: u16) -> Result<(Incoming<'_, M>, Outgoing<'_, M>)>
+    where
+        M: Serialize + DeserializeOwned + Send + 'static,
+    {
+        match self {
+            Resolved::Relay {
+                address,
+                room,
+                config,
+            } => {
+                let (_, incoming, outgoing) = sse::join_computation(
+                    address,
+                    room,
+                    PartyIndex::Explicit(i),
+                    config.clone(),
+                )
+                .await
+                .context("join relay room")?;
+                Ok((
+                    Box::pin(incoming.map_err(anyhow::Error::from)),
+                    Box::pin(outgoing.sink_map_err(anyhow::Error::from)),
+                ))
+            }
+            Resolved::Tcp { keypair, peers } => {
+                let own = peers.get(&i).context("own index is missing in peers file")?;
+                ensure!(
+                    &own.static_public_key == keypair.public_key(),
+                    "noise key doesn't match the one listed in peers file"
+                );
+                let listener = tokio::net::TcpListener::bind(own.address)
+                    .await
+                    .with_context(|| format!("listen on {}", own.address))?;
+                let (incoming, outgoing) =
+                    tcp::connect(i, keypair, listener, peers, TcpConfig::default())
+                        .await
+                        .context("connect to peers")?;
+                Ok((
+                    Box::pin(incoming.map_err(anyhow::Error::from)),
+                    Box::pin(outgoing.sink_map_err(anyhow::Error::from)),
+                ))
+            }
+        }
+    }
+}
+
+fn read_noise_key(path: &Path) -> Result<NoiseKeypair> {
+    let secret = std::fs::read_to_string(path).context("read noise key")?;
+    let secret = hex::decode(secret.trim()).context("noise key is not valid hex")?;
+    ensure!(secret.len() == 32, "noise key must be 32 bytes long");
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&secret);
+    Ok(NoiseKeypair::from_secret(bytes))
+}
@@ -0,0 +1,5 @@
+//! Helpers for signing blockchain transactions with a threshold key
+
+#[cfg(feature = "bitcoin")]
+pub mod bitcoin;
+pub mod ethereum;
@@ -0,0 +1,377 @@
+//! Signing Bitcoin SegWit transactions with a threshold key
+//!
+//! Threshold key is used as a P2WPKH key: coins are received on [p2wpkh_address] and spent by
+//! [TransactionSigning], which signs every input of the transaction with BIP143 sighash. Each
+//! input takes its own completed offline stage, so spending `k` inputs needs `k` offline stages
+//! completed in advance.
+
+use std::convert::TryFrom;
+
+use bech32::{ToBase32, Variant};
+use curv::elliptic::curves::{Point, Secp256k1};
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::protocols::gg_2020::party_i::SignatureRecid;
+use crate::protocols::gg_2020::state_machine::sign::error::sign_local_complete_error::SignLocalCompleteError;
+use crate::protocols::gg_2020::state_machine::sign::stages::sign_manual::SignManual;
+use crate::protocols::gg_2020::state_machine::sign::{CompletedOfflineStage, PartialSignature};
+use crate::utilities::message_digest::MessageDigest;
+
+/// The only supported sighash type: signature commits to all inputs and outputs
+pub const SIGHASH_ALL: u32 = 0x01;
+
+/// Witness stack of a single input
+pub type Witness = Vec<Vec<u8>>;
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BitcoinError {
+    #[error("transaction has {inputs} inputs, but {actual} {what} given")]
+    InputsMismatch {
+        inputs: usize,
+        actual: usize,
+        what: &'static str,
+    },
+    #[error("transaction has no input #{0}")]
+    InputOutOfRange(usize),
+    #[error("input #{0} doesn't spend P2WPKH output of the key")]
+    NotOwnedInput(usize),
+    #[error("signing input #{input}: {source}")]
+    Signing {
+        input: usize,
+        source: SignLocalCompleteError,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    fn bech32_hrp(self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet => "tb",
+        }
+    }
+
+    fn p2pkh_prefix(self) -> u8 {
+        match self {
+            Network::Mainnet => 0x00,
+            Network::Testnet => 0x6f,
+        }
+    }
+}
+
+/// RIPEMD-160 of SHA-256 of compressed public key
+pub fn hash160(public_key: &Point<Secp256k1>) -> [u8; 20] {
+    let sha = Sha256::digest(&public_key.to_bytes(true));
+    Ripemd160::digest(&sha).into()
+}
+
+/// Legacy (base58check) address of the public key
+pub fn p2pkh_address(public_key: &Point<Secp256k1>, network: Network) -> String {
+    let mut data = vec![network.p2pkh_prefix()];
+    data.extend_from_slice(&hash160(public_key));
+    bs58::encode(data).with_check().into_string()
+}
+
+/// Native SegWit v0 (bech32) address of the public key
+pub fn p2wpkh_address(public_key: &Point<Secp256k1>, network: Network) -> String {
+    let mut data = vec![bech32::u5::try_from_u8(0).expect("0 is valid u5")];
+    data.extend(hash160(public_key).to_base32());
+    bech32::encode(network.bech32_hrp(), data, Variant::Bech32).expect("hrp is valid")
+}
+
+/// `OP_0 <hash160>`, script of an output paying to [p2wpkh_address]
+pub fn p2wpkh_script_pubkey(public_key: &Point<Secp256k1>) -> Vec<u8> {
+    let mut script = vec![0x00, 0x14];
+    script.extend_from_slice(&hash160(public_key));
+    script
+}
+
+/// Witness spending P2WPKH output: DER signature with sighash flag, and compressed public key
+pub fn p2wpkh_witness(signature: &SignatureRecid, public_key: &Point<Secp256k1>) -> Witness {
+    let mut sig = signature.to_der();
+    sig.push(SIGHASH_ALL as u8);
+    vec![sig, public_key.to_bytes(true).to_vec()]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutPoint {
+    /// Id of transaction in internal byte order, i.e. reversed hex shown by block explorers
+    pub txid: [u8; 32],
+    pub vout: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxIn {
+    pub previous_output: OutPoint,
+    /// Empty for SegWit inputs
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxOut {
+    /// Amount in satoshis
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transaction {
+    pub version: i32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub lock_time: u32,
+}
+
+impl Transaction {
+    /// Serializes transaction without witnesses
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut out = vec![];
+        out.extend_from_slice(&self.version.to_le_bytes());
+        self.encode_inputs_and_outputs(&mut out);
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        out
+    }
+
+    /// Serializes transaction with witness of every input (BIP144)
+    ///
+    /// Inputs that aren't spent by witness have empty witness stack.
+    pub fn serialize_with_witnesses(
+        &self,
+        witnesses: &[Witness],
+    ) -> Result<Vec<u8>, BitcoinError> {
+        if witnesses.len() != self.inputs.len() {
+            return Err(BitcoinError::InputsMismatch {
+                inputs: self.inputs.len(),
+                actual: witnesses.len(),
+                what: "witnesses",
+            });
+        }
+        if witnesses.iter().all(|witness| witness.is_empty()) {
+            return Ok(self.serialize());
+        }
+
+        let mut out = vec![];
+        out.extend_from_slice(&self.version.to_le_bytes());
+        // marker and flag
+        out.extend_from_slice(&[0x00, 0x01]);
+        self.encode_inputs_and_outputs(&mut out);
+        for witness in witnesses {
+            encode_compact_size(witness.len(), &mut out);
+            for item in witness {
+                encode_bytes(item, &mut out);
+            }
+        }
+        out.extend_from_slice(&self.lock_time.to_le_bytes());
+        Ok(out)
+    }
+
+    /// BIP143 `SIGHASH_ALL` digest of input spending P2WPKH `prevout`
+    pub fn p2wpkh_sighash(
+        &self,
+        input_index: usize,
+        prevout: &TxOut,
+    ) -> Result<MessageDigest, BitcoinError> {
+        let input = self
+            .inputs
+            .get(input_index)
+            .ok_or(BitcoinError::InputOutOfRange(input_index))?;
+        let pubkey_hash = match prevout.script_pubkey.as_slice() {
+            [0x00, 0x14, hash @ ..] if hash.len() == 20 => hash,
+            _ => return Err(BitcoinError::NotOwnedInput(input_index)),
+        };
+
+        let mut prevouts = vec![];
+        let mut sequences = vec![];
+        for input in &self.inputs {
+            encode_outpoint(&input.previous_output, &mut prevouts);
+            sequences.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        let mut outputs = vec![];
+        for output in &self.outputs {
+            encode_output(output, &mut outputs);
+        }
+
+        let mut preimage = vec![];
+        preimage.extend_from_slice(&self.version.to_le_bytes());
+        preimage.extend_from_slice(&double_sha256(&prevouts));
+        preimage.extend_from_slice(&double_sha256(&sequences));
+        encode_outpoint(&input.previous_output, &mut preimage);
+        // scriptCode: OP_DUP OP_HASH160 <hash160> OP_EQUALVERIFY OP_CHECKSIG
+        preimage.extend_from_slice(&[0x19, 0x76, 0xa9, 0x14]);
+        preimage.extend_from_slice(pubkey_hash);
+        preimage.extend_from_slice(&[0x88, 0xac]);
+        preimage.extend_from_slice(&prevout.value.to_le_bytes());
+        preimage.extend_from_slice(&input.sequence.to_le_bytes());
+        preimage.extend_from_slice(&double_sha256(&outputs));
+        preimage.extend_from_slice(&self.lock_time.to_le_bytes());
+        preimage.extend_from_slice(&SIGHASH_ALL.to_le_bytes());
+        Ok(MessageDigest::double_sha256(&preimage))
+    }
+
+    fn encode_inputs_and_outputs(&self, out: &mut Vec<u8>) {
+        encode_compact_size(self.inputs.len(), out);
+        for input in &self.inputs {
+            encode_outpoint(&input.previous_output, out);
+            encode_bytes(&input.script_sig, out);
+            out.extend_from_slice(&input.sequence.to_le_bytes());
+        }
+        encode_compact_size(self.outputs.len(), out);
+        for output in &self.outputs {
+            encode_output(output, out);
+        }
+    }
+}
+
+fn encode_outpoint(outpoint: &OutPoint, out: &mut Vec<u8>) {
+    out.extend_from_slice(&outpoint.txid);
+    out.extend_from_slice(&outpoint.vout.to_le_bytes());
+}
+
+fn encode_output(output: &TxOut, out: &mut Vec<u8>) {
+    out.extend_from_slice(&output.value.to_le_bytes());
+    encode_bytes(&output.script_pubkey, out);
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    encode_compact_size(bytes.len(), out);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_compact_size(n: usize, out: &mut Vec<u8>) {
+    match n {
+        0..=0xfc => out.push(n as u8),
+        0xfd..=0xffff => {
+            out.push(0xfd);
+            out.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        _ => match u32::try_from(n) {
+            Ok(n) => {
+                out.push(0xfe);
+                out.extend_from_slice(&n.to_le_bytes());
+            }
+            Err(_) => {
+                out.push(0xff);
+                out.extend_from_slice(&(n as u64).to_le_bytes());
+            }
+        },
+    }
+}
+
+fn double_sha256(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(&Sha256::digest(bytes)).into()
+}
+
+/// Online stage of signing every input of a transaction
+pub struct TransactionSigning {
+    transaction: Transaction,
+    public_key: Point<Secp256k1>,
+    inputs: Vec<SignManual>,
+}
+
+impl TransactionSigning {
+    /// Computes partial signatures of every input that need to be sent to other signers
+    ///
+    /// `prevouts[i]` is the output spent by `i`-th input, it must be P2WPKH output of the
+    /// threshold key. `offline_stages[i]` is used to sign `i`-th input.
+    pub fn new(
+        transaction: Transaction,
+        prevouts: &[TxOut],
+        offline_stages: Vec<CompletedOfflineStage>,
+    ) -> Result<(Self, Vec<PartialSignature>), BitcoinError> {
+        let inputs = transaction.inputs.len();
+        if prevouts.len() != inputs {
+            return Err(BitcoinError::InputsMismatch {
+                inputs,
+                actual: prevouts.len(),
+                what: "prevouts",
+            });
+        }
+        if offline_stages.len() != inputs {
+            return Err(BitcoinError::InputsMismatch {
+                inputs,
+                actual: offline_stages.len(),
+                what: "offline stages",
+            });
+        }
+        let public_key = match offline_stages.first() {
+            Some(stage) => stage.public_key().clone(),
+            None => return Err(BitcoinError::InputOutOfRange(0)),
+        };
+        let script_pubkey = p2wpkh_script_pubkey(&public_key);
+
+        let mut signing = Vec::with_capacity(inputs);
+        let mut partial_signatures = Vec::with_capacity(inputs);
+        for (input, (prevout, stage)) in prevouts.iter().zip(offline_stages).enumerate() {
+            if prevout.script_pubkey != script_pubkey || stage.public_key() != &public_key {
+                return Err(BitcoinError::NotOwnedInput(input));
+            }
+            let sighash = transaction.p2wpkh_sighash(input, prevout)?;
+            let (sign, partial_signature) = SignManual::new(sighash, stage)
+                .map_err(|source| BitcoinError::Signing { input, source })?;
+            signing.push(sign);
+            partial_signatures.push(partial_signature);
+        }
+
+        Ok((
+            Self {
+                transaction,
+                public_key,
+                inputs: signing,
+            },
+            partial_signatures,
+        ))
+    }
+
+    /// Assembles signed transaction out of partial signatures of other signers
+    ///
+    /// `partial_signatures` contains partial signatures received from every other signer, one
+    /// per input. It must not include partial signatures of local party.
+    pub fn complete(
+        self,
+        partial_signatures: &[Vec<PartialSignature>],
+    ) -> Result<SignedTransaction, BitcoinError> {
+        let inputs = self.inputs.len();
+        if let Some(sigs) = partial_signatures.iter().find(|sigs| sigs.len() != inputs) {
+            return Err(BitcoinError::InputsMismatch {
+                inputs,
+                actual: sigs.len(),
+                what: "partial signatures",
+            });
+        }
+
+        let mut witnesses = Vec::with_capacity(inputs);
+        for (input, sign) in self.inputs.into_iter().enumerate() {
+            let sigs: Vec<_> = partial_signatures
+                .iter()
+                .map(|sigs| sigs[input].clone())
+                .collect();
+            let signature = sign
+                .complete(&sigs)
+                .map_err(|source| BitcoinError::Signing { input, source })?;
+            witnesses.push(p2wpkh_witness(&signature, &self.public_key));
+        }
+
+        let raw = self.transaction.serialize_with_witnesses(&witnesses)?;
+        Ok(SignedTransaction { witnesses, raw })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedTransaction {
+    /// Witness of every input
+    pub witnesses: Vec<Witness>,
+    /// Serialized signed transaction
+    pub raw: Vec<u8>,
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,237 @@
+use curv::elliptic::curves::{Point, Secp256k1};
+
+use crate::integrations::bitcoin::*;
+use crate::protocols::gg_2020::party_i::{verify, SignatureRecid};
+use crate::protocols::gg_2020::state_machine::keygen::test::simulate_keygen;
+use crate::protocols::gg_2020::state_machine::sign::test::simulate_offline_stage;
+
+fn txid(hex: &str) -> [u8; 32] {
+    let mut txid = [0u8; 32];
+    txid.copy_from_slice(&hex::decode(hex).unwrap());
+    txid
+}
+
+/// Unsigned transaction from "Native P2WPKH" example of BIP143
+fn bip143_transaction() -> Transaction {
+    Transaction {
+        version: 1,
+        inputs: vec![
+            TxIn {
+                previous_output: OutPoint {
+                    txid: txid("fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f"),
+                    vout: 0,
+                },
+                script_sig: vec![],
+                sequence: 0xffffffee,
+            },
+            TxIn {
+                previous_output: OutPoint {
+                    txid: txid("ef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a"),
+                    vout: 1,
+                },
+                script_sig: vec![],
+                sequence: 0xffffffff,
+            },
+        ],
+        outputs: vec![
+            TxOut {
+                value: 112340000,
+                script_pubkey: hex::decode("76a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac")
+                    .unwrap(),
+            },
+            TxOut {
+                value: 223450000,
+                script_pubkey: hex::decode("76a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac")
+                    .unwrap(),
+            },
+        ],
+        lock_time: 17,
+    }
+}
+
+#[test]
+fn addresses_match_known_values() {
+    let g = Point::<Secp256k1>::generator().to_point();
+    assert_eq!(
+        p2wpkh_address(&g, Network::Mainnet),
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    );
+    assert_eq!(
+        p2wpkh_address(&g, Network::Testnet),
+        "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"
+    );
+    assert_eq!(
+        p2pkh_address(&g, Network::Mainnet),
+        "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH"
+    );
+    assert_eq!(
+        p2pkh_address(&g, Network::Testnet),
+        "mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r"
+    );
+}
+
+#[test]
+fn bip143_native_p2wpkh() {
+    let tx = bip143_transaction();
+    assert_eq!(
+        hex::encode(tx.serialize()),
+        "0100000002fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f0000000000eeffff\
+         ffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202c\
+         b206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143b\
+         de42dbee7e4dbe6a21b2d50ce2f0167faa815988ac11000000"
+    );
+
+    let public_key = Point::<Secp256k1>::from_bytes(
+        &hex::decode("025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee6357").unwrap(),
+    )
+    .unwrap();
+    let prevout = TxOut {
+        value: 600000000,
+        script_pubkey: p2wpkh_script_pubkey(&public_key),
+    };
+    assert_eq!(
+        hex::encode(&prevout.script_pubkey),
+        "00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1"
+    );
+    let sighash = tx.p2wpkh_sighash(1, &prevout).unwrap();
+    assert_eq!(
+        hex::encode(sighash.as_bytes()),
+        "c37af31116d1b27caf68aae9e3ac82f1477929014d5b917657d0eb49478cb670"
+    );
+
+    let signature = SignatureRecid::from_der(
+        &hex::decode(
+            "304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c45\
+             18331561406f90300e8f3358f51928d43c212a8caed02de67eebee",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    verify(&signature, &public_key, &sighash).unwrap();
+
+    // First input spends P2PK output, it's signed by scriptSig
+    let mut tx = tx;
+    tx.inputs[0].script_sig = hex::decode(
+        "4830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b19\
+         4ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01",
+    )
+    .unwrap();
+    let witnesses = vec![vec![], p2wpkh_witness(&signature, &public_key)];
+    assert_eq!(
+        hex::encode(tx.serialize_with_witnesses(&witnesses).unwrap()),
+        "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f000000004948\
+         30450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3\
+         f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3a\
+         a89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df3\
+         78db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f016\
+         7faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a\
+         0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368d\
+         a1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000"
+    );
+}
+
+#[test]
+fn sighash_requires_p2wpkh_prevout() {
+    let tx = bip143_transaction();
+    let p2pk = TxOut {
+        value: 625000000,
+        script_pubkey: hex::decode(
+            "2103c9f4836b9a4f77fc0d81f7bcb01b7f1b35916864b9476c241ce9fc198bd25432ac",
+        )
+        .unwrap(),
+    };
+    assert!(matches!(
+        tx.p2wpkh_sighash(0, &p2pk),
+        Err(BitcoinError::NotOwnedInput(0))
+    ));
+    assert!(matches!(
+        tx.p2wpkh_sighash(2, &p2pk),
+        Err(BitcoinError::InputOutOfRange(2))
+    ));
+}
+
+#[test]
+fn threshold_key_signs_every_input() {
+    let local_keys = simulate_keygen(1, 2);
+    let public_key = local_keys[0].public_key.clone();
+    let prevouts = vec![
+        TxOut {
+            value: 50000,
+            script_pubkey: p2wpkh_script_pubkey(&public_key),
+        },
+        TxOut {
+            value: 70000,
+            script_pubkey: p2wpkh_script_pubkey(&public_key),
+        },
+    ];
+    let tx = Transaction {
+        version: 2,
+        inputs: (0..2)
+            .map(|i| TxIn {
+                previous_output: OutPoint {
+                    txid: [i as u8 + 1; 32],
+                    vout: i,
+                },
+                script_sig: vec![],
+                sequence: 0xfffffffd,
+            })
+            .collect(),
+        outputs: vec![TxOut {
+            value: 110000,
+            script_pubkey: p2wpkh_script_pubkey(&Point::generator().to_point()),
+        }],
+        lock_time: 0,
+    };
+
+    // Every party completes one offline stage per input
+    let mut offline_stages = vec![vec![], vec![]];
+    for _ in &tx.inputs {
+        for (party, stage) in simulate_offline_stage(local_keys.clone(), &[1, 2])
+            .into_iter()
+            .enumerate()
+        {
+            offline_stages[party].push(stage);
+        }
+    }
+    let (signing, partial_sigs): (Vec<_>, Vec<_>) = offline_stages
+        .into_iter()
+        .map(|stages| TransactionSigning::new(tx.clone(), &prevouts, stages).unwrap())
+        .unzip();
+    let signed: Vec<_> = signing
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| s.complete(&[partial_sigs[1 - i].clone()]).unwrap())
+        .collect();
+    assert_eq!(signed[0].raw, signed[1].raw);
+
+    for (input, witness) in signed[0].witnesses.iter().enumerate() {
+        let (flag, der) = witness[0].split_last().unwrap();
+        assert_eq!(u32::from(*flag), SIGHASH_ALL);
+        assert_eq!(witness[1], public_key.to_bytes(true).to_vec());
+        let signature = SignatureRecid::from_der(der).unwrap();
+        let sighash = tx.p2wpkh_sighash(input, &prevouts[input]).unwrap();
+        verify(&signature, &public_key, &sighash).unwrap();
+    }
+}
+
+#[test]
+fn inputs_of_other_keys_are_rejected() {
+    let local_keys = simulate_keygen(1, 2);
+    let offline_stage = simulate_offline_stage(local_keys, &[1, 2]);
+    let tx = Transaction {
+        inputs: vec![bip143_transaction().inputs[1].clone()],
+        ..bip143_transaction()
+    };
+    let foreign = TxOut {
+        value: 600000000,
+        script_pubkey: hex::decode("00141d0f172a0ecb48aee1be1f2687d2963ae33f71a1").unwrap(),
+    };
+    assert!(matches!(
+        TransactionSigning::new(tx.clone(), &[foreign], vec![offline_stage[0].clone()]),
+        Err(BitcoinError::NotOwnedInput(0))
+    ));
+    assert!(matches!(
+        TransactionSigning::new(tx, &[], vec![offline_stage[0].clone()]),
+        Err(BitcoinError::InputsMismatch { .. })
+    ));
+}
@@ -0,0 +1,277 @@
+//! Signing Ethereum transactions with a threshold key
+//!
+//! Signers complete the offline stage in advance, then sign a transaction via
+//! [TransactionSigning]: every signer computes the signing hash of the same [Transaction]
+//! locally, broadcasts its partial signature, and assembles signed raw transaction ready to be
+//! submitted via `eth_sendRawTransaction`.
+
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Point, Secp256k1};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+use crate::protocols::gg_2020::party_i::SignatureRecid;
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::gg_2020::state_machine::sign::error::sign_local_complete_error::SignLocalCompleteError;
+use crate::protocols::gg_2020::state_machine::sign::stages::sign_manual::SignManual;
+use crate::protocols::gg_2020::state_machine::sign::{CompletedOfflineStage, PartialSignature};
+use crate::utilities::message_digest::MessageDigest;
+
+mod rlp;
+
+/// 20-byte Ethereum account address
+pub type Address = [u8; 20];
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum EthereumError {
+    #[error("signing transaction: {0}")]
+    Signing(SignLocalCompleteError),
+    #[error("recovery id {0} can't be encoded in a transaction")]
+    UnsupportedRecoveryId(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<[u8; 32]>,
+}
+
+/// Legacy transaction with EIP-155 replay protection
+///
+/// Amounts are in wei. `to` is `None` for contract creation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyTransaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: u128,
+    pub gas_limit: u64,
+    pub to: Option<Address>,
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+/// EIP-2930 transaction (type 1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip2930Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: u128,
+    pub gas_limit: u64,
+    pub to: Option<Address>,
+    pub value: u128,
+    pub data: Vec<u8>,
+    pub access_list: Vec<AccessListItem>,
+}
+
+/// EIP-1559 transaction (type 2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub gas_limit: u64,
+    pub to: Option<Address>,
+    pub value: u128,
+    pub data: Vec<u8>,
+    pub access_list: Vec<AccessListItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transaction {
+    Legacy(LegacyTransaction),
+    Eip2930(Eip2930Transaction),
+    Eip1559(Eip1559Transaction),
+}
+
+impl Transaction {
+    /// Serialized transaction which hash is signed
+    pub fn signing_payload(&self) -> Vec<u8> {
+        self.encode(None)
+    }
+
+    /// Keccak-256 of [signing payload](Self::signing_payload)
+    pub fn signing_hash(&self) -> MessageDigest {
+        MessageDigest::keccak256(&self.signing_payload())
+    }
+
+    /// Serializes transaction signed with `signature`
+    ///
+    /// For legacy transactions `v = recid + 35 + 2 * chain_id`, for typed transactions
+    /// `yParity = recid`.
+    pub fn encode_signed(&self, signature: &SignatureRecid) -> Result<Vec<u8>, EthereumError> {
+        if signature.recid > 1 {
+            return Err(EthereumError::UnsupportedRecoveryId(signature.recid));
+        }
+        Ok(self.encode(Some(signature)))
+    }
+
+    fn encode(&self, signature: Option<&SignatureRecid>) -> Vec<u8> {
+        let mut list = rlp::List::new();
+        let tx_type = match self {
+            Transaction::Legacy(tx) => {
+                list.uint(tx.nonce.into())
+                    .uint(tx.gas_price)
+                    .uint(tx.gas_limit.into());
+                encode_call(&mut list, &tx.to, tx.value, &tx.data);
+                match signature {
+                    Some(sig) => {
+                        let v = u128::from(sig.recid) + 35 + 2 * u128::from(tx.chain_id);
+                        list.uint(v);
+                        encode_rs(&mut list, sig);
+                    }
+                    None => {
+                        list.uint(tx.chain_id.into()).uint(0).uint(0);
+                    }
+                }
+                None
+            }
+            Transaction::Eip2930(tx) => {
+                list.uint(tx.chain_id.into())
+                    .uint(tx.nonce.into())
+                    .uint(tx.gas_price)
+                    .uint(tx.gas_limit.into());
+                encode_call(&mut list, &tx.to, tx.value, &tx.data);
+                list.list(encode_access_list(&tx.access_list));
+                encode_y_parity(&mut list, signature);
+                Some(0x01)
+            }
+            Transaction::Eip1559(tx) => {
+                list.uint(tx.chain_id.into())
+                    .uint(tx.nonce.into())
+                    .uint(tx.max_priority_fee_per_gas)
+                    .uint(tx.max_fee_per_gas)
+                    .uint(tx.gas_limit.into());
+                encode_call(&mut list, &tx.to, tx.value, &tx.data);
+                list.list(encode_access_list(&tx.access_list));
+                encode_y_parity(&mut list, signature);
+                Some(0x02)
+            }
+        };
+
+        let mut out = vec![];
+        out.extend(tx_type);
+        out.extend(list.finish());
+        out
+    }
+}
+
+fn encode_call(list: &mut rlp::List, to: &Option<Address>, value: u128, data: &[u8]) {
+    match to {
+        Some(address) => list.bytes(address),
+        None => list.bytes(&[]),
+    };
+    list.uint(value).bytes(data);
+}
+
+fn encode_access_list(access_list: &[AccessListItem]) -> rlp::List {
+    let mut list = rlp::List::new();
+    for item in access_list {
+        let mut keys = rlp::List::new();
+        for key in &item.storage_keys {
+            keys.bytes(key);
+        }
+        let mut entry = rlp::List::new();
+        entry.bytes(&item.address).list(keys);
+        list.list(entry);
+    }
+    list
+}
+
+fn encode_y_parity(list: &mut rlp::List, signature: Option<&SignatureRecid>) {
+    if let Some(sig) = signature {
+        list.uint(sig.recid.into());
+        encode_rs(list, sig);
+    }
+}
+
+fn encode_rs(list: &mut rlp::List, signature: &SignatureRecid) {
+    list.bytes(&signature.r.to_bigint().to_bytes())
+        .bytes(&signature.s.to_bigint().to_bytes());
+}
+
+/// Online stage of signing a transaction
+pub struct TransactionSigning {
+    transaction: Transaction,
+    sign: SignManual,
+}
+
+impl TransactionSigning {
+    /// Computes partial signature of the transaction that needs to be sent to other signers
+    pub fn new(
+        transaction: Transaction,
+        completed_offline_stage: CompletedOfflineStage,
+    ) -> Result<(Self, PartialSignature), EthereumError> {
+        let (sign, partial_signature) =
+            SignManual::new(transaction.signing_hash(), completed_offline_stage)
+                .map_err(EthereumError::Signing)?;
+        Ok((Self { transaction, sign }, partial_signature))
+    }
+
+    /// Assembles signed transaction out of partial signatures of other signers
+    ///
+    /// `partial_signatures` must not include partial signature of local party.
+    pub fn complete(
+        self,
+        partial_signatures: &[PartialSignature],
+    ) -> Result<SignedTransaction, EthereumError> {
+        let signature = self
+            .sign
+            .complete(partial_signatures)
+            .map_err(EthereumError::Signing)?;
+        let raw = self.transaction.encode_signed(&signature)?;
+        Ok(SignedTransaction { signature, raw })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedTransaction {
+    pub signature: SignatureRecid,
+    /// Serialized signed transaction
+    pub raw: Vec<u8>,
+}
+
+impl SignedTransaction {
+    /// Transaction hash, i.e. Keccak-256 of raw transaction
+    pub fn hash(&self) -> [u8; 32] {
+        Keccak256::digest(&self.raw).into()
+    }
+}
+
+/// Address controlled by the public key: last 20 bytes of Keccak-256 of uncompressed point
+pub fn public_key_to_address(public_key: &Point<Secp256k1>) -> Address {
+    let uncompressed = public_key.to_bytes(false);
+    let hash = Keccak256::digest(&uncompressed[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// EIP-55 checksummed hex of the address
+pub fn to_checksum_address(address: &Address) -> String {
+    let address = hex::encode(address);
+    // Letters whose nibble in hash of the lowercase address is >= 8 are uppercased
+    let checksum = Keccak256::digest(address.as_bytes());
+    let checksummed: String = address
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (checksum[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+/// EIP-55 checksummed address of the shared key
+pub fn local_key_address(local_key: &LocalKey<Secp256k1>) -> String {
+    to_checksum_address(&public_key_to_address(&local_key.public_key))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,63 @@
+//! Minimal RLP encoder, sufficient for transaction encoding
+
+/// RLP list being built item by item
+#[derive(Default)]
+pub struct List(Vec<u8>);
+
+impl List {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a byte string
+    pub fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        encode_bytes(bytes, &mut self.0);
+        self
+    }
+
+    /// Appends an unsigned integer as a minimal big-endian byte string (zero is empty string)
+    pub fn uint(&mut self, n: u128) -> &mut Self {
+        let bytes = n.to_be_bytes();
+        let leading_zeros = bytes.iter().take_while(|b| **b == 0).count();
+        self.bytes(&bytes[leading_zeros..])
+    }
+
+    /// Appends a nested list
+    pub fn list(&mut self, list: List) -> &mut Self {
+        list.encode_into(&mut self.0);
+        self
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        let mut out = vec![];
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(self, out: &mut Vec<u8>) {
+        encode_length(self.0.len(), 0xc0, out);
+        out.extend_from_slice(&self.0);
+    }
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    if let [b] = bytes {
+        if *b < 0x80 {
+            out.push(*b);
+            return;
+        }
+    }
+    encode_length(bytes.len(), 0x80, out);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_length(len: usize, offset: u8, out: &mut Vec<u8>) {
+    if len < 56 {
+        out.push(offset + len as u8);
+    } else {
+        let len = (len as u64).to_be_bytes();
+        let len = &len[len.iter().take_while(|b| **b == 0).count()..];
+        out.push(offset + 55 + len.len() as u8);
+        out.extend_from_slice(len);
+    }
+}
@@ -0,0 +1,208 @@
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+
+use crate::integrations::ethereum::*;
+use crate::protocols::gg_2020::party_i::SignatureRecid;
+use crate::protocols::gg_2020::state_machine::keygen::test::simulate_keygen;
+use crate::protocols::gg_2020::state_machine::sign::test::simulate_offline_stage;
+
+const RECIPIENT: Address = [0x35; 20];
+/// Address of private key `0x4646..46` used in EIP-155 example
+const SENDER: &str = "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F";
+
+fn signature(r: &str, s: &str, recid: u8) -> SignatureRecid {
+    let compact = hex::decode(format!("{}{}", r, s)).unwrap();
+    SignatureRecid {
+        recid,
+        ..SignatureRecid::from_compact(&compact).unwrap()
+    }
+}
+
+fn recovered_sender(tx: &Transaction, sig: &SignatureRecid) -> String {
+    let public_key = sig.recover_public_key(&tx.signing_hash()).unwrap();
+    to_checksum_address(&public_key_to_address(&public_key))
+}
+
+#[test]
+fn eip155_example() {
+    let tx = Transaction::Legacy(LegacyTransaction {
+        chain_id: 1,
+        nonce: 9,
+        gas_price: 20_000_000_000,
+        gas_limit: 21000,
+        to: Some(RECIPIENT),
+        value: 1_000_000_000_000_000_000,
+        data: vec![],
+    });
+    assert_eq!(
+        hex::encode(tx.signing_payload()),
+        "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+    );
+    assert_eq!(
+        hex::encode(tx.signing_hash().as_bytes()),
+        "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+    );
+
+    let sig = signature(
+        "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+        "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        0,
+    );
+    assert_eq!(
+        hex::encode(tx.encode_signed(&sig).unwrap()),
+        "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a0\
+         28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb7033\
+         04b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+    );
+    assert_eq!(recovered_sender(&tx, &sig), SENDER);
+}
+
+#[test]
+fn eip2930_transaction() {
+    let tx = Transaction::Eip2930(Eip2930Transaction {
+        chain_id: 1,
+        nonce: 0,
+        gas_price: 1_000_000_000,
+        gas_limit: 30000,
+        to: Some(RECIPIENT),
+        value: 1_000_000_000_000_000_000,
+        data: vec![0xde, 0xad, 0xbe, 0xef],
+        access_list: vec![AccessListItem {
+            address: RECIPIENT,
+            storage_keys: vec![{
+                let mut key = [0u8; 32];
+                key[31] = 1;
+                key
+            }],
+        }],
+    });
+    assert_eq!(
+        hex::encode(tx.signing_payload()),
+        "01f8670180843b9aca00827530943535353535353535353535353535353535353535880de0b6b3a764000084\
+         deadbeeff838f7943535353535353535353535353535353535353535e1a0000000000000000000000000000000\
+         0000000000000000000000000000000001"
+    );
+    assert_eq!(
+        hex::encode(tx.signing_hash().as_bytes()),
+        "32cd4ac79e9fd4004df93083b6b715aa6270244473a18a2bd7496ec98886a16f"
+    );
+
+    let sig = signature(
+        "4f355bdcb7cc0af728ef3cceb9615d90684bb5b2ca5f859ab0f0b704075871aa",
+        "7e0c53a9fe48f59bcc803545777c4f659555d8c62a23f6a02d9a46d4fe4022ed",
+        1,
+    );
+    assert_eq!(
+        hex::encode(tx.encode_signed(&sig).unwrap()),
+        "01f8aa0180843b9aca00827530943535353535353535353535353535353535353535880de0b6b3a764000084\
+         deadbeeff838f7943535353535353535353535353535353535353535e1a0000000000000000000000000000000\
+         000000000000000000000000000000000101a04f355bdcb7cc0af728ef3cceb9615d90684bb5b2ca5f859ab0f0\
+         b704075871aaa07e0c53a9fe48f59bcc803545777c4f659555d8c62a23f6a02d9a46d4fe4022ed"
+    );
+    assert_eq!(recovered_sender(&tx, &sig), SENDER);
+}
+
+#[test]
+fn eip1559_transaction() {
+    let tx = Transaction::Eip1559(Eip1559Transaction {
+        chain_id: 1,
+        nonce: 3,
+        max_priority_fee_per_gas: 2_000_000_000,
+        max_fee_per_gas: 100_000_000_000,
+        gas_limit: 21000,
+        to: Some(RECIPIENT),
+        value: 1_000_000_000_000_000_000,
+        data: vec![],
+        access_list: vec![],
+    });
+    assert_eq!(
+        hex::encode(tx.signing_payload()),
+        "02f00103847735940085174876e800825208943535353535353535353535353535353535353535880de0b6b3a7\
+         64000080c0"
+    );
+    assert_eq!(
+        hex::encode(tx.signing_hash().as_bytes()),
+        "69f677afff6b87080067ab5e91dce4cf1cc0dafe0429095310b8322468b4205e"
+    );
+
+    let sig = signature(
+        "466d7fcae563e5cb09a0d1870bb580344804617879a14949cf22285f1bae3f27",
+        "3b479d939cdea252e272519efa3e93fcbc779ea994cba32849259c5a357fdf83",
+        1,
+    );
+    assert_eq!(
+        hex::encode(tx.encode_signed(&sig).unwrap()),
+        "02f8730103847735940085174876e800825208943535353535353535353535353535353535353535880de0b6b3\
+         a764000080c001a0466d7fcae563e5cb09a0d1870bb580344804617879a14949cf22285f1bae3f27a03b479d93\
+         9cdea252e272519efa3e93fcbc779ea994cba32849259c5a357fdf83"
+    );
+    assert_eq!(recovered_sender(&tx, &sig), SENDER);
+
+    let overflowing = SignatureRecid { recid: 2, ..sig };
+    assert!(matches!(
+        tx.encode_signed(&overflowing),
+        Err(EthereumError::UnsupportedRecoveryId(2))
+    ));
+}
+
+#[test]
+fn contract_creation_has_empty_recipient() {
+    let tx = Transaction::Legacy(LegacyTransaction {
+        chain_id: 5,
+        nonce: 0,
+        gas_price: 1,
+        gas_limit: 53000,
+        to: None,
+        value: 0,
+        data: vec![0x60, 0x00],
+    });
+    assert_eq!(
+        hex::encode(tx.signing_payload()),
+        "cd800182cf088080826000058080"
+    );
+}
+
+#[test]
+fn addresses_are_checksummed() {
+    let g = Point::<Secp256k1>::generator().to_point();
+    assert_eq!(
+        to_checksum_address(&public_key_to_address(&g)),
+        "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+    );
+
+    let sk = Scalar::<Secp256k1>::from_bytes(&[0x46; 32]).unwrap();
+    let pk = Point::generator() * sk;
+    assert_eq!(to_checksum_address(&public_key_to_address(&pk)), SENDER);
+}
+
+#[test]
+fn threshold_key_signs_transaction() {
+    let local_keys = simulate_keygen(1, 3);
+    let address = local_key_address(&local_keys[0]);
+    assert!(local_keys.iter().all(|key| local_key_address(key) == address));
+
+    let tx = Transaction::Eip1559(Eip1559Transaction {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: 2_000_000_000,
+        max_fee_per_gas: 100_000_000_000,
+        gas_limit: 21000,
+        to: Some(RECIPIENT),
+        value: 1,
+        data: vec![],
+        access_list: vec![],
+    });
+    let offline_stage = simulate_offline_stage(local_keys, &[1, 3]);
+    let (signing, partial_sigs): (Vec<_>, Vec<_>) = offline_stage
+        .into_iter()
+        .map(|o| TransactionSigning::new(tx.clone(), o).unwrap())
+        .unzip();
+    let signed: Vec<_> = signing
+        .into_iter()
+        .enumerate()
+        .map(|(i, s)| s.complete(&[partial_sigs[1 - i].clone()]).unwrap())
+        .collect();
+
+    assert_eq!(signed[0].raw, signed[1].raw);
+    assert_eq!(signed[0].hash(), signed[1].hash());
+    assert_eq!(recovered_sender(&tx, &signed[0].signature), address);
+}
@@ -18,7 +18,12 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
 
+pub mod integrations;
 pub mod protocols;
+#[cfg(feature = "relay")]
+pub mod relay;
+pub mod storage;
+pub mod transport;
 pub mod utilities;
 use std::fmt;
 
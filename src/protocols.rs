@@ -1 +1,2 @@
-pub mod gg_2020;
\ No newline at end of file
+pub mod gg_2020;
+pub mod schnorr_bip340;
\ No newline at end of file
@@ -126,15 +126,14 @@ impl GlobalStatePhase5 {
 
         let alpha_beta_matrix = (0..len)
             .map(|i| {
-                let message_a = MessageA::a_with_predefined_randomness(
+                let c_a = MessageA::ciphertext_with_predefined_randomness(
                     &self.k_vec[i],
                     &self.encryption_key_vec[i],
                     &self.k_randomness_vec[i],
-                    &[],
                 );
 
                 // check message a
-                if message_a.c != self.m_a_vec[i].c {
+                if c_a != self.m_a_vec[i].c {
                     bad_signers_vec.push(i)
                 }
 
@@ -142,17 +141,15 @@ impl GlobalStatePhase5 {
                     (0..len - 1)
                         .map(|j| {
                             let ind = if j < i { j } else { j + 1 };
-                            let (message_b, beta) = MessageB::b_with_predefined_randomness(
+                            let (c_b, beta) = MessageB::ciphertext_with_predefined_randomness(
                                 &self.gamma_vec[ind],
                                 &self.encryption_key_vec[i],
-                                message_a.clone(),
+                                &c_a,
                                 &self.beta_randomness_vec[i][j],
                                 &self.beta_tag_vec[i][j],
-                                &[],
-                            )
-                            .unwrap();
+                            );
                             // check message_b
-                            if message_b.c != self.m_b_mat[i][j].c {
+                            if c_b != self.m_b_mat[i][j].c {
                                 bad_signers_vec.push(ind)
                             }
 
@@ -339,13 +336,11 @@ impl GlobalStatePhase6 {
 
         // check correctness of k
         for i in 0..len {
-            if MessageA::a_with_predefined_randomness(
+            if MessageA::ciphertext_with_predefined_randomness(
                 &self.k_vec[i],
                 &self.encryption_key_vec[i],
                 &self.k_randomness_vec[i],
-                &[],
-            )
-            .c != self.m_a_vec[i].c
+            ) != self.m_a_vec[i].c
             {
                 bad_signers_vec.push(i)
             }
@@ -16,18 +16,10 @@
     @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
 */
 
-use std::fmt::Debug;
-use centipede::juggling::proof_system::{Helgamalsegmented, Witness};
-use centipede::juggling::segmentation::Msegmentation;
 use curv::arithmetic::traits::*;
-use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
-use curv::cryptographic_primitives::commitments::traits::Commitment;
-use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::*;
-use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
 use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Curve, Point, Scalar};
 use curv::BigInt;
-use sha2::Sha256;
 
 use crate::protocols::gg_2020::state_machine::keygen::messages::broadcast::KeyGenBroadcast;
 
@@ -38,13 +30,24 @@ use paillier::{
 
 use serde::{Deserialize, Serialize};
 use zk_paillier::zkproofs::NiCorrectKeyProof;
-use zk_paillier::zkproofs::{CompositeDLogProof, DLogStatement};
+use zk_paillier::zkproofs::DLogStatement;
 
 use crate::protocols::gg_2020::ErrorType;
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
 use crate::utilities::zk_pdl_with_slack::{PDLwSlackProof, PDLwSlackStatement, PDLwSlackWitness};
-use curv::cryptographic_primitives::proofs::sigma_valid_pedersen::PedersenProof;
+use crate::utilities::zk_sigma::{
+    DLogProof, HomoELGamalProof, HomoElGamalStatement, HomoElGamalWitness, PedersenProof,
+};
 
 use std::convert::TryInto;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+mod recovery;
+mod signature;
+pub use recovery::RecoveryError;
+pub use signature::SignatureEncodingError;
 
 const SECURITY: usize = 256;
 const PAILLIER_MIN_BIT_LENGTH: usize = 2047;
@@ -56,13 +59,27 @@ pub struct Parameters {
     pub share_count: u16, //n
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SharedKeys {
     pub y: Point<Secp256k1>,
     pub x_i: Scalar<Secp256k1>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl Zeroize for SharedKeys {
+    fn zeroize(&mut self) {
+        self.x_i = Scalar::zero();
+    }
+}
+
+impl Drop for SharedKeys {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SharedKeys {}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SignKeys {
     pub w_i: Scalar<Secp256k1>,
     pub g_w_i: Point<Secp256k1>,
@@ -71,18 +88,41 @@ pub struct SignKeys {
     pub g_gamma_i: Point<Secp256k1>,
 }
 
+impl Zeroize for SignKeys {
+    fn zeroize(&mut self) {
+        self.w_i = Scalar::zero();
+        self.k_i = Scalar::zero();
+        self.gamma_i = Scalar::zero();
+    }
+}
+
+impl Drop for SignKeys {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SignKeys {}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignBroadcastPhase1 {
     pub com: BigInt,
 }
 
+wire_struct!(SignBroadcastPhase1 { com });
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SignDecommitPhase1 {
     pub blind_factor: BigInt,
     pub g_gamma_i: Point<Secp256k1>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+wire_struct!(SignDecommitPhase1 {
+    blind_factor,
+    g_gamma_i,
+});
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct LocalSignature {
     pub r: Scalar<Secp256k1>,
     pub R: Point<Secp256k1>,
@@ -166,11 +206,11 @@ impl SignKeys {
         }
     }
 
-    pub fn phase1_broadcast(&self) -> (SignBroadcastPhase1, SignDecommitPhase1) {
+    pub fn phase1_broadcast(&self, sid: &SessionId) -> (SignBroadcastPhase1, SignDecommitPhase1) {
         let blind_factor = BigInt::sample(SECURITY);
         let g = Point::generator();
         let g_gamma_i = g * &self.gamma_i;
-        let com = HashCommitment::<Sha256>::create_commitment_with_user_defined_randomness(
+        let com = sid.commit(
             &BigInt::from_bytes(g_gamma_i.to_bytes(true).as_ref()),
             &blind_factor,
         );
@@ -215,16 +255,13 @@ impl SignKeys {
 
     pub fn phase3_compute_t_i(
         sigma_i: &Scalar<Secp256k1>,
-    ) -> (
-        Point<Secp256k1>,
-        Scalar<Secp256k1>,
-        PedersenProof<Secp256k1, Sha256>,
-    ) {
+        sid: &SessionId,
+    ) -> (Point<Secp256k1>, Scalar<Secp256k1>, PedersenProof) {
         let g_sigma_i = Point::generator() * sigma_i;
         let l = Scalar::<Secp256k1>::random();
         let h_l = Point::<Secp256k1>::base_point2() * &l;
         let T = g_sigma_i + h_l;
-        let T_zk_proof = PedersenProof::<Secp256k1, Sha256>::prove(sigma_i, &l);
+        let T_zk_proof = PedersenProof::prove(sigma_i, &l, sid);
 
         (T, l, T_zk_proof)
     }
@@ -237,17 +274,18 @@ impl SignKeys {
 
     pub fn phase4(
         delta_inv: &Scalar<Secp256k1>,
-        b_proof_vec: &[&DLogProof<Secp256k1, Sha256>],
+        b_proof_vec: &[&DLogProof],
         phase1_decommit_vec: Vec<SignDecommitPhase1>,
         bc1_vec: &[SignBroadcastPhase1],
         index: usize,
+        sid: &SessionId,
     ) -> Result<Point<Secp256k1>, ErrorType> {
         let mut bad_actors_vec = Vec::new();
         let test_b_vec_and_com = (0..b_proof_vec.len())
             .map(|j| {
                 let ind = if j < index { j } else { j + 1 };
                 let res = b_proof_vec[j].pk == phase1_decommit_vec[ind].g_gamma_i
-                    && HashCommitment::<Sha256>::create_commitment_with_user_defined_randomness(
+                    && sid.commit(
                         &BigInt::from_bytes(
                             phase1_decommit_vec[ind].g_gamma_i.to_bytes(true).as_ref(),
                         ),
@@ -292,6 +330,7 @@ impl LocalSignature {
         k_i: &Scalar<Secp256k1>,
         k_enc_randomness: &BigInt,
         dlog_statement: &DLogStatement,
+        sid: &SessionId,
     ) -> PDLwSlackProof {
         // Generate PDL with slack statement, witness and proof
         let pdl_w_slack_statement = PDLwSlackStatement {
@@ -309,7 +348,7 @@ impl LocalSignature {
             r: k_enc_randomness.clone(),
         };
 
-        PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement)
+        PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement, sid)
     }
 
     pub fn phase5_verify_pdl(
@@ -321,6 +360,7 @@ impl LocalSignature {
         dlog_statement: &[DLogStatement],
         s: &[usize],
         i: usize,
+        sid: &SessionId,
     ) -> Result<(), ErrorType> {
         let mut bad_actors_vec = Vec::new();
 
@@ -340,7 +380,7 @@ impl LocalSignature {
                         h2: dlog_statement[s[ind]].ni.clone(),
                         N_tilde: dlog_statement[s[ind]].N.clone(),
                     };
-                    let ver_res = pdl_w_slack_proof_vec[j].verify(&pdl_w_slack_statement);
+                    let ver_res = pdl_w_slack_proof_vec[j].verify(&pdl_w_slack_statement, sid);
                     if ver_res.is_err() {
                         bad_actors_vec.push(i);
                         false
@@ -376,7 +416,8 @@ impl LocalSignature {
         T: &Point<Secp256k1>,
         sigma: &Scalar<Secp256k1>,
         l: &Scalar<Secp256k1>,
-    ) -> (Point<Secp256k1>, HomoELGamalProof<Secp256k1, Sha256>) {
+        sid: &SessionId,
+    ) -> (Point<Secp256k1>, HomoELGamalProof) {
         let S = R * sigma;
         let delta = HomoElGamalStatement {
             G: R.clone(),
@@ -389,16 +430,17 @@ impl LocalSignature {
             x: l.clone(),
             r: sigma.clone(),
         };
-        let proof = HomoELGamalProof::prove(&witness, &delta);
+        let proof = HomoELGamalProof::prove(&witness, &delta, sid);
 
         (S, proof)
     }
 
     pub fn phase6_verify_proof(
         S_vec: &[Point<Secp256k1>],
-        proof_vec: &[HomoELGamalProof<Secp256k1, Sha256>],
+        proof_vec: &[HomoELGamalProof],
         R_vec: &[Point<Secp256k1>],
         T_vec: &[Point<Secp256k1>],
+        sid: &SessionId,
     ) -> Result<(), ErrorType> {
         let mut bad_actors_vec = Vec::new();
         let mut verify_proofs = true;
@@ -410,7 +452,7 @@ impl LocalSignature {
                 D: T_vec[i].clone(),
                 E: S_vec[i].clone(),
             };
-            if proof_vec[i].verify(&delta).is_err() {
+            if proof_vec[i].verify(&delta, sid).is_err() {
                 verify_proofs = false;
                 bad_actors_vec.push(i);
             };
@@ -445,12 +487,13 @@ impl LocalSignature {
 
     pub fn phase7_local_sig(
         k_i: &Scalar<Secp256k1>,
-        message: &BigInt,
+        message: &MessageDigest,
         R: &Point<Secp256k1>,
         sigma_i: &Scalar<Secp256k1>,
         pubkey: &Point<Secp256k1>,
     ) -> Self {
-        let m_fe = Scalar::<Secp256k1>::from(message);
+        let message = message.to_bigint();
+        let m_fe = Scalar::<Secp256k1>::from(&message);
         let r = Scalar::<Secp256k1>::from(
             &R.x_coord()
                 .unwrap()
@@ -461,7 +504,7 @@ impl LocalSignature {
             r,
             R: R.clone(),
             s_i,
-            m: message.clone(),
+            m: message,
             y: pubkey.clone(),
         }
     }
@@ -470,35 +513,31 @@ impl LocalSignature {
         let mut s = s_vec.iter().fold(self.s_i.clone(), |acc, x| acc + x);
         let s_bn = s.to_bigint();
 
-        let r = Scalar::<Secp256k1>::from(
-            &self
-                .R
-                .x_coord()
-                .unwrap()
-                .mod_floor(Scalar::<Secp256k1>::group_order()),
-        );
-        let ry: BigInt = self
-            .R
-            .y_coord()
-            .unwrap()
-            .mod_floor(Scalar::<Secp256k1>::group_order());
+        let rx = self.R.x_coord().unwrap();
+        let ry = self.R.y_coord().unwrap();
+        let r = Scalar::<Secp256k1>::from(&rx.mod_floor(Scalar::<Secp256k1>::group_order()));
 
         /*
          Calculate recovery id - it is not possible to compute the public key out of the signature
          itself. Recovery id is used to enable extracting the public key uniquely.
          1. id = R.y & 1
-         2. if (s > curve.q / 2) id = id ^ 1
+         2. if (R.x >= curve.q) id = id | 2
+         3. if (s > curve.q / 2) id = id ^ 1
         */
-        let is_ry_odd = ry.test_bit(0);
-        let mut recid = if is_ry_odd { 1 } else { 0 };
+        let mut recid = if ry.test_bit(0) { 1 } else { 0 };
+        if &rx >= Scalar::<Secp256k1>::group_order() {
+            recid |= 2;
+        }
         let s_tag_bn = Scalar::<Secp256k1>::group_order() - &s_bn;
         if s_bn > s_tag_bn {
             s = Scalar::<Secp256k1>::from(&s_tag_bn);
             recid ^= 1;
         }
         let sig = SignatureRecid { r, s, recid };
-        let ver = verify(&sig, &self.y, &self.m).is_ok();
-        if ver {
+        let message = MessageDigest::from_bigint(&self.m).map_err(|_| InvalidSig)?;
+        let ver = verify(&sig, &self.y, &message).is_ok();
+        let recovered = sig.recover_public_key(&message).ok();
+        if ver && recovered.as_ref() == Some(&self.y) {
             Ok(sig)
         } else {
             Err(InvalidSig)
@@ -506,9 +545,17 @@ impl LocalSignature {
     }
 }
 
-pub fn verify(sig: &SignatureRecid, y: &Point<Secp256k1>, message: &BigInt) -> Result<(), Error> {
-    let b = sig.s.invert().unwrap();
-    let a = Scalar::<Secp256k1>::from(message);
+/// Verifies ECDSA signature of the message digest under public key `y`
+pub fn verify(
+    sig: &SignatureRecid,
+    y: &Point<Secp256k1>,
+    message: &MessageDigest,
+) -> Result<(), Error> {
+    if sig.r.is_zero() {
+        return Err(InvalidSig);
+    }
+    let b = sig.s.invert().ok_or(InvalidSig)?;
+    let a = Scalar::<Secp256k1>::from(&message.to_bigint());
     let u1 = a * &b;
     let u2 = &sig.r * &b;
 
@@ -517,14 +564,9 @@ pub fn verify(sig: &SignatureRecid, y: &Point<Secp256k1>, message: &BigInt) -> R
     let yu2 = y * &u2;
     // can be faster using shamir trick
 
-    if sig.r
-        == Scalar::<Secp256k1>::from(
-            &(gu1 + yu2)
-                .x_coord()
-                .unwrap()
-                .mod_floor(Scalar::<Secp256k1>::group_order()),
-        )
-    {
+    // x coordinate is missing if u1·G + u2·Y is the point at infinity
+    let x = (gu1 + yu2).x_coord().ok_or(InvalidSig)?;
+    if sig.r == Scalar::<Secp256k1>::from(&x.mod_floor(Scalar::<Secp256k1>::group_order())) {
         Ok(())
     } else {
         Err(InvalidSig)
@@ -0,0 +1,69 @@
+//! Public key recovery from [SignatureRecid]
+
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use thiserror::Error;
+
+use super::SignatureRecid;
+use crate::utilities::message_digest::MessageDigest;
+
+/// Modulus of secp256k1 base field
+const FIELD_MODULUS: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum RecoveryError {
+    #[error("recovery id must be in range [0; 3], got {0}")]
+    InvalidRecoveryId(u8),
+    #[error("r is zero")]
+    ZeroR,
+    #[error("x coordinate of R exceeds field modulus")]
+    XCoordinateOverflow,
+    #[error("R is not on the curve")]
+    InvalidR,
+    #[error("recovered public key is the point at infinity")]
+    ZeroPublicKey,
+}
+
+impl SignatureRecid {
+    /// Recovers public key that produced this signature of `message` (SEC1 4.1.6)
+    ///
+    /// Bit 0 of `recid` is the parity of `R.y`, bit 1 tells whether `R.x` is `r + q` rather
+    /// than `r`. Note that recovered key is only meaningful if the signature is valid: any
+    /// `(r, s, recid)` recovers to some key, so the result must be compared against the
+    /// expected key or address.
+    pub fn recover_public_key(
+        &self,
+        message: &MessageDigest,
+    ) -> Result<Point<Secp256k1>, RecoveryError> {
+        if self.recid > 3 {
+            return Err(RecoveryError::InvalidRecoveryId(self.recid));
+        }
+        let r_inv = self.r.invert().ok_or(RecoveryError::ZeroR)?;
+
+        let mut x = self.r.to_bigint();
+        if self.recid & 2 != 0 {
+            x = x + Scalar::<Secp256k1>::group_order();
+        }
+        if x >= BigInt::from_hex(FIELD_MODULUS).unwrap() {
+            return Err(RecoveryError::XCoordinateOverflow);
+        }
+        let mut compressed = vec![0x02 | (self.recid & 1)];
+        let x = x.to_bytes();
+        compressed.resize(33 - x.len(), 0);
+        compressed.extend_from_slice(&x);
+        let big_r =
+            Point::<Secp256k1>::from_bytes(&compressed).map_err(|_| RecoveryError::InvalidR)?;
+
+        let e = Scalar::<Secp256k1>::from_bigint(&message.to_bigint());
+        let public_key = (big_r * &self.s - Point::generator() * e) * r_inv;
+        if public_key.is_zero() {
+            return Err(RecoveryError::ZeroPublicKey);
+        }
+        Ok(public_key)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,65 @@
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use secp256k1::{Message, SecretKey, SECP256K1};
+
+use crate::protocols::gg_2020::party_i::{RecoveryError, SignatureRecid};
+use crate::utilities::message_digest::MessageDigest;
+
+#[test]
+fn recovers_public_key_of_secp256k1_signatures() {
+    for i in 0..50u32 {
+        let sk = Scalar::<Secp256k1>::random();
+        let message = MessageDigest::sha256(&i.to_be_bytes());
+        let secp_sig = SECP256K1.sign_ecdsa_recoverable(
+            &Message::from_slice(message.as_bytes()).unwrap(),
+            &SecretKey::from_slice(&sk.to_bytes()).unwrap(),
+        );
+        let (recid, compact) = secp_sig.serialize_compact();
+        let mut bytes = compact.to_vec();
+        bytes.push(recid.to_i32() as u8);
+        let sig = SignatureRecid::from_recoverable(&bytes, 0).unwrap();
+
+        let pk = Point::generator() * &sk;
+        assert_eq!(sig.recover_public_key(&message).unwrap(), pk);
+
+        // Wrong parity recovers a different key
+        let flipped = SignatureRecid {
+            recid: sig.recid ^ 1,
+            ..sig.clone()
+        };
+        assert_ne!(flipped.recover_public_key(&message).unwrap(), pk);
+    }
+}
+
+#[test]
+fn invalid_recovery_inputs_are_rejected() {
+    let message = MessageDigest::sha256(b"ZenGo");
+    let q_minus_one = Scalar::<Secp256k1>::group_order() - BigInt::one();
+    let sig = SignatureRecid {
+        r: Scalar::from_bigint(&q_minus_one),
+        s: Scalar::from_bigint(&BigInt::one()),
+        recid: 4,
+    };
+    assert_eq!(
+        sig.recover_public_key(&message).unwrap_err(),
+        RecoveryError::InvalidRecoveryId(4)
+    );
+
+    // r + q exceeds field modulus
+    let sig = SignatureRecid { recid: 2, ..sig };
+    assert_eq!(
+        sig.recover_public_key(&message).unwrap_err(),
+        RecoveryError::XCoordinateOverflow
+    );
+
+    let sig = SignatureRecid {
+        r: Scalar::zero(),
+        recid: 0,
+        ..sig
+    };
+    assert_eq!(
+        sig.recover_public_key(&message).unwrap_err(),
+        RecoveryError::ZeroR
+    );
+}
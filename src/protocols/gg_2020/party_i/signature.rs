@@ -0,0 +1,209 @@
+//! Standard encodings of [SignatureRecid]
+
+#[cfg(feature = "secp256k1-compat")]
+use std::convert::TryFrom;
+
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Scalar};
+use curv::BigInt;
+use thiserror::Error;
+
+use super::SignatureRecid;
+
+const SCALAR_BYTES: usize = 32;
+/// `SEQUENCE` of two 33-byte `INTEGER`s
+const MAX_DER_LEN: usize = 2 + 2 * (2 + SCALAR_BYTES + 1);
+
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SignatureEncodingError {
+    #[error("signature must be {expected} bytes long, got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("malformed DER encoding: {0}")]
+    MalformedDer(&'static str),
+    #[error("{0} is not in range [1; q-1]")]
+    ScalarOutOfRange(&'static str),
+    #[error("v={v} doesn't correspond to any recovery id with offset {v_offset}")]
+    InvalidRecoveryByte { v: u8, v_offset: u8 },
+    #[error("recovery id {recid} with offset {v_offset} doesn't fit into a byte")]
+    RecoveryByteOverflow { recid: u8, v_offset: u8 },
+}
+
+impl SignatureRecid {
+    /// Encodes the signature as DER `SEQUENCE { r INTEGER, s INTEGER }`
+    pub fn to_der(&self) -> Vec<u8> {
+        let r = der_integer(&self.r);
+        let s = der_integer(&self.s);
+        let mut der = Vec::with_capacity(MAX_DER_LEN);
+        der.push(0x30);
+        der.push((r.len() + s.len()) as u8);
+        der.extend_from_slice(&r);
+        der.extend_from_slice(&s);
+        der
+    }
+
+    /// Parses strict DER encoding produced by [to_der](Self::to_der)
+    ///
+    /// DER doesn't carry recovery id, so `recid` of returned signature is `0`.
+    pub fn from_der(der: &[u8]) -> Result<Self, SignatureEncodingError> {
+        if der.len() > MAX_DER_LEN {
+            return Err(SignatureEncodingError::MalformedDer("too long"));
+        }
+        match der {
+            [0x30, len, body @ ..] if usize::from(*len) == body.len() => {
+                let (r, rest) = parse_der_integer(body, "r")?;
+                let (s, rest) = parse_der_integer(rest, "s")?;
+                if !rest.is_empty() {
+                    return Err(SignatureEncodingError::MalformedDer("trailing bytes"));
+                }
+                Ok(SignatureRecid { r, s, recid: 0 })
+            }
+            [0x30, ..] => Err(SignatureEncodingError::MalformedDer("length mismatch")),
+            _ => Err(SignatureEncodingError::MalformedDer("expected sequence")),
+        }
+    }
+
+    /// Encodes the signature as 64 bytes `r || s`
+    pub fn to_compact(&self) -> [u8; 64] {
+        let mut compact = [0u8; 64];
+        compact[..32].copy_from_slice(&scalar_to_bytes(&self.r));
+        compact[32..].copy_from_slice(&scalar_to_bytes(&self.s));
+        compact
+    }
+
+    /// Parses 64 bytes `r || s`
+    ///
+    /// Compact encoding doesn't carry recovery id, so `recid` of returned signature is `0`.
+    pub fn from_compact(bytes: &[u8]) -> Result<Self, SignatureEncodingError> {
+        if bytes.len() != 64 {
+            return Err(SignatureEncodingError::InvalidLength {
+                expected: 64,
+                actual: bytes.len(),
+            });
+        }
+        Ok(SignatureRecid {
+            r: scalar_from_bytes(&bytes[..32], "r")?,
+            s: scalar_from_bytes(&bytes[32..], "s")?,
+            recid: 0,
+        })
+    }
+
+    /// Encodes the signature as 65 bytes `r || s || v`, where `v = recid + v_offset`
+    ///
+    /// Common offsets are `0` (raw recovery id) and `27` (Bitcoin signed messages, Ethereum
+    /// `personal_sign` and pre-EIP-155 transactions). Returns error if `recid + v_offset`
+    /// exceeds `255`.
+    pub fn to_recoverable(&self, v_offset: u8) -> Result<[u8; 65], SignatureEncodingError> {
+        let v = self.recid.checked_add(v_offset).ok_or(
+            SignatureEncodingError::RecoveryByteOverflow {
+                recid: self.recid,
+                v_offset,
+            },
+        )?;
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&self.to_compact());
+        bytes[64] = v;
+        Ok(bytes)
+    }
+
+    /// Parses 65 bytes `r || s || v` produced by [to_recoverable](Self::to_recoverable)
+    pub fn from_recoverable(bytes: &[u8], v_offset: u8) -> Result<Self, SignatureEncodingError> {
+        if bytes.len() != 65 {
+            return Err(SignatureEncodingError::InvalidLength {
+                expected: 65,
+                actual: bytes.len(),
+            });
+        }
+        let v = bytes[64];
+        let recid = match v.checked_sub(v_offset) {
+            Some(recid) if recid <= 3 => recid,
+            _ => return Err(SignatureEncodingError::InvalidRecoveryByte { v, v_offset }),
+        };
+        Ok(SignatureRecid {
+            recid,
+            ..Self::from_compact(&bytes[..64])?
+        })
+    }
+}
+
+#[cfg(feature = "secp256k1-compat")]
+impl TryFrom<&SignatureRecid> for secp256k1::ecdsa::RecoverableSignature {
+    type Error = secp256k1::Error;
+
+    fn try_from(sig: &SignatureRecid) -> Result<Self, Self::Error> {
+        let recid = secp256k1::ecdsa::RecoveryId::from_i32(i32::from(sig.recid))?;
+        Self::from_compact(&sig.to_compact(), recid)
+    }
+}
+
+#[cfg(feature = "secp256k1-compat")]
+impl TryFrom<&SignatureRecid> for secp256k1::ecdsa::Signature {
+    type Error = secp256k1::Error;
+
+    fn try_from(sig: &SignatureRecid) -> Result<Self, Self::Error> {
+        Self::from_compact(&sig.to_compact())
+    }
+}
+
+fn scalar_to_bytes(scalar: &Scalar<Secp256k1>) -> [u8; SCALAR_BYTES] {
+    let bytes = scalar.to_bigint().to_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|b| **b == 0).count()..];
+    let mut padded = [0u8; SCALAR_BYTES];
+    padded[SCALAR_BYTES - bytes.len()..].copy_from_slice(bytes);
+    padded
+}
+
+fn scalar_from_bytes(
+    bytes: &[u8],
+    name: &'static str,
+) -> Result<Scalar<Secp256k1>, SignatureEncodingError> {
+    let n = BigInt::from_bytes(bytes);
+    if n == BigInt::zero() || &n >= Scalar::<Secp256k1>::group_order() {
+        return Err(SignatureEncodingError::ScalarOutOfRange(name));
+    }
+    Ok(Scalar::from_bigint(&n))
+}
+
+/// DER `INTEGER`: minimal big-endian bytes, prefixed with zero if the top bit is set
+fn der_integer(scalar: &Scalar<Secp256k1>) -> Vec<u8> {
+    let bytes = scalar_to_bytes(scalar);
+    let mut bytes: &[u8] = &bytes[bytes.iter().take_while(|b| **b == 0).count()..];
+    if bytes.is_empty() {
+        bytes = &[0];
+    }
+    let mut out = vec![0x02, 0];
+    if bytes[0] & 0x80 != 0 {
+        out.push(0);
+    }
+    out.extend_from_slice(bytes);
+    out[1] = (out.len() - 2) as u8;
+    out
+}
+
+fn parse_der_integer<'a>(
+    der: &'a [u8],
+    name: &'static str,
+) -> Result<(Scalar<Secp256k1>, &'a [u8]), SignatureEncodingError> {
+    let (len, rest) = match der {
+        [0x02, len, rest @ ..] => (usize::from(*len), rest),
+        _ => return Err(SignatureEncodingError::MalformedDer("expected integer")),
+    };
+    if len == 0 || len > rest.len() {
+        return Err(SignatureEncodingError::MalformedDer("invalid integer length"));
+    }
+    let (bytes, rest) = rest.split_at(len);
+    if bytes[0] & 0x80 != 0 {
+        return Err(SignatureEncodingError::MalformedDer("negative integer"));
+    }
+    if bytes.len() > 1 && bytes[0] == 0 && bytes[1] & 0x80 == 0 {
+        return Err(SignatureEncodingError::MalformedDer("integer is not minimal"));
+    }
+    let bytes = if bytes[0] == 0 { &bytes[1..] } else { bytes };
+    if bytes.len() > SCALAR_BYTES {
+        return Err(SignatureEncodingError::ScalarOutOfRange(name));
+    }
+    Ok((scalar_from_bytes(bytes, name)?, rest))
+}
+
+#[cfg(test)]
+mod test;
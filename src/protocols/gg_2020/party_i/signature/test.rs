@@ -0,0 +1,186 @@
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use secp256k1::{ecdsa, Message, SecretKey, SECP256K1};
+
+use crate::protocols::gg_2020::party_i::{verify, SignatureEncodingError, SignatureRecid};
+use crate::utilities::message_digest::MessageDigest;
+use crate::Error;
+
+/// Signs random message with random key by `secp256k1` crate
+fn secp256k1_signature() -> (Point<Secp256k1>, MessageDigest, ecdsa::RecoverableSignature) {
+    let sk = Scalar::<Secp256k1>::random();
+    let message = MessageDigest::sha256(&Scalar::<Secp256k1>::random().to_bytes());
+    let secp_sk = SecretKey::from_slice(&sk.to_bytes()).unwrap();
+    let secp_message = Message::from_slice(message.as_bytes()).unwrap();
+    let signature = SECP256K1.sign_ecdsa_recoverable(&secp_message, &secp_sk);
+    (Point::generator() * &sk, message, signature)
+}
+
+#[test]
+fn recoverable_signature_of_secp256k1_is_parsed() {
+    for _ in 0..20 {
+        let (pk, message, secp_sig) = secp256k1_signature();
+        let (recid, compact) = secp_sig.serialize_compact();
+        let mut bytes = compact.to_vec();
+        bytes.push(recid.to_i32() as u8 + 27);
+
+        let sig = SignatureRecid::from_recoverable(&bytes, 27).unwrap();
+        assert_eq!(i32::from(sig.recid), recid.to_i32());
+        verify(&sig, &pk, &message).unwrap();
+        assert_eq!(&sig.to_recoverable(27).unwrap()[..], &bytes[..]);
+        assert_eq!(&sig.to_compact()[..], &compact[..]);
+    }
+}
+
+#[test]
+fn der_encoding_matches_secp256k1() {
+    for _ in 0..20 {
+        let (pk, message, secp_sig) = secp256k1_signature();
+        let der = secp_sig.to_standard().serialize_der();
+
+        let sig = SignatureRecid::from_der(&der).unwrap();
+        verify(&sig, &pk, &message).unwrap();
+        assert_eq!(&sig.to_der()[..], &der[..]);
+    }
+}
+
+#[test]
+fn zero_s_is_rejected() {
+    let (pk, message, secp_sig) = secp256k1_signature();
+    let (_, compact) = secp_sig.serialize_compact();
+    let sig = SignatureRecid {
+        s: Scalar::zero(),
+        ..SignatureRecid::from_compact(&compact).unwrap()
+    };
+    assert!(matches!(
+        verify(&sig, &pk, &message),
+        Err(Error::InvalidSig)
+    ));
+}
+
+#[test]
+fn zero_r_is_rejected() {
+    let (pk, message, secp_sig) = secp256k1_signature();
+    let (_, compact) = secp_sig.serialize_compact();
+    let sig = SignatureRecid {
+        r: Scalar::zero(),
+        ..SignatureRecid::from_compact(&compact).unwrap()
+    };
+    assert!(matches!(
+        verify(&sig, &pk, &message),
+        Err(Error::InvalidSig)
+    ));
+}
+
+#[test]
+fn point_at_infinity_is_rejected() {
+    // with r = s = 1, u1·G + u2·Y = m·G + Y, which is infinity for Y = -m·G
+    let message = MessageDigest::sha256(b"message");
+    let m = Scalar::<Secp256k1>::from(&message.to_bigint());
+    let pk = Point::generator() * (Scalar::zero() - m);
+    let sig = SignatureRecid {
+        r: Scalar::from_bigint(&BigInt::from(1)),
+        s: Scalar::from_bigint(&BigInt::from(1)),
+        recid: 0,
+    };
+    assert!(matches!(
+        verify(&sig, &pk, &message),
+        Err(Error::InvalidSig)
+    ));
+}
+
+#[test]
+fn small_scalars_are_der_encoded_minimally() {
+    let sig = SignatureRecid {
+        r: Scalar::from_bigint(&BigInt::from(1)),
+        s: Scalar::from_bigint(&BigInt::from(0x80)),
+        recid: 0,
+    };
+    let der = sig.to_der();
+    assert_eq!(der, vec![0x30, 7, 0x02, 1, 1, 0x02, 2, 0, 0x80]);
+    let parsed = SignatureRecid::from_der(&der).unwrap();
+    assert_eq!((parsed.r, parsed.s), (sig.r, sig.s));
+}
+
+#[test]
+fn malformed_encodings_are_rejected() {
+    let (_, _, secp_sig) = secp256k1_signature();
+    let der = secp_sig.to_standard().serialize_der().to_vec();
+
+    let mut trailing = der.clone();
+    trailing.push(0);
+    assert!(SignatureRecid::from_der(&trailing).is_err());
+
+    // Non-minimal integer: r = 1 encoded with extra zero byte
+    let padded = [0x30, 7, 0x02, 2, 0, 1, 0x02, 1, 1];
+    assert_eq!(
+        SignatureRecid::from_der(&padded).unwrap_err(),
+        SignatureEncodingError::MalformedDer("integer is not minimal")
+    );
+    // Negative integer
+    let negative = [0x30, 6, 0x02, 1, 0x81, 0x02, 1, 1];
+    assert!(SignatureRecid::from_der(&negative).is_err());
+
+    assert_eq!(
+        SignatureRecid::from_compact(&[0u8; 64]).unwrap_err(),
+        SignatureEncodingError::ScalarOutOfRange("r")
+    );
+    let mut order = vec![0u8; 32];
+    order[31] = 1;
+    order.extend_from_slice(&Scalar::<Secp256k1>::group_order().to_bytes());
+    assert_eq!(
+        SignatureRecid::from_compact(&order).unwrap_err(),
+        SignatureEncodingError::ScalarOutOfRange("s")
+    );
+    assert!(SignatureRecid::from_compact(&[1u8; 63]).is_err());
+
+    let sig = SignatureRecid::from_compact(&[1u8; 64]).unwrap();
+    assert_eq!(sig.to_recoverable(255).unwrap()[64], 255);
+    let sig = SignatureRecid { recid: 1, ..sig };
+    assert_eq!(
+        sig.to_recoverable(255).unwrap_err(),
+        SignatureEncodingError::RecoveryByteOverflow {
+            recid: 1,
+            v_offset: 255
+        }
+    );
+
+    let mut recoverable = [1u8; 65];
+    recoverable[64] = 31;
+    assert_eq!(
+        SignatureRecid::from_recoverable(&recoverable, 27).unwrap_err(),
+        SignatureEncodingError::InvalidRecoveryByte {
+            v: 31,
+            v_offset: 27
+        }
+    );
+}
+
+#[cfg(feature = "secp256k1-compat")]
+#[test]
+fn converts_into_secp256k1_recoverable_signature() {
+    use std::convert::TryFrom;
+
+    let sk = Scalar::<Secp256k1>::random();
+    let message = [7u8; 32];
+    let secp_sig = SECP256K1.sign_ecdsa_recoverable(
+        &Message::from_slice(&message).unwrap(),
+        &SecretKey::from_slice(&sk.to_bytes()).unwrap(),
+    );
+    let (recid, compact) = secp_sig.serialize_compact();
+    let mut bytes = compact.to_vec();
+    bytes.push(recid.to_i32() as u8);
+    let sig = SignatureRecid::from_recoverable(&bytes, 0).unwrap();
+
+    let converted = ecdsa::RecoverableSignature::try_from(&sig).unwrap();
+    assert_eq!(converted, secp_sig);
+    let recovered = SECP256K1
+        .recover_ecdsa(&Message::from_slice(&message).unwrap(), &converted)
+        .unwrap();
+    let pk = Point::generator() * &sk;
+    assert_eq!(&recovered.serialize()[..], &pk.to_bytes(true)[..]);
+
+    let standard = ecdsa::Signature::try_from(&sig).unwrap();
+    assert_eq!(standard, secp_sig.to_standard());
+}
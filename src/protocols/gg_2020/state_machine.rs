@@ -1,3 +1,6 @@
+pub mod echo;
+pub mod import;
 pub mod keygen;
+pub mod recovery;
 pub mod sign;
 pub mod traits;
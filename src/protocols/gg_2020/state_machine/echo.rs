@@ -0,0 +1,129 @@
+//! Echo broadcast
+//!
+//! GG20 assumes reliable broadcast, whereas messages are delivered by a relay which (as well as
+//! a malicious sender) may send different versions of a "broadcast" message to different parties.
+//! When echo broadcast is enabled, every party records digests of all broadcast messages it
+//! sent and received, and once the protocol is completed, parties exchange the digests in an
+//! additional round. Mismatching digests reveal parties whose broadcast messages were not seen
+//! equally by everyone, and protocol output is discarded.
+
+use std::collections::BTreeMap;
+
+use round_based::containers::{self, push::Push, BroadcastMsgs, Store};
+use round_based::Msg;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+use crate::utilities::wire::wire_struct;
+
+/// Digests of broadcast messages sent by every party, as seen by the local party
+#[derive(Debug, Clone, Default)]
+pub struct EchoTranscript {
+    /// Maps `(sender, round)` to digest of message
+    digests: BTreeMap<(u16, u16), [u8; 32]>,
+}
+
+impl EchoTranscript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records broadcast message sent or received in protocol round
+    pub fn record<M>(&mut self, sender: u16, body: &M)
+    where
+        M: Serialize + RoundMessage,
+    {
+        let round = body.round();
+        // Protocol messages are always serializable
+        let serialized = serde_json::to_vec(body).expect("serialize broadcast message");
+        let digest = Sha256::new()
+            .chain(round.to_be_bytes())
+            .chain(&serialized)
+            .finalize();
+        self.digests.insert((sender, round), digest.into());
+    }
+
+    /// Summarizes the transcript of `n` parties into echo message
+    pub fn echo(&self, n: u16) -> EchoMessage {
+        let digests = (1..=n)
+            .map(|sender| {
+                let mut hasher = Sha256::new().chain(sender.to_be_bytes());
+                for ((_, round), digest) in self.digests.range((sender, 0)..=(sender, u16::MAX)) {
+                    hasher.update(round.to_be_bytes());
+                    hasher.update(digest);
+                }
+                hex::encode(hasher.finalize())
+            })
+            .collect();
+        EchoMessage { digests }
+    }
+}
+
+/// Digest of broadcast messages of every party (`digests[j-1]` corresponds to party `j`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EchoMessage {
+    pub digests: Vec<String>,
+}
+
+wire_struct!(EchoMessage { digests });
+
+/// Broadcast messages of listed parties were not seen equally by all the parties
+#[derive(Debug, Error, Clone, PartialEq)]
+#[error("parties {parties:?} equivocated: their broadcast messages were not received equally by everyone")]
+pub struct Equivocation {
+    pub parties: Vec<u16>,
+}
+
+/// Echo round which completes a protocol
+///
+/// Holds the protocol output until every party confirms it saw the same broadcast messages.
+pub struct EchoRound<O> {
+    output: O,
+    own_echo: EchoMessage,
+    n: u16,
+}
+
+impl<O> EchoRound<O> {
+    pub fn new<P>(output: O, transcript: &EchoTranscript, i: u16, n: u16, mut msgs: P) -> Self
+    where
+        P: Push<Msg<EchoMessage>>,
+    {
+        let own_echo = transcript.echo(n);
+        msgs.push(Msg {
+            sender: i,
+            receiver: None,
+            body: own_echo.clone(),
+        });
+        Self { output, own_echo, n }
+    }
+
+    pub fn proceed(self, input: BroadcastMsgs<EchoMessage>) -> Result<O, Equivocation> {
+        let echoes = input.into_vec_including_me(self.own_echo.clone());
+        let parties: Vec<u16> = (1..=self.n)
+            .filter(|&j| {
+                let own_digest = self.own_echo.digests.get(usize::from(j) - 1);
+                echoes
+                    .iter()
+                    .any(|echo| echo.digests.get(usize::from(j) - 1) != own_digest)
+            })
+            .collect();
+        if parties.is_empty() {
+            Ok(self.output)
+        } else {
+            Err(Equivocation { parties })
+        }
+    }
+
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+
+    pub fn expects_messages(i: u16, n: u16) -> Store<BroadcastMsgs<EchoMessage>> {
+        containers::BroadcastMsgsStore::new(i, n)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,105 @@
+use round_based::containers::{BroadcastMsgsStore, MessageStore};
+use round_based::Msg;
+use serde::{Deserialize, Serialize};
+
+use crate::protocols::gg_2020::state_machine::echo::{
+    EchoMessage, EchoRound, EchoTranscript, Equivocation,
+};
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct TestMsg {
+    round: u16,
+    payload: String,
+}
+
+impl RoundMessage for TestMsg {
+    fn round(&self) -> u16 {
+        self.round
+    }
+}
+
+fn msg(round: u16, payload: &str) -> TestMsg {
+    TestMsg {
+        round,
+        payload: payload.to_string(),
+    }
+}
+
+/// Builds transcripts of 3 parties, where party `equivocator` sent `payload` in round 2 to
+/// party 3 instead of original message
+fn transcripts(equivocator: Option<u16>) -> Vec<EchoTranscript> {
+    (1..=3)
+        .map(|receiver| {
+            let mut transcript = EchoTranscript::new();
+            for sender in 1..=3 {
+                transcript.record(sender, &msg(1, "commitment"));
+                let payload = if equivocator == Some(sender) && receiver == 3 && sender != 3 {
+                    "equivocated decommitment"
+                } else {
+                    "decommitment"
+                };
+                transcript.record(sender, &msg(2, payload));
+            }
+            transcript
+        })
+        .collect()
+}
+
+fn run_echo_round(transcripts: &[EchoTranscript]) -> Vec<Result<u16, Equivocation>> {
+    let n = transcripts.len() as u16;
+    let mut outgoing: Vec<Msg<EchoMessage>> = vec![];
+    let rounds: Vec<_> = (1..=n)
+        .map(|i| EchoRound::new(i, &transcripts[usize::from(i) - 1], i, n, &mut outgoing))
+        .collect();
+
+    (1..=n)
+        .zip(rounds)
+        .map(|(i, round)| {
+            let mut store = BroadcastMsgsStore::new(i, n);
+            for m in outgoing.iter().filter(|m| m.sender != i) {
+                store.push_msg(m.clone()).unwrap();
+            }
+            round.proceed(store.finish().unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn echo_round_completes_if_transcripts_match() {
+    let transcripts = transcripts(None);
+    assert_eq!(transcripts[0].echo(3), transcripts[2].echo(3));
+    for (i, output) in (1..).zip(run_echo_round(&transcripts)) {
+        assert_eq!(output, Ok(i));
+    }
+}
+
+#[test]
+fn equivocating_party_is_reported() {
+    let transcripts = transcripts(Some(2));
+    let echo_1 = transcripts[0].echo(3);
+    let echo_3 = transcripts[2].echo(3);
+    assert_eq!(echo_1.digests[0], echo_3.digests[0]);
+    assert_ne!(echo_1.digests[1], echo_3.digests[1]);
+
+    for output in run_echo_round(&transcripts) {
+        assert_eq!(output, Err(Equivocation { parties: vec![2] }));
+    }
+}
+
+#[test]
+fn missing_message_is_detected() {
+    let mut transcripts = transcripts(None);
+    let mut incomplete = EchoTranscript::new();
+    for sender in 1..=3 {
+        incomplete.record(sender, &msg(1, "commitment"));
+        if sender != 1 {
+            incomplete.record(sender, &msg(2, "decommitment"));
+        }
+    }
+    transcripts[1] = incomplete;
+
+    for output in run_echo_round(&transcripts) {
+        assert_eq!(output, Err(Equivocation { parties: vec![1] }));
+    }
+}
@@ -0,0 +1,159 @@
+//! Import of an existing private key into threshold [LocalKey]s
+//!
+//! A trusted dealer who knows the private key splits it with Feldman VSS (see
+//! [split_private_key](dealer::split_private_key)) and privately sends a share to every party.
+//! Then parties run a single-round protocol in which each of them generates its own Paillier key
+//! and `(N_tilde, h1, h2)`, and broadcasts them along with the same proofs as in keygen. Resulting
+//! [LocalKey]s have the same form as ones produced by [Keygen](super::keygen::Keygen) and
+//! correspond to the original public key, so addresses derived from it don't change.
+//!
+//! [LocalKey]: super::keygen::local_key::LocalKey
+
+pub mod dealer;
+pub mod error;
+pub mod messages;
+mod rounds;
+pub mod trait_impls;
+#[cfg(test)]
+mod test;
+
+use std::mem::replace;
+use std::time::Duration;
+
+use round_based::containers::push::{Push, PushExt};
+use round_based::containers::{BroadcastMsgs, MessageStore, Store};
+use round_based::Msg;
+
+use crate::utilities::session_id::SessionId;
+
+use self::dealer::ImportShare;
+use self::error::{ImportError, InternalError};
+use self::messages::{ImportBroadcast, ProtocolMessage, M};
+use self::rounds::{round_0::Round0, round_1::Round1, R};
+
+/// Party of key import protocol
+pub struct KeyImport {
+    round: R,
+
+    msgs1: Option<Store<BroadcastMsgs<ImportBroadcast>>>,
+
+    round_timeout: Option<Duration>,
+
+    msgs_queue: Vec<Msg<ProtocolMessage>>,
+
+    party_i: u16,
+    party_n: u16,
+}
+
+impl KeyImport {
+    /// Constructs a party of key import protocol
+    ///
+    /// Takes party index `i` (in range `[1; n]`) and `share` dealt to this party by
+    /// [split_private_key](dealer::split_private_key). Threshold `t` and number of parties `n`
+    /// are taken from dealer's commitments.
+    ///
+    /// `session_id` is bound into every proof sent during the protocol. All the parties must use
+    /// the same session id, and it must be unique for every protocol execution.
+    ///
+    /// Returns error if:
+    /// * `n` is less than 2, returns [ImportError::TooFewParties]
+    /// * `t` is not in range `[1; n-1]`, returns [ImportError::InvalidThreshold]
+    /// * `i` is not in range `[1; n]`, returns [ImportError::InvalidPartyIndex]
+    /// * `share` doesn't match dealer's commitments, returns [ImportError::InvalidShare]
+    pub fn new(i: u16, share: ImportShare, session_id: SessionId) -> Result<Self, ImportError> {
+        let t = share.vss.parameters.threshold;
+        let n = share.vss.parameters.share_count;
+        if n < 2 {
+            return Err(ImportError::TooFewParties);
+        }
+        if t == 0 || t >= n {
+            return Err(ImportError::InvalidThreshold);
+        }
+        if i == 0 || i > n {
+            return Err(ImportError::InvalidPartyIndex);
+        }
+        if share.vss.commitments.len() != usize::from(t) + 1
+            || share.vss.commitments[0].is_zero()
+            || share.vss.validate_share(&share.share, i).is_err()
+        {
+            return Err(ImportError::InvalidShare);
+        }
+
+        let mut state = Self {
+            round: R::Round0(Round0 {
+                i,
+                share,
+                session_id,
+            }),
+
+            msgs1: Some(Round1::expects_messages(i, n)),
+
+            round_timeout: None,
+
+            msgs_queue: vec![],
+
+            party_i: i,
+            party_n: n,
+        };
+
+        state.proceed_round(false)?;
+        Ok(state)
+    }
+
+    /// Sets a deadline for every round
+    ///
+    /// If party doesn't receive all messages of the round within `timeout`, import terminates
+    /// with [ImportError::Timeout] listing parties whose messages are missing.
+    pub fn with_round_timeout(mut self, timeout: Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    fn gmap_queue<'a, T, F>(&'a mut self, mut f: F) -> impl Push<Msg<T>> + 'a
+    where
+        F: FnMut(T) -> M + 'a,
+    {
+        (&mut self.msgs_queue).gmap(move |m: Msg<T>| m.map_body(|m| ProtocolMessage(f(m))))
+    }
+
+    /// Proceeds round state if it received enough messages and if it's cheap to compute or
+    /// `may_block == true`
+    fn proceed_round(&mut self, may_block: bool) -> Result<(), ImportError> {
+        let store1_wants_more = self.msgs1.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+
+        let next_state: R;
+        let try_again: bool = match replace(&mut self.round, R::Gone) {
+            R::Round0(round) if !round.is_expensive() || may_block => {
+                next_state = R::Round1(round.proceed(self.gmap_queue(M::Round1)));
+                true
+            }
+            s @ R::Round0(_) => {
+                next_state = s;
+                false
+            }
+            R::Round1(round) if !store1_wants_more && (!round.is_expensive() || may_block) => {
+                let store = self.msgs1.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveRoundMessages)?;
+                next_state = round.proceed(msgs).map(R::Final)?;
+                true
+            }
+            s @ R::Round1(_) => {
+                next_state = s;
+                false
+            }
+            s @ R::Final(_) | s @ R::Gone => {
+                next_state = s;
+                false
+            }
+        };
+
+        self.round = next_state;
+        if try_again {
+            self.proceed_round(may_block)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::{Scalar, Secp256k1};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::protocols::gg_2020::state_machine::import::error::ImportError;
+use crate::utilities::fingerprint::Fingerprint;
+use crate::utilities::wire::wire_struct;
+
+/// Share of the imported key, dealt to a single party
+///
+/// Contains a secret value, so it must be delivered to the party over a private and
+/// authenticated channel.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ImportShare {
+    /// Commitments to the coefficients of the dealer's polynomial, identical for every party
+    pub vss: VerifiableSS<Secp256k1>,
+    /// Value of the polynomial at the index of the party
+    pub share: Scalar<Secp256k1>,
+}
+
+wire_struct!(ImportShare { vss, share });
+
+impl Zeroize for ImportShare {
+    fn zeroize(&mut self) {
+        self.share = Scalar::zero();
+    }
+}
+
+impl Drop for ImportShare {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for ImportShare {}
+
+impl fmt::Debug for ImportShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ImportShare")
+            .field("vss", &self.vss)
+            .field("share", &Fingerprint::of_scalar(&self.share))
+            .finish()
+    }
+}
+
+/// Splits existing private key into `n` shares with threshold `t` using Feldman VSS
+///
+/// `i`-th share (counting from 1) must be given to the party with index `i`. The dealer learns
+/// nothing new, but it knows the full key, so it should erase the key once shares are delivered.
+///
+/// Returns error if:
+/// * `n` is less than 2, returns [ImportError::TooFewParties]
+/// * `t` is not in range `[1; n-1]`, returns [ImportError::InvalidThreshold]
+/// * `private_key` is zero, returns [ImportError::ZeroPrivateKey]
+pub fn split_private_key(
+    private_key: &Scalar<Secp256k1>,
+    t: u16,
+    n: u16,
+) -> Result<Vec<ImportShare>, ImportError> {
+    if n < 2 {
+        return Err(ImportError::TooFewParties);
+    }
+    if t == 0 || t >= n {
+        return Err(ImportError::InvalidThreshold);
+    }
+    if private_key.is_zero() {
+        return Err(ImportError::ZeroPrivateKey);
+    }
+
+    let (vss, shares) = VerifiableSS::share(t, n, private_key);
+    Ok(shares
+        .iter()
+        .map(|share| ImportShare {
+            vss: vss.clone(),
+            share: share.clone(),
+        })
+        .collect())
+}
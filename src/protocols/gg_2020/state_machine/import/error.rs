@@ -0,0 +1,75 @@
+use round_based::{containers::StoreErr, IsCritical};
+use thiserror::Error;
+
+/// Error type of key import
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ImportError {
+    /// Too few parties (`n < 2`)
+    #[error("at least 2 parties are required for key import")]
+    TooFewParties,
+    /// Threshold value `t` is not in range `[1; n-1]`
+    #[error("threshold is not in range [1; n-1]")]
+    InvalidThreshold,
+    /// Party index `i` is not in range `[1; n]`
+    #[error("party index is not in range [1; n]")]
+    InvalidPartyIndex,
+    /// Imported private key is zero
+    #[error("private key is zero")]
+    ZeroPrivateKey,
+    /// Share received from the dealer doesn't match dealer's commitments
+    #[error("share doesn't match dealer's commitments")]
+    InvalidShare,
+
+    /// Some parties received different commitments from the dealer
+    #[error("parties {parties:?} received different commitments from the dealer")]
+    VssMismatch { parties: Vec<u16> },
+    /// Some parties sent invalid proofs of correctness of Paillier key or `(N_tilde, h1, h2)`
+    #[error("parties {parties:?} sent invalid key proofs")]
+    InvalidKeyProofs { parties: Vec<u16> },
+
+    /// Received message didn't pass pre-validation
+    #[error("received message didn't pass pre-validation: {0}")]
+    HandleMessage(#[source] StoreErr),
+    /// Received message which we didn't expect to receive now (e.g. message from previous round)
+    #[error(
+        "didn't expect to receive message from round {msg_round} (being at round {current_round})"
+    )]
+    ReceivedOutOfOrderMessage { current_round: u16, msg_round: u16 },
+    /// Party didn't receive all messages of the round in time
+    #[error("round {round} timed out, missing messages from parties {missing_parties:?}")]
+    Timeout {
+        round: u16,
+        missing_parties: Vec<u16>,
+    },
+    /// `pick_output` called twice
+    #[error("pick_output called twice")]
+    DoublePickOutput,
+
+    /// Some internal assertions were failed, which is a bug
+    #[doc(hidden)]
+    #[error("internal error: {0:?}")]
+    InternalError(InternalError),
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InternalError {
+    /// Messages store reported that it received all messages it wanted to receive, but refused
+    /// to return message container
+    RetrieveRoundMessages(StoreErr),
+    #[doc(hidden)]
+    StoreGone,
+}
+
+impl IsCritical for ImportError {
+    fn is_critical(&self) -> bool {
+        true
+    }
+}
+
+impl From<InternalError> for ImportError {
+    fn from(err: InternalError) -> Self {
+        Self::InternalError(err)
+    }
+}
@@ -0,0 +1,82 @@
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::Secp256k1;
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+use zk_paillier::zkproofs::{DLogStatement, NiCorrectKeyProof};
+
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+use crate::utilities::wire::{self, wire_struct, Reader, Wire, WireError};
+use crate::utilities::zk_sigma::CompositeDLogProof;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolMessage(pub(super) M);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) enum M {
+    Round1(ImportBroadcast),
+}
+
+/// Paillier key and `(N_tilde, h1, h2)` of the party with the same proofs as in
+/// [KeyGenBroadcast](crate::protocols::gg_2020::state_machine::keygen::messages::broadcast::KeyGenBroadcast),
+/// along with dealer's commitments received by the party
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportBroadcast {
+    pub e: EncryptionKey,
+    pub dlog_statement: DLogStatement,
+    pub correct_key_proof: NiCorrectKeyProof,
+    pub composite_dlog_proof_base_h1: CompositeDLogProof,
+    pub composite_dlog_proof_base_h2: CompositeDLogProof,
+    pub vss: VerifiableSS<Secp256k1>,
+}
+
+wire_struct!(ImportBroadcast {
+    e,
+    dlog_statement,
+    correct_key_proof,
+    composite_dlog_proof_base_h1,
+    composite_dlog_proof_base_h2,
+    vss,
+});
+
+impl RoundMessage for ProtocolMessage {
+    fn round(&self) -> u16 {
+        match self.0 {
+            M::Round1(_) => 1,
+        }
+    }
+}
+
+impl ProtocolMessage {
+    /// Encodes the message in compact binary format, see [wire](crate::utilities::wire)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        wire::to_bytes(self)
+    }
+
+    /// Decodes the message produced by [to_bytes](Self::to_bytes), rejecting non-canonical input
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        wire::from_bytes(bytes)
+    }
+}
+
+/// Tag of every message is the number of its round
+impl Wire for ProtocolMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.round() as u8);
+        match &self.0 {
+            M::Round1(m) => m.encode(out),
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let m = match reader.take_tag()? {
+            1 => M::Round1(Wire::decode(reader)?),
+            tag => {
+                return Err(WireError::UnknownTag {
+                    ty: "key import message",
+                    tag,
+                })
+            }
+        };
+        Ok(ProtocolMessage(m))
+    }
+}
@@ -0,0 +1,13 @@
+pub mod round_0;
+pub mod round_1;
+
+use curv::elliptic::curves::Secp256k1;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+
+pub enum R {
+    Round0(round_0::Round0),
+    Round1(round_1::Round1),
+    Final(LocalKey<Secp256k1>),
+    Gone,
+}
@@ -0,0 +1,56 @@
+use round_based::containers::push::Push;
+use round_based::Msg;
+
+use crate::protocols::gg_2020::state_machine::import::dealer::ImportShare;
+use crate::protocols::gg_2020::state_machine::import::messages::ImportBroadcast;
+use crate::protocols::gg_2020::state_machine::import::rounds::round_1::Round1;
+use crate::protocols::gg_2020::state_machine::keygen::party_i::keys::Keys;
+use crate::utilities::session_id::SessionId;
+
+pub struct Round0 {
+    /// Index of this party in range `[1; n]`
+    pub i: u16,
+    /// Share received from the dealer, already checked against dealer's commitments
+    pub share: ImportShare,
+    pub session_id: SessionId,
+}
+
+impl Round0 {
+    pub fn proceed<O>(self, mut output: O) -> Round1
+    where
+        O: Push<Msg<ImportBroadcast>>,
+    {
+        let keys = Keys::create_safe_prime_from(self.share.share.clone(), usize::from(self.i));
+        let (
+            dlog_statement,
+            correct_key_proof,
+            composite_dlog_proof_base_h1,
+            composite_dlog_proof_base_h2,
+        ) = keys.proof_of_correct_key_proof_of_correct_h1h2(&self.session_id);
+        let broadcast = ImportBroadcast {
+            e: keys.paillier_keys.ek.clone(),
+            dlog_statement,
+            correct_key_proof,
+            composite_dlog_proof_base_h1,
+            composite_dlog_proof_base_h2,
+            vss: self.share.vss.clone(),
+        };
+
+        output.push(Msg {
+            sender: self.i,
+            receiver: None,
+            body: broadcast.clone(),
+        });
+
+        Round1 {
+            i: self.i,
+            keys,
+            share: self.share,
+            broadcast,
+            session_id: self.session_id,
+        }
+    }
+    pub fn is_expensive(&self) -> bool {
+        true
+    }
+}
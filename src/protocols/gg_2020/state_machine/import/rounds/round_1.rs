@@ -0,0 +1,115 @@
+use std::collections::{BTreeSet, HashMap};
+
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::{Point, Secp256k1};
+use round_based::containers::{self, BroadcastMsgs, Store};
+
+use crate::protocols::gg_2020::state_machine::import::dealer::ImportShare;
+use crate::protocols::gg_2020::state_machine::import::error::ImportError;
+use crate::protocols::gg_2020::state_machine::import::messages::ImportBroadcast;
+use crate::protocols::gg_2020::state_machine::keygen::{
+    local_key::LocalKey,
+    messages::parameters::Parameters,
+    party_i::keys::Keys,
+    party_i::party_to_point_map::PartyToPointMap,
+    party_i::shared_keys::SharedKeys,
+};
+use crate::utilities::session_id::SessionId;
+
+pub struct Round1 {
+    pub(super) i: u16,
+    pub(super) keys: Keys,
+    pub(super) share: ImportShare,
+    pub(super) broadcast: ImportBroadcast,
+    pub(super) session_id: SessionId,
+}
+
+impl Round1 {
+    pub fn proceed(
+        self,
+        input: BroadcastMsgs<ImportBroadcast>,
+    ) -> Result<LocalKey<Secp256k1>, ImportError> {
+        let broadcasts = input.into_vec_including_me(self.broadcast);
+        let vss = &self.share.vss;
+
+        // Dealer must have sent the same commitments to everyone, otherwise shares don't lie
+        // on a single polynomial
+        let vss_mismatch: Vec<u16> = (1..)
+            .zip(&broadcasts)
+            .filter(|(_, b)| !same_vss(&b.vss, vss))
+            .map(|(j, _)| j)
+            .collect();
+        if !vss_mismatch.is_empty() {
+            return Err(ImportError::VssMismatch {
+                parties: vss_mismatch,
+            });
+        }
+
+        let invalid_proofs: Vec<u16> = (1..)
+            .zip(&broadcasts)
+            .filter(|(_, b)| {
+                !Keys::verify_correct_key_verify_correct_h1h2(
+                    &b.e,
+                    &b.dlog_statement,
+                    &b.correct_key_proof,
+                    &b.composite_dlog_proof_base_h1,
+                    &b.composite_dlog_proof_base_h2,
+                    &self.session_id,
+                )
+            })
+            .map(|(j, _)| j)
+            .collect();
+        if !invalid_proofs.is_empty() {
+            return Err(ImportError::InvalidKeyProofs {
+                parties: invalid_proofs,
+            });
+        }
+
+        let t = vss.parameters.threshold;
+        let n = vss.parameters.share_count;
+        let own_party_index = usize::from(self.i);
+        let public_key = vss.commitments[0].clone();
+        let pk_vec = (1..=n)
+            .map(|j| vss.get_point_commitment(j))
+            .collect::<Vec<Point<Secp256k1>>>();
+        let points = (1..=usize::from(n)).map(|j| (j, j)).collect::<HashMap<_, _>>();
+
+        Ok(LocalKey {
+            paillier_dk: self.keys.paillier_keys.dk.clone(),
+            pk_vec,
+
+            keys_linear: SharedKeys {
+                y: public_key.clone(),
+                x_i: self.share.share.clone(),
+            },
+            paillier_key_vec: broadcasts.iter().map(|b| b.e.clone()).collect(),
+            h1_h2_n_tilde_vec: broadcasts
+                .iter()
+                .map(|b| b.dlog_statement.clone())
+                .collect(),
+
+            vss_scheme: vss.clone(),
+
+            own_party_index,
+            other_parties: (1..=usize::from(n))
+                .filter(|j| *j != own_party_index)
+                .collect::<BTreeSet<_>>(),
+            public_key,
+            key_params: Parameters::new(t, n),
+            secret_share: (own_party_index, self.share.share.clone()),
+            party_to_point_map: PartyToPointMap { points },
+        })
+    }
+    pub fn is_expensive(&self) -> bool {
+        true
+    }
+    pub fn expects_messages(i: u16, n: u16) -> Store<BroadcastMsgs<ImportBroadcast>> {
+        containers::BroadcastMsgsStore::new(i, n)
+    }
+}
+
+fn same_vss(a: &VerifiableSS<Secp256k1>, b: &VerifiableSS<Secp256k1>) -> bool {
+    a.parameters.threshold == b.parameters.threshold
+        && a.parameters.share_count == b.parameters.share_count
+        && a.commitments == b.commitments
+}
@@ -0,0 +1,151 @@
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use round_based::dev::Simulation;
+use round_based::StateMachine;
+
+use crate::integrations::ethereum::local_key_address;
+use crate::protocols::gg_2020::party_i::verify;
+use crate::protocols::gg_2020::state_machine::import::{
+    dealer::{split_private_key, ImportShare},
+    error::ImportError,
+    KeyImport,
+};
+use crate::protocols::gg_2020::state_machine::keygen::local_key::{
+    reconstruction::reconstruct_private_key, LocalKey,
+};
+use crate::protocols::gg_2020::state_machine::sign::stages::sign_manual::SignManual;
+use crate::protocols::gg_2020::state_machine::sign::test::simulate_offline_stage;
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
+
+pub fn simulate_import(shares: Vec<ImportShare>) -> Vec<LocalKey<Secp256k1>> {
+    let mut simulation = Simulation::new();
+
+    let session_id = SessionId::random();
+    for (i, share) in (1..).zip(shares) {
+        simulation.add_party(KeyImport::new(i, share, session_id).unwrap());
+    }
+
+    simulation.run().unwrap()
+}
+
+#[test]
+fn imported_key_signs_under_original_public_key() {
+    let private_key = Scalar::<Secp256k1>::random();
+    let public_key = Point::generator() * &private_key;
+    let local_keys = simulate_import(split_private_key(&private_key, 1, 3).unwrap());
+
+    for local_key in &local_keys {
+        assert_eq!(local_key.public_key, public_key);
+        assert!(local_key.validate().is_valid(), "{}", local_key.validate());
+    }
+    assert_eq!(reconstruct_private_key(&local_keys[1..]).unwrap(), private_key);
+
+    let message = MessageDigest::sha256(b"imported");
+    let offline = simulate_offline_stage(local_keys, &[3, 1]);
+    let (parties, partial_signatures): (Vec<_>, Vec<_>) = offline
+        .into_iter()
+        .map(|o| SignManual::new(message, o).unwrap())
+        .unzip();
+    for (i, party) in parties.into_iter().enumerate() {
+        let others: Vec<_> = partial_signatures
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, s)| s.clone())
+            .collect();
+        let signature = party.complete(&others).unwrap();
+        assert!(verify(&signature, &public_key, &message).is_ok());
+    }
+}
+
+#[test]
+fn address_is_preserved() {
+    // Private key 1 corresponds to the well-known address of the generator point
+    let private_key = Scalar::<Secp256k1>::from_bigint(&BigInt::one());
+    let local_keys = simulate_import(split_private_key(&private_key, 1, 2).unwrap());
+    for local_key in &local_keys {
+        assert_eq!(
+            local_key_address(local_key),
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+        );
+    }
+}
+
+#[test]
+fn dealer_rejects_invalid_parameters() {
+    let private_key = Scalar::<Secp256k1>::random();
+    assert!(matches!(
+        split_private_key(&private_key, 1, 1),
+        Err(ImportError::TooFewParties)
+    ));
+    assert!(matches!(
+        split_private_key(&private_key, 0, 3),
+        Err(ImportError::InvalidThreshold)
+    ));
+    assert!(matches!(
+        split_private_key(&private_key, 3, 3),
+        Err(ImportError::InvalidThreshold)
+    ));
+    assert!(matches!(
+        split_private_key(&Scalar::zero(), 1, 3),
+        Err(ImportError::ZeroPrivateKey)
+    ));
+}
+
+#[test]
+fn party_rejects_share_not_matching_commitments() {
+    let private_key = Scalar::<Secp256k1>::random();
+    let shares = split_private_key(&private_key, 1, 3).unwrap();
+    let session_id = SessionId::random();
+
+    assert!(matches!(
+        KeyImport::new(2, shares[0].clone(), session_id),
+        Err(ImportError::InvalidShare)
+    ));
+    assert!(matches!(
+        KeyImport::new(4, shares[0].clone(), session_id),
+        Err(ImportError::InvalidPartyIndex)
+    ));
+
+    let mut tampered = shares[0].clone();
+    tampered.share = &tampered.share + Scalar::from_bigint(&BigInt::one());
+    assert!(matches!(
+        KeyImport::new(1, tampered, session_id),
+        Err(ImportError::InvalidShare)
+    ));
+}
+
+#[test]
+fn equivocating_dealer_is_detected() {
+    let private_key = Scalar::<Secp256k1>::random();
+    let shares_a = split_private_key(&private_key, 1, 2).unwrap();
+    let shares_b = split_private_key(&private_key, 1, 2).unwrap();
+    let session_id = SessionId::random();
+
+    // Every share is valid on its own, but they come from different polynomials
+    let mut parties = vec![
+        KeyImport::new(1, shares_a[0].clone(), session_id).unwrap(),
+        KeyImport::new(2, shares_b[1].clone(), session_id).unwrap(),
+    ];
+    for party in parties.iter_mut() {
+        party.proceed().unwrap();
+    }
+    let msgs: Vec<_> = parties
+        .iter_mut()
+        .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+        .collect();
+    for party in parties.iter_mut() {
+        for msg in msgs.iter().filter(|m| m.sender != party.party_ind()) {
+            party.handle_incoming(msg.clone()).unwrap();
+        }
+    }
+
+    let err = parties[0].proceed().unwrap_err();
+    assert!(
+        matches!(&err, ImportError::VssMismatch { parties } if parties == &[2]),
+        "{:?}",
+        err
+    );
+}
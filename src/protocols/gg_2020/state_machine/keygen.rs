@@ -8,7 +8,7 @@ pub mod types;
 #[cfg(test)]
 pub mod test;
 
-use std::{mem::replace, collections::BTreeSet};
+use std::{mem::replace, collections::BTreeSet, time::Duration};
 use round_based::{
     Msg,
     containers::{
@@ -17,10 +17,13 @@ use round_based::{
     },
 };
 
+use crate::protocols::gg_2020::state_machine::echo::{EchoMessage, EchoTranscript};
+use crate::utilities::session_id::SessionId;
 use crate::protocols::gg_2020::state_machine::keygen::{
     error::{
         internal_error::InternalError,
         keygen_error::KeygenError,
+        keygen_round_error::KeygenRoundError,
     },
     messages::{
         ProtocolMessage,
@@ -34,6 +37,7 @@ use crate::protocols::gg_2020::state_machine::keygen::{
     types::KeygenResult,
     rounds::{
         R,
+        EchoRound,
         round_0::Round0, 
         round_1::Round1, 
         round_2::Round2, 
@@ -49,6 +53,12 @@ pub struct Keygen {
     msgs2: Option<Store<BroadcastMsgs<KeyGenDecommit>>>,
     msgs3: Option<Store<P2PMsgs<FeldmanVSS>>>,
     msgs4: Option<Store<BroadcastMsgs<Proof>>>,
+    msgs_echo: Option<Store<BroadcastMsgs<EchoMessage>>>,
+
+    echo: Option<EchoTranscript>,
+    echo_broadcast: bool,
+
+    round_timeout: Option<Duration>,
 
     msgs_queue: Vec<Msg<ProtocolMessage>>,
 
@@ -63,11 +73,14 @@ impl Keygen {
     /// parties `n`. Party index identifies this party in the protocol, so it must be guaranteed
     /// to be unique.
     ///
+    /// `session_id` is bound into every proof and commitment sent during the protocol. All the
+    /// parties must use the same session id, and it must be unique for every protocol execution.
+    ///
     /// Returns error if:
     /// * `n` is less than 2, returns [Error::TooFewParties]
     /// * `t` is not in range `[1; n-1]`, returns [Error::InvalidThreshold]
     /// * `i` is not in range `[1; n]`, returns [Error::InvalidPartyIndex]
-    pub fn new(i: u16, t: u16, n: u16) -> KeygenResult<Self> {
+    pub fn new(i: u16, t: u16, n: u16, session_id: SessionId) -> KeygenResult<Self> {
         if n < 2 {
             return Err(KeygenError::TooFewParties);
         }
@@ -85,13 +98,20 @@ impl Keygen {
             round: R::Round0(Round0 { 
                 own_party_index: i as usize, 
                 key_params: Parameters::new(t, n),
-                other_parties
+                other_parties,
+                session_id,
             }),
 
             msgs1: Some(Round1::expects_messages(i, n)),
             msgs2: Some(Round2::expects_messages(i, n)),
             msgs3: Some(Round3::expects_messages(i, n)),
             msgs4: Some(Round4::expects_messages(i, n)),
+            msgs_echo: None,
+
+            echo: None,
+            echo_broadcast: false,
+
+            round_timeout: None,
 
             msgs_queue: vec![],
 
@@ -103,6 +123,41 @@ impl Keygen {
         Ok(state)
     }
 
+    /// Enables echo broadcast
+    ///
+    /// Adds an extra round in which parties exchange digests of all broadcast messages they
+    /// received. If any party received different broadcast messages than others (because sender
+    /// or relay equivocated), keygen fails with [KeygenRoundError::Equivocation] listing parties
+    /// whose messages were inconsistent. All parties must agree on enabling echo broadcast.
+    ///
+    /// Must be called right after [Keygen::new], before any message is taken from the queue.
+    pub fn with_echo_broadcast(mut self) -> Self {
+        self.msgs_echo = Some(EchoRound::expects_messages(self.party_i, self.party_n));
+        self.echo = Some(EchoTranscript::new());
+        self.echo_broadcast = true;
+        self.record_own_broadcasts(0);
+        self
+    }
+
+    /// Sets a deadline for every round
+    ///
+    /// If party doesn't receive all messages of the round within `timeout`, keygen terminates
+    /// with [KeygenError::Timeout] listing parties whose messages are missing.
+    pub fn with_round_timeout(mut self, timeout: Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    /// Records broadcast messages queued starting from `from` into echo transcript
+    fn record_own_broadcasts(&mut self, from: usize) {
+        if let Some(echo) = self.echo.as_mut() {
+            self.msgs_queue[from..]
+                .iter()
+                .filter(|m| m.receiver.is_none() && !matches!(m.body.0, M::Echo(_)))
+                .for_each(|m| echo.record(m.sender, &m.body));
+        }
+    }
+
     fn gmap_queue<'a, T, F>(&'a mut self, mut f: F) -> impl Push<Msg<T>> + 'a
     where
         F: FnMut(T) -> M + 'a,
@@ -117,7 +172,9 @@ impl Keygen {
         let store2_wants_more = self.msgs2.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store3_wants_more = self.msgs3.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store4_wants_more = self.msgs4.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store_echo_wants_more = self.msgs_echo.as_ref().map(|s| s.wants_more()).unwrap_or(false);
 
+        let queued = self.msgs_queue.len();
         let next_state: R;
         let try_again: bool = match replace(&mut self.round, R::Gone) {
             R::Round0(round) if !round.is_expensive() || may_block => {
@@ -181,16 +238,37 @@ impl Keygen {
                 let msgs = store
                     .finish()
                     .map_err(InternalError::RetrieveRoundMessages)?;
-                next_state = round
+                let local_key = round
                     .proceed(msgs)
-                    .map(R::Final)
                     .map_err(KeygenError::ProceedRound)?;
+                next_state = match self.echo.take() {
+                    Some(echo) => {
+                        let (i, n) = (self.party_i, self.party_n);
+                        R::Echo(EchoRound::new(local_key, &echo, i, n, self.gmap_queue(M::Echo)))
+                    }
+                    None => R::Final(local_key),
+                };
                 true
             }
             s @ R::Round4(_) => {
                 next_state = s;
                 false
             }
+            R::Echo(round) if !store_echo_wants_more => {
+                let store = self.msgs_echo.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveRoundMessages)?;
+                next_state = round
+                    .proceed(msgs)
+                    .map(R::Final)
+                    .map_err(|e| KeygenError::ProceedRound(KeygenRoundError::Equivocation(e)))?;
+                true
+            }
+            s @ R::Echo(_) => {
+                next_state = s;
+                false
+            }
             s @ R::Final(_) | s @ R::Gone => {
                 next_state = s;
                 false
@@ -198,6 +276,7 @@ impl Keygen {
         };
 
         self.round = next_state;
+        self.record_own_broadcasts(queued);
         if try_again {
             self.proceed_round(may_block)
         } else {
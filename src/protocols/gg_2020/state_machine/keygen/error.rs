@@ -1,3 +1,4 @@
 pub mod keygen_round_error;
 pub mod keygen_error;
-pub mod internal_error;
\ No newline at end of file
+pub mod internal_error;
+pub mod local_key_error;
\ No newline at end of file
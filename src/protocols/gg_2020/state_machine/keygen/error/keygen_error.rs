@@ -35,6 +35,19 @@ pub enum KeygenError {
         "didn't expect to receive message from round {msg_round} (being at round {current_round})"
     )]
     ReceivedOutOfOrderMessage { current_round: u16, msg_round: u16 },
+    /// Sender written in message body differs from the party message was received from
+    #[error("message of round {msg_round} from party {sender} claims to be sent by party {claimed}")]
+    SenderMismatch {
+        msg_round: u16,
+        sender: u16,
+        claimed: usize,
+    },
+    /// Party didn't receive all messages of the round in time, see [Keygen::with_round_timeout]
+    #[error("round {round} timed out, missing messages from parties {missing_parties:?}")]
+    Timeout {
+        round: u16,
+        missing_parties: Vec<u16>,
+    },
     /// [Keygen::pick_output] called twice
     #[error("pick_output called twice")]
     DoublePickOutput,
@@ -1,5 +1,6 @@
 use thiserror::Error;
 
+use crate::protocols::gg_2020::state_machine::echo::Equivocation;
 use crate::protocols::gg_2020::ErrorType;
 
 
@@ -11,4 +12,6 @@ pub enum KeygenRoundError {
     Round3VerifyVssConstruct(ErrorType),
     #[error("round 4: verify dlog proof: {0:?}")]
     Round4VerifyDLogProof(ErrorType),
+    #[error("echo round: {0}")]
+    Equivocation(#[source] Equivocation),
 }
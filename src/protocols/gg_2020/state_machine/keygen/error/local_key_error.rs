@@ -0,0 +1,142 @@
+use thiserror::Error;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::validation::ValidationReport;
+
+/// Single inconsistency found by [LocalKey::validate](crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey::validate)
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LocalKeyIssue {
+    /// `key_params.threshold` is not in range `[1; n-1]`
+    #[error("threshold t={t} is not in range [1; n-1] for n={n}")]
+    InvalidKeyParams { t: u16, n: u16 },
+    /// `own_party_index` is not in range `[1; n]`
+    #[error("own party index {index} is not in range [1; {n}]")]
+    InvalidOwnPartyIndex { index: usize, n: u16 },
+    /// `other_parties` is not exactly `[1; n]` without own party index
+    #[error("other_parties doesn't list every party except own one")]
+    InvalidOtherParties,
+    /// One of per-party vectors doesn't have `n` entries
+    #[error("{field} has {actual} entries, expected {expected}")]
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    /// `vss_scheme` was produced for different `(t, n)`
+    #[error("vss_scheme parameters t={t}, n={n} disagree with key_params")]
+    VssParamsMismatch { t: u16, n: u16 },
+    /// `g^{x_i}` is not equal to `pk_vec[own_party_index-1]`
+    #[error("g^x_i doesn't match pk_vec entry of party {party}")]
+    SharePublicKeyMismatch { party: usize },
+    /// `keys_linear.y` is not equal to `public_key`
+    #[error("keys_linear.y doesn't match public_key")]
+    SharedPublicKeyMismatch,
+    /// Lagrange interpolation of `pk_vec` at zero doesn't give `public_key`
+    #[error("pk_vec doesn't interpolate to public_key")]
+    PublicKeyInterpolation,
+    /// `pk_vec` entry of the party doesn't lie on the polynomial defined by first `t+1` entries
+    #[error("pk_vec entry of party {party} is inconsistent with a degree-t polynomial")]
+    PkVecInconsistent { party: usize },
+    /// `paillier_dk` doesn't correspond to own entry of `paillier_key_vec`
+    #[error("paillier_dk doesn't match paillier_key_vec entry of party {party}")]
+    PaillierKeyMismatch { party: usize },
+    /// Party is not mapped to any point in `party_to_point_map`
+    #[error("party {party} is missing in party_to_point_map")]
+    PartyNotMapped { party: usize },
+    /// `party_to_point_map` contains a party which didn't take part in keygen
+    #[error("party_to_point_map contains unknown party {party}")]
+    UnknownPartyMapped { party: usize },
+    /// Point is zero or is shared by several parties
+    #[error("party {party} is mapped to invalid point {point}")]
+    InvalidPoint { party: usize, point: usize },
+    /// `secret_share` is evaluated at a point different from the one own party is mapped to
+    #[error("secret_share is evaluated at point {actual}, expected {expected}")]
+    SecretSharePointMismatch { expected: usize, actual: usize },
+}
+
+/// Error of [reconstruct_private_key](crate::protocols::gg_2020::state_machine::keygen::local_key::reconstruction::reconstruct_private_key)
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ReconstructionError {
+    /// No local keys were given
+    #[error("no local keys given")]
+    NoShares,
+    /// Several local keys belong to the same party
+    #[error("several local keys belong to party {party}")]
+    DuplicateParty { party: usize },
+    /// Shares of listed parties are invalid or don't belong to the same key as the others
+    #[error("shares of parties {parties:?} are inconsistent")]
+    InconsistentShares { parties: Vec<usize> },
+    /// Fewer than `t+1` local keys were given
+    #[error("at least {required} local keys are required, got {actual}")]
+    TooFewShares { required: usize, actual: usize },
+    /// Reconstructed private key doesn't correspond to the public key
+    #[error("reconstructed private key doesn't match public key")]
+    PublicKeyMismatch,
+}
+
+/// Error of making, verifying or restoring [ShareBackup](crate::protocols::gg_2020::state_machine::keygen::local_key::backup::ShareBackup)
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum BackupError {
+    /// Recovery public key is the point at infinity
+    #[error("recovery key is zero")]
+    ZeroRecoveryKey,
+    /// Party index of the backup is not in range `[1; n]`
+    #[error("party index {party} is not in range [1; {n}]")]
+    UnknownParty { party: usize, n: usize },
+    /// Backup was made for a different `pk_vec` than the one it's verified against
+    #[error("backup doesn't match given pk_vec")]
+    PkVecMismatch,
+    /// Proof that ciphertext encrypts discrete log of the `pk_vec` entry doesn't verify
+    #[error("proof of encrypted share is invalid")]
+    InvalidProof,
+    /// Ciphertext can't be decrypted, most likely the recovery key is wrong
+    #[error("couldn't decrypt the share")]
+    Decryption,
+    /// Decrypted share doesn't correspond to `pk_vec` entry of the party
+    #[error("decrypted share doesn't match pk_vec entry of party {party}")]
+    ShareMismatch { party: usize },
+    /// Public data of the backup doesn't make up a consistent local key
+    #[error("restored local key is invalid: {0}")]
+    InvalidLocalKey(ValidationReport),
+}
+
+/// Error of applying [PaillierKeysUpdate](crate::protocols::gg_2020::state_machine::keygen::local_key::paillier_update::PaillierKeysUpdate)
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PaillierKeysUpdateError {
+    /// Update comes from a party which is not in range `[1; n]`
+    #[error("party index {party} is not in range [1; {n}]")]
+    UnknownParty { party: usize, n: usize },
+    /// Update replaces own keys of the party, which requires a new `paillier_dk` as well
+    #[error("update replaces own paillier key")]
+    OwnParty,
+    /// Proofs of correctness of the keys don't verify
+    #[error("keys of party {party} are not proven to be correct")]
+    InvalidProofs { party: usize },
+    /// Update doesn't prove knowledge of the share behind `pk_vec` entry of the party
+    #[error("update is not proven to come from the holder of share of party {party}")]
+    NotShareHolder { party: usize },
+}
+
+/// Error of encoding or decoding [LocalKeyFile](crate::protocols::gg_2020::state_machine::keygen::local_key::versioned::LocalKeyFile)
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum LocalKeyFileError {
+    /// File is neither a valid versioned key file nor a key in legacy layout
+    #[error("malformed key file: {0}")]
+    Malformed(#[source] serde_json::Error),
+    /// Key was produced by a protocol this library doesn't implement
+    #[error("key file belongs to unsupported protocol {0:?}")]
+    UnsupportedProtocol(String),
+    /// Key is defined over a curve this library doesn't support
+    #[error("key file is defined over unsupported curve {0:?}")]
+    UnsupportedCurve(String),
+    /// Key file was written by a newer version of the library
+    #[error("key file version {0} is not supported")]
+    UnsupportedVersion(u32),
+    /// Field is not properly encoded
+    #[error("field {field} is malformed")]
+    InvalidField { field: &'static str },
+}
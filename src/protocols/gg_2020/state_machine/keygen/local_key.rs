@@ -1,10 +1,17 @@
+pub mod backup;
+pub mod paillier_update;
+pub mod reconstruction;
+pub mod validation;
+pub mod versioned;
+
 use std::collections::BTreeSet;
 
 use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
-use curv::elliptic::curves::{secp256_k1::Secp256k1, Curve, Point};
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Curve, Point, Scalar};
 use serde::{Deserialize, Serialize};
 use paillier::EncryptionKey;
 use zk_paillier::zkproofs::DLogStatement;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::protocols::gg_2020::state_machine::keygen::messages::parameters::Parameters;
 use crate::protocols::gg_2020::state_machine::keygen::types::SecretShare;
@@ -14,7 +21,7 @@ use super::party_i::shared_keys::SharedKeys;
 
 
 /// Local secret obtained by party after [keygen](super::Keygen) protocol is completed
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct LocalKey<E: Curve> {
     pub paillier_dk: paillier::DecryptionKey,
     pub pk_vec: Vec<Point<E>>,
@@ -31,6 +38,23 @@ pub struct LocalKey<E: Curve> {
     pub party_to_point_map: PartyToPointMap,
 }
 
+impl<E: Curve> Zeroize for LocalKey<E> {
+    fn zeroize(&mut self) {
+        self.paillier_dk.p.zeroize();
+        self.paillier_dk.q.zeroize();
+        self.keys_linear.zeroize();
+        self.secret_share.1 = Scalar::zero();
+    }
+}
+
+impl<E: Curve> Drop for LocalKey<E> {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl<E: Curve> ZeroizeOnDrop for LocalKey<E> {}
+
 
 
 // #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -0,0 +1,202 @@
+//! Verifiable encrypted backups of key shares
+//!
+//! Party encrypts its share `x_i` under a recovery public key with segmented ElGamal and proves
+//! that the ciphertext decrypts to the discrete log of its `pk_vec` entry. Other parties, or an
+//! auditor, can check the backup against `pk_vec` without learning the share. Holder of the
+//! recovery private key can later [restore](ShareBackup::restore) the local key.
+
+use std::collections::BTreeSet;
+
+use centipede::juggling::proof_system::{Helgamalsegmented, Proof};
+use centipede::juggling::segmentation::Msegmentation;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+use zk_paillier::zkproofs::DLogStatement;
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    error::local_key_error::BackupError,
+    local_key::{paillier_update::PaillierKeysUpdate, LocalKey},
+    messages::parameters::Parameters,
+    party_i::keys::Keys,
+    party_i::party_to_point_map::PartyToPointMap,
+    party_i::shared_keys::SharedKeys,
+};
+use crate::utilities::secret_scalar::SecretScalar;
+use crate::utilities::session_id::SessionId;
+
+/// Size of a single encrypted segment in bits
+pub const BACKUP_SEGMENT_SIZE: usize = 8;
+/// Number of segments, enough to cover any secp256k1 scalar
+pub const BACKUP_NUM_SEGMENTS: usize = 32;
+
+/// Encrypted share of a single party along with public part of its local key
+///
+/// Paillier decryption key is not backed up: restored party generates a new one, and the others
+/// learn it via [PaillierKeysUpdate].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareBackup {
+    pub party: usize,
+    pub public_key: Point<Secp256k1>,
+    pub pk_vec: Vec<Point<Secp256k1>>,
+    pub key_params: Parameters,
+    pub party_to_point_map: PartyToPointMap,
+    pub vss_scheme: VerifiableSS<Secp256k1>,
+    pub paillier_key_vec: Vec<EncryptionKey>,
+    pub h1_h2_n_tilde_vec: Vec<DLogStatement>,
+    pub ciphertext: Helgamalsegmented,
+    pub proof: Proof,
+}
+
+impl LocalKey<Secp256k1> {
+    /// Encrypts the share under `recovery_key` and proves the encryption is correct
+    pub fn backup_share(
+        &self,
+        recovery_key: &Point<Secp256k1>,
+    ) -> Result<ShareBackup, BackupError> {
+        if recovery_key.is_zero() {
+            return Err(BackupError::ZeroRecoveryKey);
+        }
+        let g = Point::generator().to_point();
+        let (witness, ciphertext) = Msegmentation::to_encrypted_segments(
+            &self.keys_linear.x_i,
+            &BACKUP_SEGMENT_SIZE,
+            BACKUP_NUM_SEGMENTS,
+            recovery_key,
+            &g,
+        );
+        let proof = Proof::prove(
+            &witness,
+            &ciphertext,
+            &g,
+            recovery_key,
+            &BACKUP_SEGMENT_SIZE,
+        );
+
+        Ok(ShareBackup {
+            party: self.own_party_index,
+            public_key: self.public_key.clone(),
+            pk_vec: self.pk_vec.clone(),
+            key_params: self.key_params.clone(),
+            party_to_point_map: self.party_to_point_map.clone(),
+            vss_scheme: self.vss_scheme.clone(),
+            paillier_key_vec: self.paillier_key_vec.clone(),
+            h1_h2_n_tilde_vec: self.h1_h2_n_tilde_vec.clone(),
+            ciphertext,
+            proof,
+        })
+    }
+}
+
+impl ShareBackup {
+    /// Checks that the backup was made for `pk_vec` and decrypts to the share of its party
+    ///
+    /// `pk_vec` must be taken from verifier's own local key (or other trusted source), not from
+    /// the backup itself.
+    pub fn verify(
+        &self,
+        recovery_key: &Point<Secp256k1>,
+        pk_vec: &[Point<Secp256k1>],
+    ) -> Result<(), BackupError> {
+        if recovery_key.is_zero() {
+            return Err(BackupError::ZeroRecoveryKey);
+        }
+        let public_share = self.public_share()?;
+        if self.pk_vec != pk_vec {
+            return Err(BackupError::PkVecMismatch);
+        }
+        let g = Point::generator().to_point();
+        self.proof
+            .verify(
+                &self.ciphertext,
+                &g,
+                recovery_key,
+                public_share,
+                &BACKUP_SEGMENT_SIZE,
+            )
+            .map_err(|_| BackupError::InvalidProof)
+    }
+
+    /// Decrypts the share and rebuilds local key of the party
+    ///
+    /// The party gets a fresh Paillier key and `(N_tilde, h1, h2)`. Returned [PaillierKeysUpdate]
+    /// is proven under `session_id` and must be applied by every other party, see
+    /// [LocalKey::apply_paillier_keys_update].
+    pub fn restore(
+        &self,
+        recovery_private_key: &Scalar<Secp256k1>,
+        session_id: &SessionId,
+    ) -> Result<(LocalKey<Secp256k1>, PaillierKeysUpdate), BackupError> {
+        let public_share = self.public_share()?;
+        let g = Point::generator().to_point();
+        let x_i = SecretScalar::new(
+            Msegmentation::decrypt(
+                &self.ciphertext,
+                &g,
+                recovery_private_key,
+                &BACKUP_SEGMENT_SIZE,
+            )
+            .map_err(|_| BackupError::Decryption)?,
+        );
+        if &(&g * &*x_i) != public_share {
+            return Err(BackupError::ShareMismatch { party: self.party });
+        }
+
+        let keys = Keys::create_safe_prime_from((*x_i).clone(), self.party);
+        let update = PaillierKeysUpdate::new(&keys, &x_i, session_id);
+
+        let mut paillier_key_vec = self.paillier_key_vec.clone();
+        let mut h1_h2_n_tilde_vec = self.h1_h2_n_tilde_vec.clone();
+        if let Some(e) = paillier_key_vec.get_mut(self.party - 1) {
+            *e = update.e.clone();
+        }
+        if let Some(statement) = h1_h2_n_tilde_vec.get_mut(self.party - 1) {
+            *statement = update.dlog_statement.clone();
+        }
+        let point = self
+            .party_to_point_map
+            .points
+            .get(&self.party)
+            .copied()
+            .unwrap_or(self.party);
+
+        let local_key = LocalKey {
+            paillier_dk: keys.paillier_keys.dk.clone(),
+            pk_vec: self.pk_vec.clone(),
+            keys_linear: SharedKeys {
+                y: self.public_key.clone(),
+                x_i: (*x_i).clone(),
+            },
+            paillier_key_vec,
+            h1_h2_n_tilde_vec,
+            vss_scheme: self.vss_scheme.clone(),
+
+            own_party_index: self.party,
+            other_parties: (1..=self.pk_vec.len())
+                .filter(|j| *j != self.party)
+                .collect::<BTreeSet<_>>(),
+            public_key: self.public_key.clone(),
+            key_params: self.key_params.clone(),
+            secret_share: (point, (*x_i).clone()),
+            party_to_point_map: self.party_to_point_map.clone(),
+        };
+        local_key
+            .validate()
+            .into_result()
+            .map_err(BackupError::InvalidLocalKey)?;
+
+        Ok((local_key, update))
+    }
+
+    fn public_share(&self) -> Result<&Point<Secp256k1>, BackupError> {
+        match self.party {
+            0 => None,
+            party => self.pk_vec.get(party - 1),
+        }
+        .ok_or(BackupError::UnknownParty {
+            party: self.party,
+            n: self.pk_vec.len(),
+        })
+    }
+}
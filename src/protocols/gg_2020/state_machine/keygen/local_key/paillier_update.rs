@@ -0,0 +1,138 @@
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+use zk_paillier::zkproofs::{DLogStatement, NiCorrectKeyProof};
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    error::local_key_error::PaillierKeysUpdateError, local_key::LocalKey, party_i::keys::Keys,
+};
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::{wire_struct, Wire};
+use crate::utilities::zk_sigma::{CompositeDLogProof, DLogProof};
+
+const OWNERSHIP_DOMAIN: &[u8] = b"multi-party-ecdsa/paillier-keys-update/v1";
+
+/// New Paillier key and `(N_tilde, h1, h2)` of a single party, along with the same proofs as in
+/// [KeyGenBroadcast](crate::protocols::gg_2020::state_machine::keygen::messages::broadcast::KeyGenBroadcast)
+///
+/// Party which had to regenerate its keys (e.g. after restoring its share) sends the update to
+/// everyone else, and they [apply](LocalKey::apply_paillier_keys_update) it to their local keys.
+/// The update also proves knowledge of the share `x_i` behind `pk_vec[party-1]`, so only the
+/// holder of the share can replace keys of the party.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaillierKeysUpdate {
+    pub party: usize,
+    pub e: EncryptionKey,
+    pub dlog_statement: DLogStatement,
+    pub correct_key_proof: NiCorrectKeyProof,
+    pub composite_dlog_proof_base_h1: CompositeDLogProof,
+    pub composite_dlog_proof_base_h2: CompositeDLogProof,
+    /// Proof of knowledge of `x_i`, bound to the session id and the keys above
+    pub ownership_proof: DLogProof,
+}
+
+wire_struct!(PaillierKeysUpdate {
+    party,
+    e,
+    dlog_statement,
+    correct_key_proof,
+    composite_dlog_proof_base_h1,
+    composite_dlog_proof_base_h2,
+    ownership_proof,
+});
+
+impl PaillierKeysUpdate {
+    /// Announces Paillier key and `(N_tilde, h1, h2)` of `keys`, proving them and knowledge of
+    /// the share `x_i` under `session_id`
+    pub fn new(keys: &Keys, x_i: &Scalar<Secp256k1>, session_id: &SessionId) -> Self {
+        let (dlog_statement, correct_key_proof, proof_h1, proof_h2) =
+            keys.proof_of_correct_key_proof_of_correct_h1h2(session_id);
+        let party = keys.party_index;
+        let e = keys.paillier_keys.ek.clone();
+        let ownership_sid = ownership_session_id(session_id, party, &e, &dlog_statement);
+        Self {
+            party,
+            e,
+            dlog_statement,
+            correct_key_proof,
+            composite_dlog_proof_base_h1: proof_h1,
+            composite_dlog_proof_base_h2: proof_h2,
+            ownership_proof: DLogProof::prove(x_i, &ownership_sid),
+        }
+    }
+
+    pub fn verify(&self, session_id: &SessionId) -> bool {
+        Keys::verify_correct_key_verify_correct_h1h2(
+            &self.e,
+            &self.dlog_statement,
+            &self.correct_key_proof,
+            &self.composite_dlog_proof_base_h1,
+            &self.composite_dlog_proof_base_h2,
+            session_id,
+        )
+    }
+
+    /// Checks that the update was made by the holder of the share behind `pk`
+    pub fn verify_ownership(&self, pk: &Point<Secp256k1>, session_id: &SessionId) -> bool {
+        let ownership_sid =
+            ownership_session_id(session_id, self.party, &self.e, &self.dlog_statement);
+        &self.ownership_proof.pk == pk && self.ownership_proof.verify(&ownership_sid).is_ok()
+    }
+}
+
+/// Session id of the proof of knowledge of `x_i`, so the proof can't be replayed in another
+/// session or attached to other keys
+fn ownership_session_id(
+    session_id: &SessionId,
+    party: usize,
+    e: &EncryptionKey,
+    dlog_statement: &DLogStatement,
+) -> SessionId {
+    let mut context = OWNERSHIP_DOMAIN.to_vec();
+    context.extend_from_slice(session_id.as_bytes());
+    party.encode(&mut context);
+    e.encode(&mut context);
+    dlog_statement.encode(&mut context);
+    SessionId::new(&context)
+}
+
+impl LocalKey<Secp256k1> {
+    /// Replaces Paillier key and `(N_tilde, h1, h2)` of another party with the ones from `update`
+    ///
+    /// Proofs are checked under `session_id`, which must be the one the update was made with. The
+    /// update must prove knowledge of the share behind `pk_vec[update.party-1]`.
+    pub fn apply_paillier_keys_update(
+        &mut self,
+        update: &PaillierKeysUpdate,
+        session_id: &SessionId,
+    ) -> Result<(), PaillierKeysUpdateError> {
+        let n = self.paillier_key_vec.len();
+        if update.party == 0
+            || update.party > n
+            || update.party > self.h1_h2_n_tilde_vec.len()
+            || update.party > self.pk_vec.len()
+        {
+            return Err(PaillierKeysUpdateError::UnknownParty {
+                party: update.party,
+                n,
+            });
+        }
+        if update.party == self.own_party_index {
+            return Err(PaillierKeysUpdateError::OwnParty);
+        }
+        if !update.verify(session_id) {
+            return Err(PaillierKeysUpdateError::InvalidProofs {
+                party: update.party,
+            });
+        }
+        if !update.verify_ownership(&self.pk_vec[update.party - 1], session_id) {
+            return Err(PaillierKeysUpdateError::NotShareHolder {
+                party: update.party,
+            });
+        }
+
+        self.paillier_key_vec[update.party - 1] = update.e.clone();
+        self.h1_h2_n_tilde_vec[update.party - 1] = update.dlog_statement.clone();
+        Ok(())
+    }
+}
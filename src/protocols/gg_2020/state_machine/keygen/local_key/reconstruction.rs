@@ -0,0 +1,124 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    error::local_key_error::ReconstructionError, local_key::LocalKey, types::FE,
+};
+
+/// Reconstructs the full private key from `t+1` or more local keys
+///
+/// Meant for offline disaster recovery only: once the key is reconstructed, it's no longer
+/// protected by the threshold scheme.
+///
+/// Every share `x_i` is checked against `g^{x_i}` committed in `pk_vec`, and against
+/// `vss_scheme` if the latter commits to the whole key (as it does for
+/// [imported](crate::protocols::gg_2020::state_machine::import) keys). Keys must also be
+/// [valid](LocalKey::validate) and agree on the public key, `pk_vec` and `(t, n)`. Shares are
+/// then combined with Lagrange coefficients from `party_to_point_map`, and the result is checked
+/// against `public_key`.
+///
+/// Returns [ReconstructionError::InconsistentShares] listing `own_party_index` of every key that
+/// didn't pass the checks.
+pub fn reconstruct_private_key(
+    local_keys: &[LocalKey<Secp256k1>],
+) -> Result<Scalar<Secp256k1>, ReconstructionError> {
+    if local_keys.is_empty() {
+        return Err(ReconstructionError::NoShares);
+    }
+
+    let mut parties = BTreeSet::new();
+    for key in local_keys {
+        if !parties.insert(key.own_party_index) {
+            return Err(ReconstructionError::DuplicateParty {
+                party: key.own_party_index,
+            });
+        }
+    }
+
+    // Single corrupted key must not make the honest ones look inconsistent, so the reference is
+    // the valid key which most of other keys agree with
+    let valid: Vec<bool> = local_keys.iter().map(is_valid_share).collect();
+    let reference = local_keys
+        .iter()
+        .zip(&valid)
+        .filter(|(_, valid)| **valid)
+        .map(|(reference, _)| reference)
+        .max_by_key(|reference| {
+            local_keys
+                .iter()
+                .filter(|key| describe_same_key(key, reference))
+                .count()
+        });
+    let inconsistent: Vec<usize> = local_keys
+        .iter()
+        .zip(&valid)
+        .filter(|(key, valid)| match reference {
+            Some(reference) => !**valid || !describe_same_key(key, reference),
+            None => true,
+        })
+        .map(|(key, _)| key.own_party_index)
+        .collect();
+    let reference = match reference {
+        Some(reference) if inconsistent.is_empty() => reference,
+        _ => {
+            return Err(ReconstructionError::InconsistentShares {
+                parties: inconsistent,
+            })
+        }
+    };
+
+    let required = usize::from(reference.key_params.threshold) + 1;
+    if local_keys.len() < required {
+        return Err(ReconstructionError::TooFewShares {
+            required,
+            actual: local_keys.len(),
+        });
+    }
+
+    let parties: Vec<usize> = local_keys.iter().map(|key| key.own_party_index).collect();
+    let points = &reference.party_to_point_map;
+    let private_key = local_keys
+        .iter()
+        .fold(Scalar::<Secp256k1>::zero(), |acc, key| {
+            let x_j: FE = Scalar::from_bigint(&BigInt::from(
+                points.points[&key.own_party_index] as u64,
+            ));
+            acc + points.calculate_lagrange_multiplier(&parties, x_j) * &key.keys_linear.x_i
+        });
+
+    if Point::generator() * &private_key != reference.public_key {
+        return Err(ReconstructionError::PublicKeyMismatch);
+    }
+    Ok(private_key)
+}
+
+/// Checks the key on its own, including its share against `vss_scheme` if possible
+fn is_valid_share(key: &LocalKey<Secp256k1>) -> bool {
+    if !key.validate().is_valid() {
+        return false;
+    }
+    if key.vss_scheme.commitments.first() != Some(&key.public_key) {
+        // Key was generated jointly, `vss_scheme` only commits to contribution of the party
+        return true;
+    }
+    let point = key.party_to_point_map.points[&key.own_party_index];
+    match u16::try_from(point) {
+        Ok(point) => key
+            .vss_scheme
+            .validate_share(&key.keys_linear.x_i, point)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Checks that both keys are shares of the same key
+fn describe_same_key(key: &LocalKey<Secp256k1>, other: &LocalKey<Secp256k1>) -> bool {
+    key.public_key == other.public_key
+        && key.pk_vec == other.pk_vec
+        && key.key_params.threshold == other.key_params.threshold
+        && key.key_params.share_count == other.key_params.share_count
+        && key.party_to_point_map.points == other.party_to_point_map.points
+}
@@ -0,0 +1,221 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    error::local_key_error::LocalKeyIssue,
+    local_key::LocalKey,
+    types::{FE, GE},
+};
+
+/// Outcome of [LocalKey::validate]
+///
+/// Lists every inconsistency found in the key, so a corrupted or tampered key file can be
+/// diagnosed at once instead of failing somewhere in the middle of signing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<LocalKeyIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    fn push(&mut self, issue: LocalKeyIssue) {
+        self.issues.push(issue)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.issues.is_empty() {
+            return write!(f, "local key is consistent");
+        }
+        write!(f, "local key is inconsistent:")?;
+        for issue in &self.issues {
+            write!(f, "\n * {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl LocalKey<Secp256k1> {
+    /// Checks consistency of the key
+    ///
+    /// Verifies that:
+    /// * `g^{x_i}` equals `pk_vec[own_party_index-1]`, and `keys_linear.y` equals `public_key`
+    /// * `pk_vec` lies on a single degree-`t` polynomial which interpolates to `public_key`
+    /// * `paillier_dk` matches `paillier_key_vec[own_party_index-1]`
+    /// * `pk_vec`, `paillier_key_vec` and `h1_h2_n_tilde_vec` have `n` entries, where `n` is taken
+    ///   from `key_params`
+    /// * `party_to_point_map` maps every party to a distinct non-zero point
+    ///
+    /// Key loaded from untrusted storage should be validated before being used in signing.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        let t = self.key_params.threshold;
+        let n = self.key_params.share_count;
+        if t == 0 || t >= n {
+            report.push(LocalKeyIssue::InvalidKeyParams { t, n });
+            // Nothing else can be checked meaningfully
+            return report;
+        }
+        let n_usize = usize::from(n);
+        let own = self.own_party_index;
+        let own_in_range = own >= 1 && own <= n_usize;
+        if !own_in_range {
+            report.push(LocalKeyIssue::InvalidOwnPartyIndex { index: own, n });
+        }
+
+        let expected_others: BTreeSet<usize> = (1..=n_usize).filter(|p| *p != own).collect();
+        if self.other_parties != expected_others {
+            report.push(LocalKeyIssue::InvalidOtherParties);
+        }
+
+        let lengths = [
+            ("pk_vec", self.pk_vec.len()),
+            ("paillier_key_vec", self.paillier_key_vec.len()),
+            ("h1_h2_n_tilde_vec", self.h1_h2_n_tilde_vec.len()),
+        ];
+        for (field, actual) in lengths.iter().copied() {
+            if actual != n_usize {
+                report.push(LocalKeyIssue::LengthMismatch {
+                    field,
+                    expected: n_usize,
+                    actual,
+                });
+            }
+        }
+
+        let vss_params = &self.vss_scheme.parameters;
+        if vss_params.threshold != t || vss_params.share_count != n {
+            report.push(LocalKeyIssue::VssParamsMismatch {
+                t: vss_params.threshold,
+                n: vss_params.share_count,
+            });
+        }
+
+        if self.keys_linear.y != self.public_key {
+            report.push(LocalKeyIssue::SharedPublicKeyMismatch);
+        }
+
+        if own_in_range {
+            if let Some(pk_i) = self.pk_vec.get(own - 1) {
+                if &(Point::generator() * &self.keys_linear.x_i) != pk_i {
+                    report.push(LocalKeyIssue::SharePublicKeyMismatch { party: own });
+                }
+            }
+            if let Some(ek) = self.paillier_key_vec.get(own - 1) {
+                if &self.paillier_dk.p * &self.paillier_dk.q != ek.n {
+                    report.push(LocalKeyIssue::PaillierKeyMismatch { party: own });
+                }
+            }
+        }
+
+        let points_are_valid = self.check_party_to_point_map(n_usize, &mut report);
+        if points_are_valid && self.pk_vec.len() == n_usize {
+            self.check_pk_vec_interpolation(usize::from(t), &mut report);
+        }
+
+        report
+    }
+
+    /// Returns `true` if every party is mapped to a distinct non-zero point
+    fn check_party_to_point_map(&self, n: usize, report: &mut ValidationReport) -> bool {
+        let mut valid = true;
+        let mut used_points: HashMap<usize, usize> = HashMap::new();
+        for party in 1..=n {
+            match self.party_to_point_map.points.get(&party) {
+                None => {
+                    report.push(LocalKeyIssue::PartyNotMapped { party });
+                    valid = false;
+                }
+                Some(&point) if point == 0 || used_points.contains_key(&point) => {
+                    report.push(LocalKeyIssue::InvalidPoint { party, point });
+                    valid = false;
+                }
+                Some(&point) => {
+                    used_points.insert(point, party);
+                }
+            }
+        }
+        for party in self.party_to_point_map.points.keys() {
+            if *party == 0 || *party > n {
+                report.push(LocalKeyIssue::UnknownPartyMapped { party: *party });
+                valid = false;
+            }
+        }
+
+        if let Some(&expected) = self.party_to_point_map.points.get(&self.own_party_index) {
+            if self.secret_share.0 != expected {
+                report.push(LocalKeyIssue::SecretSharePointMismatch {
+                    expected,
+                    actual: self.secret_share.0,
+                });
+            }
+        }
+
+        valid
+    }
+
+    /// Interpolates polynomial over first `t+1` entries of `pk_vec` and checks that it
+    /// evaluates to `public_key` at zero and to remaining `pk_vec` entries at their points
+    fn check_pk_vec_interpolation(&self, t: usize, report: &mut ValidationReport) {
+        let point_of = |party: usize| self.party_to_point_map.points[&party];
+        let base_parties: Vec<usize> = (1..=t + 1).collect();
+        let xs: Vec<FE> = base_parties.iter().map(|&p| point_to_scalar(point_of(p))).collect();
+        let ys: Vec<&GE> = base_parties.iter().map(|&p| &self.pk_vec[p - 1]).collect();
+
+        match interpolate_in_exponent(&xs, &ys, &Scalar::zero()) {
+            Some(y) if y == self.public_key => (),
+            _ => report.push(LocalKeyIssue::PublicKeyInterpolation),
+        }
+
+        for party in (t + 2)..=self.pk_vec.len() {
+            let x = point_to_scalar(point_of(party));
+            match interpolate_in_exponent(&xs, &ys, &x) {
+                Some(y) if y == self.pk_vec[party - 1] => (),
+                _ => report.push(LocalKeyIssue::PkVecInconsistent { party }),
+            }
+        }
+    }
+}
+
+fn point_to_scalar(point: usize) -> FE {
+    Scalar::<Secp256k1>::from_bigint(&BigInt::from(point as u64))
+}
+
+/// Evaluates at `x` polynomial (in the exponent) passing through `(xs[j], ys[j])`
+///
+/// Returns `None` if `xs` contains duplicates.
+fn interpolate_in_exponent(xs: &[FE], ys: &[&GE], x: &FE) -> Option<GE> {
+    let mut result = Point::<Secp256k1>::zero();
+    for (j, x_j) in xs.iter().enumerate() {
+        let mut num = Scalar::<Secp256k1>::from_bigint(&BigInt::from(1));
+        let mut denom = Scalar::<Secp256k1>::from_bigint(&BigInt::from(1));
+        for (m, x_m) in xs.iter().enumerate() {
+            if m == j {
+                continue;
+            }
+            num = num * (x - x_m);
+            denom = denom * (x_j - x_m);
+        }
+        let lambda_j = num * denom.invert()?;
+        result = result + ys[j] * &lambda_j;
+    }
+    Some(result)
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use curv::arithmetic::traits::*;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::{
+    ShamirSecretSharing, VerifiableSS,
+};
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use paillier::{DecryptionKey, EncryptionKey};
+use serde::{Deserialize, Serialize};
+use zk_paillier::zkproofs::DLogStatement;
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    error::local_key_error::LocalKeyFileError,
+    local_key::LocalKey,
+    messages::parameters::Parameters,
+    party_i::party_to_point_map::PartyToPointMap,
+    party_i::shared_keys::SharedKeys,
+};
+
+/// Version of [LocalKeyFile] layout written by this library
+pub const LOCAL_KEY_FILE_VERSION: u32 = 1;
+pub const LOCAL_KEY_FILE_PROTOCOL: &str = "gg20";
+pub const LOCAL_KEY_FILE_CURVE: &str = "secp256k1";
+
+const SCALAR_BYTES: usize = 32;
+
+/// Stable on-disk representation of [LocalKey]
+///
+/// Unlike `LocalKey` itself, this layout doesn't depend on serde representations of curv and
+/// paillier types: points are stored as hex of compressed SEC1 encoding, scalars as hex of
+/// 32 big-endian bytes, and big integers as big-endian hex without leading zeroes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LocalKeyFile {
+    pub protocol: String,
+    pub curve: String,
+    pub version: u32,
+    /// Seconds since unix epoch
+    pub created_at: u64,
+    pub key: LocalKeyFieldsV1,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LocalKeyFieldsV1 {
+    pub own_party_index: u16,
+    pub threshold: u16,
+    pub share_count: u16,
+    pub public_key: String,
+    pub x_i: String,
+    pub secret_share_point: u16,
+    pub secret_share: String,
+    pub pk_vec: Vec<String>,
+    pub paillier_p: String,
+    pub paillier_q: String,
+    pub paillier_n_vec: Vec<String>,
+    pub h1_h2_n_tilde_vec: Vec<DLogStatementFields>,
+    pub vss_commitments: Vec<String>,
+    pub party_to_point_map: BTreeMap<u16, u16>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DLogStatementFields {
+    pub n_tilde: String,
+    pub h1: String,
+    pub h2: String,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    protocol: String,
+    curve: String,
+    version: u32,
+}
+
+impl LocalKeyFile {
+    pub fn new(key: &LocalKey<Secp256k1>, created_at: u64) -> Result<Self, LocalKeyFileError> {
+        let fields = LocalKeyFieldsV1 {
+            own_party_index: index_to_u16(key.own_party_index, "own_party_index")?,
+            threshold: key.key_params.threshold,
+            share_count: key.key_params.share_count,
+            public_key: encode_point(&key.public_key),
+            x_i: encode_scalar(&key.keys_linear.x_i),
+            secret_share_point: index_to_u16(key.secret_share.0, "secret_share_point")?,
+            secret_share: encode_scalar(&key.secret_share.1),
+            pk_vec: key.pk_vec.iter().map(encode_point).collect(),
+            paillier_p: key.paillier_dk.p.to_hex(),
+            paillier_q: key.paillier_dk.q.to_hex(),
+            paillier_n_vec: key.paillier_key_vec.iter().map(|ek| ek.n.to_hex()).collect(),
+            h1_h2_n_tilde_vec: key
+                .h1_h2_n_tilde_vec
+                .iter()
+                .map(|s| DLogStatementFields {
+                    n_tilde: s.N.to_hex(),
+                    h1: s.g.to_hex(),
+                    h2: s.ni.to_hex(),
+                })
+                .collect(),
+            vss_commitments: key.vss_scheme.commitments.iter().map(encode_point).collect(),
+            party_to_point_map: key
+                .party_to_point_map
+                .points
+                .iter()
+                .map(|(party, point)| {
+                    Ok((
+                        index_to_u16(*party, "party_to_point_map")?,
+                        index_to_u16(*point, "party_to_point_map")?,
+                    ))
+                })
+                .collect::<Result<_, LocalKeyFileError>>()?,
+        };
+
+        Ok(Self {
+            protocol: LOCAL_KEY_FILE_PROTOCOL.to_string(),
+            curve: LOCAL_KEY_FILE_CURVE.to_string(),
+            version: LOCAL_KEY_FILE_VERSION,
+            created_at,
+            key: fields,
+        })
+    }
+
+    /// Parses versioned key file, checking protocol, curve and version in the header first
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LocalKeyFileError> {
+        let header: Header = serde_json::from_slice(bytes).map_err(LocalKeyFileError::Malformed)?;
+        if header.protocol != LOCAL_KEY_FILE_PROTOCOL {
+            return Err(LocalKeyFileError::UnsupportedProtocol(header.protocol));
+        }
+        if header.curve != LOCAL_KEY_FILE_CURVE {
+            return Err(LocalKeyFileError::UnsupportedCurve(header.curve));
+        }
+        match header.version {
+            1 => serde_json::from_slice(bytes).map_err(LocalKeyFileError::Malformed),
+            version => Err(LocalKeyFileError::UnsupportedVersion(version)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LocalKeyFileError> {
+        serde_json::to_vec_pretty(self).map_err(LocalKeyFileError::Malformed)
+    }
+
+    pub fn to_local_key(&self) -> Result<LocalKey<Secp256k1>, LocalKeyFileError> {
+        let f = &self.key;
+        let own_party_index = usize::from(f.own_party_index);
+        let public_key = decode_point(&f.public_key, "public_key")?;
+        let x_i = decode_scalar(&f.x_i, "x_i")?;
+
+        let paillier_dk = DecryptionKey {
+            p: decode_bigint(&f.paillier_p, "paillier_p")?,
+            q: decode_bigint(&f.paillier_q, "paillier_q")?,
+        };
+        let paillier_key_vec = f
+            .paillier_n_vec
+            .iter()
+            .map(|n| {
+                let n = decode_bigint(n, "paillier_n_vec")?;
+                Ok(EncryptionKey {
+                    nn: &n * &n,
+                    n,
+                })
+            })
+            .collect::<Result<Vec<_>, LocalKeyFileError>>()?;
+        let h1_h2_n_tilde_vec = f
+            .h1_h2_n_tilde_vec
+            .iter()
+            .map(|s| {
+                Ok(DLogStatement {
+                    N: decode_bigint(&s.n_tilde, "h1_h2_n_tilde_vec")?,
+                    g: decode_bigint(&s.h1, "h1_h2_n_tilde_vec")?,
+                    ni: decode_bigint(&s.h2, "h1_h2_n_tilde_vec")?,
+                })
+            })
+            .collect::<Result<Vec<_>, LocalKeyFileError>>()?;
+        let pk_vec = f
+            .pk_vec
+            .iter()
+            .map(|p| decode_point(p, "pk_vec"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let vss_scheme = VerifiableSS {
+            parameters: ShamirSecretSharing {
+                threshold: f.threshold,
+                share_count: f.share_count,
+            },
+            commitments: f
+                .vss_commitments
+                .iter()
+                .map(|c| decode_point(c, "vss_commitments"))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let points: HashMap<usize, usize> = f
+            .party_to_point_map
+            .iter()
+            .map(|(party, point)| (usize::from(*party), usize::from(*point)))
+            .collect();
+        let other_parties: BTreeSet<usize> = (1..=usize::from(f.share_count))
+            .filter(|p| *p != own_party_index)
+            .collect();
+
+        Ok(LocalKey {
+            paillier_dk,
+            pk_vec,
+            keys_linear: SharedKeys {
+                y: public_key.clone(),
+                x_i,
+            },
+            paillier_key_vec,
+            h1_h2_n_tilde_vec,
+            vss_scheme,
+            own_party_index,
+            other_parties,
+            public_key,
+            key_params: Parameters::new(f.threshold, f.share_count),
+            secret_share: (
+                usize::from(f.secret_share_point),
+                decode_scalar(&f.secret_share, "secret_share")?,
+            ),
+            party_to_point_map: PartyToPointMap { points },
+        })
+    }
+}
+
+impl LocalKey<Secp256k1> {
+    /// Serializes the key into versioned [LocalKeyFile] layout
+    pub fn to_versioned_bytes(&self) -> Result<Vec<u8>, LocalKeyFileError> {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        LocalKeyFile::new(self, created_at)?.to_bytes()
+    }
+
+    /// Deserializes the key written by [to_versioned_bytes](Self::to_versioned_bytes)
+    ///
+    /// Keys saved in legacy layout (plain serde_json of `LocalKey`, as older versions of
+    /// `gg20_keygen` example did) are recognised by the lack of `version` field and migrated
+    /// transparently. Use [migrate_legacy_local_key] to rewrite such files.
+    pub fn from_versioned_bytes(bytes: &[u8]) -> Result<Self, LocalKeyFileError> {
+        let value: serde_json::Value =
+            serde_json::from_slice(bytes).map_err(LocalKeyFileError::Malformed)?;
+        if value.get("version").is_none() {
+            return serde_json::from_value(value).map_err(LocalKeyFileError::Malformed);
+        }
+        LocalKeyFile::from_bytes(bytes)?.to_local_key()
+    }
+}
+
+/// Converts key in legacy serde_json layout into current versioned layout
+pub fn migrate_legacy_local_key(bytes: &[u8]) -> Result<Vec<u8>, LocalKeyFileError> {
+    let key: LocalKey<Secp256k1> =
+        serde_json::from_slice(bytes).map_err(LocalKeyFileError::Malformed)?;
+    key.to_versioned_bytes()
+}
+
+fn index_to_u16(index: usize, field: &'static str) -> Result<u16, LocalKeyFileError> {
+    u16::try_from(index).map_err(|_| LocalKeyFileError::InvalidField { field })
+}
+
+fn encode_point(point: &Point<Secp256k1>) -> String {
+    hex::encode(point.to_bytes(true))
+}
+
+fn decode_point(s: &str, field: &'static str) -> Result<Point<Secp256k1>, LocalKeyFileError> {
+    let bytes = hex::decode(s).map_err(|_| LocalKeyFileError::InvalidField { field })?;
+    Point::from_bytes(&bytes).map_err(|_| LocalKeyFileError::InvalidField { field })
+}
+
+fn encode_scalar(scalar: &Scalar<Secp256k1>) -> String {
+    let bytes = scalar.to_bigint().to_bytes();
+    let mut padded = vec![0u8; SCALAR_BYTES - bytes.len()];
+    padded.extend_from_slice(&bytes);
+    hex::encode(padded)
+}
+
+fn decode_scalar(s: &str, field: &'static str) -> Result<Scalar<Secp256k1>, LocalKeyFileError> {
+    let bytes = hex::decode(s).map_err(|_| LocalKeyFileError::InvalidField { field })?;
+    if bytes.len() != SCALAR_BYTES {
+        return Err(LocalKeyFileError::InvalidField { field });
+    }
+    let n = BigInt::from_bytes(&bytes);
+    if &n >= Scalar::<Secp256k1>::group_order() {
+        return Err(LocalKeyFileError::InvalidField { field });
+    }
+    Ok(Scalar::from_bigint(&n))
+}
+
+fn decode_bigint(s: &str, field: &'static str) -> Result<BigInt, LocalKeyFileError> {
+    if s.is_empty() || (s.starts_with('0') && s.len() > 1) {
+        return Err(LocalKeyFileError::InvalidField { field });
+    }
+    BigInt::from_hex(s).map_err(|_| LocalKeyFileError::InvalidField { field })
+}
@@ -14,6 +14,9 @@ use self::broadcast::KeyGenBroadcast;
 use self::decommit::KeyGenDecommit;
 use self::feldman_vss::FeldmanVSS;
 use self::proof::Proof;
+use crate::protocols::gg_2020::state_machine::echo::EchoMessage;
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+use crate::utilities::wire::{self, Reader, Wire, WireError};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProtocolMessage(pub M);
@@ -24,6 +27,62 @@ pub enum M {
     Round2(KeyGenDecommit),
     Round3(FeldmanVSS),
     Round4(Proof),
+    Echo(EchoMessage),
+}
+
+impl RoundMessage for ProtocolMessage {
+    fn round(&self) -> u16 {
+        match self.0 {
+            M::Round1(_) => 1,
+            M::Round2(_) => 2,
+            M::Round3(_) => 3,
+            M::Round4(_) => 4,
+            M::Echo(_) => 5,
+        }
+    }
+}
+
+impl ProtocolMessage {
+    /// Encodes the message in compact binary format, see [wire](crate::utilities::wire)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        wire::to_bytes(self)
+    }
+
+    /// Decodes the message produced by [to_bytes](Self::to_bytes), rejecting non-canonical input
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        wire::from_bytes(bytes)
+    }
+}
+
+/// Tag of every message is the number of its round
+impl Wire for ProtocolMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.round() as u8);
+        match &self.0 {
+            M::Round1(m) => m.encode(out),
+            M::Round2(m) => m.encode(out),
+            M::Round3(m) => m.encode(out),
+            M::Round4(m) => m.encode(out),
+            M::Echo(m) => m.encode(out),
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let m = match reader.take_tag()? {
+            1 => M::Round1(Wire::decode(reader)?),
+            2 => M::Round2(Wire::decode(reader)?),
+            3 => M::Round3(Wire::decode(reader)?),
+            4 => M::Round4(Wire::decode(reader)?),
+            5 => M::Echo(Wire::decode(reader)?),
+            tag => {
+                return Err(WireError::UnknownTag {
+                    ty: "keygen message",
+                    tag,
+                })
+            }
+        };
+        Ok(ProtocolMessage(m))
+    }
 }
 
 
@@ -4,9 +4,31 @@ use serde::{
     Deserialize,
 };
 
+use crate::utilities::wire::{Reader, Wire, WireError};
+
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum Address {
     Peer(usize),
     Broadcast,
 }
+
+impl Wire for Address {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Address::Peer(i) => {
+                out.push(0);
+                i.encode(out);
+            }
+            Address::Broadcast => out.push(1),
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        match reader.take_tag()? {
+            0 => Ok(Address::Peer(usize::decode(reader)?)),
+            1 => Ok(Address::Broadcast),
+            tag => Err(WireError::UnknownTag { ty: "address", tag }),
+        }
+    }
+}
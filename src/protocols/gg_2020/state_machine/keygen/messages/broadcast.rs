@@ -7,10 +7,12 @@ use serde::{
 use zk_paillier::zkproofs::{
     DLogStatement, 
     NiCorrectKeyProof, 
-    CompositeDLogProof
 };
 
+use crate::utilities::zk_sigma::CompositeDLogProof;
+
 use super::address::Address;
+use crate::utilities::wire::wire_struct;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KeyGenBroadcast {
@@ -25,6 +27,17 @@ pub struct KeyGenBroadcast {
     pub recipient: Address,
 }
 
+wire_struct!(KeyGenBroadcast {
+    e,
+    dlog_statement,
+    com,
+    correct_key_proof,
+    composite_dlog_proof_base_h1,
+    composite_dlog_proof_base_h2,
+    sender,
+    recipient,
+});
+
 
 // ing
 // #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,6 +6,7 @@ use serde::{
 };
 
 use super::address::Address;
+use crate::utilities::wire::wire_struct;
 
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -17,6 +18,13 @@ pub struct KeyGenDecommit {
     pub recipient: Address,
 }
 
+wire_struct!(KeyGenDecommit {
+    blind_factor,
+    y_i,
+    sender,
+    recipient,
+});
+
 // ing
 // Decommitment of partial public EC schema key
 // #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -8,6 +8,7 @@ use serde::{
 use crate::protocols::gg_2020::state_machine::keygen::types::SecretShare;
 
 use super::address::Address;
+use crate::utilities::wire::wire_struct;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FeldmanVSS {
@@ -18,6 +19,13 @@ pub struct FeldmanVSS {
     pub recipient: Address,
 }
 
+wire_struct!(FeldmanVSS {
+    vss,
+    share,
+    sender,
+    recipient,
+});
+
 // zengo
 //  (VerifiableSS<Secp256k1>, Scalar<Secp256k1>)
 
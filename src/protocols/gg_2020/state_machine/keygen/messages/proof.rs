@@ -1,18 +1,23 @@
-use sha2::Sha256;
-use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
-use curv::elliptic::curves::Secp256k1;
 use serde::{
     Serialize,
     Deserialize,
 };
 
 use super::address::Address;
+use crate::utilities::wire::wire_struct;
+use crate::utilities::zk_sigma::DLogProof;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Proof {
-    pub proof: DLogProof<Secp256k1, Sha256>,
+    pub proof: DLogProof,
     
     pub sender: usize,
     pub recipient: Address,
 }
 
+wire_struct!(Proof {
+    proof,
+    sender,
+    recipient,
+});
+
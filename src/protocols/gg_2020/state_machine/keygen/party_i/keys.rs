@@ -1,13 +1,10 @@
 use std::fmt::Debug;
 
 use curv::arithmetic::traits::*;
-use curv::cryptographic_primitives::commitments::hash_commitment::HashCommitment;
-use curv::cryptographic_primitives::commitments::traits::Commitment;
-use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
 use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Curve, Point, Scalar};
 use curv::BigInt;
-use sha2::Sha256;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::protocols::gg_2020::state_machine::keygen::{
     messages::broadcast::KeyGenBroadcast,
@@ -22,15 +19,18 @@ use crate::protocols::gg_2020::state_machine::keygen::{
 
 
 use paillier::{
+    EncryptionKey,
     KeyGeneration, 
     Paillier, 
 };
 
 use serde::{Deserialize, Serialize};
 use zk_paillier::zkproofs::NiCorrectKeyProof;
-use zk_paillier::zkproofs::{CompositeDLogProof, DLogStatement};
+use zk_paillier::zkproofs::DLogStatement;
 
 use crate::protocols::gg_2020::ErrorType;
+use crate::utilities::session_id::SessionId;
+use crate::utilities::zk_sigma::{CompositeDLogProof, DLogProof};
 use std::convert::TryInto;
 
 
@@ -39,7 +39,7 @@ const PAILLIER_MIN_BIT_LENGTH: usize = 2047;
 const PAILLIER_MAX_BIT_LENGTH: usize = 2048;
 
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Keys<E: Curve = Secp256k1> {
     
     pub u_i: Scalar<E>,
@@ -54,10 +54,32 @@ pub struct Keys<E: Curve = Secp256k1> {
     pub xhi_inv: BigInt,
 }
 
+impl<E: Curve> Zeroize for Keys<E> {
+    fn zeroize(&mut self) {
+        self.u_i = Scalar::zero();
+        self.paillier_keys.zeroize();
+        self.xhi.zeroize();
+        self.xhi_inv.zeroize();
+    }
+}
+
+impl<E: Curve> Drop for Keys<E> {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl<E: Curve> ZeroizeOnDrop for Keys<E> {}
+
 impl Keys {
 
     pub fn create_safe_prime(index: usize) -> Self {
-        let u = Scalar::<Secp256k1>::random();
+        Self::create_safe_prime_from(Scalar::random(), index)
+    }
+
+    /// Creates keys of the party holding secret `u`, generating fresh Paillier key and
+    /// `(N_tilde, h1, h2)`
+    pub fn create_safe_prime_from(u: Scalar<Secp256k1>, index: usize) -> Self {
         let y = Point::generator() * &u;
 
         let (ek, dk) = Paillier::keypair_safe_primes().keys();
@@ -78,27 +100,17 @@ impl Keys {
 
     pub fn phase1_broadcast_phase3_proof_of_correct_key_proof_of_correct_h1h2(
         &self,
+        sid: &SessionId,
     ) -> (KeyGenBroadcast, KeyGenDecommit) {
         let blind_factor = BigInt::sample(SECURITY);
-        let correct_key_proof = NiCorrectKeyProof::proof(&self.paillier_keys.dk, None);
-
-        let dlog_statement_base_h1 = DLogStatement {
-            N: self.n_tilde.clone(),
-            g: self.h1.clone(),
-            ni: self.h2.clone(),
-        };
-        let dlog_statement_base_h2 = DLogStatement {
-            N: self.n_tilde.clone(),
-            g: self.h2.clone(),
-            ni: self.h1.clone(),
-        };
-
-        let composite_dlog_proof_base_h1 =
-            CompositeDLogProof::prove(&dlog_statement_base_h1, &self.xhi);
-        let composite_dlog_proof_base_h2 =
-            CompositeDLogProof::prove(&dlog_statement_base_h2, &self.xhi_inv);
+        let (
+            dlog_statement_base_h1,
+            correct_key_proof,
+            composite_dlog_proof_base_h1,
+            composite_dlog_proof_base_h2,
+        ) = self.proof_of_correct_key_proof_of_correct_h1h2(sid);
 
-        let com = HashCommitment::<Sha256>::create_commitment_with_user_defined_randomness(
+        let com = sid.commit(
             &BigInt::from_bytes(self.y_i.to_bytes(true).as_ref()),
             &blind_factor,
         );
@@ -123,11 +135,75 @@ impl Keys {
         (bcm1, decom1)
     }
 
+    /// Proves that Paillier key and `(N_tilde, h1, h2)` are well-formed
+    ///
+    /// Returns statement `h2 = h1^xhi mod N_tilde`, proof of correct Paillier key, and proofs
+    /// of knowledge of discrete logs with bases `h1` and `h2`.
+    pub fn proof_of_correct_key_proof_of_correct_h1h2(
+        &self,
+        sid: &SessionId,
+    ) -> (DLogStatement, NiCorrectKeyProof, CompositeDLogProof, CompositeDLogProof) {
+        let correct_key_proof = NiCorrectKeyProof::proof(
+            &self.paillier_keys.dk,
+            Some(sid.correct_key_proof_salt_bigint()),
+        );
+
+        let dlog_statement_base_h1 = DLogStatement {
+            N: self.n_tilde.clone(),
+            g: self.h1.clone(),
+            ni: self.h2.clone(),
+        };
+        let dlog_statement_base_h2 = DLogStatement {
+            N: self.n_tilde.clone(),
+            g: self.h2.clone(),
+            ni: self.h1.clone(),
+        };
+
+        let composite_dlog_proof_base_h1 =
+            CompositeDLogProof::prove(&dlog_statement_base_h1, &self.xhi, sid);
+        let composite_dlog_proof_base_h2 =
+            CompositeDLogProof::prove(&dlog_statement_base_h2, &self.xhi_inv, sid);
+
+        (
+            dlog_statement_base_h1,
+            correct_key_proof,
+            composite_dlog_proof_base_h1,
+            composite_dlog_proof_base_h2,
+        )
+    }
+
+    /// Verifies proofs produced by [proof_of_correct_key_proof_of_correct_h1h2], and checks that
+    /// both moduli have expected bit length
+    ///
+    /// [proof_of_correct_key_proof_of_correct_h1h2]: Self::proof_of_correct_key_proof_of_correct_h1h2
+    pub fn verify_correct_key_verify_correct_h1h2(
+        e: &EncryptionKey,
+        dlog_statement: &DLogStatement,
+        correct_key_proof: &NiCorrectKeyProof,
+        composite_dlog_proof_base_h1: &CompositeDLogProof,
+        composite_dlog_proof_base_h2: &CompositeDLogProof,
+        sid: &SessionId,
+    ) -> bool {
+        let dlog_statement_base_h2 = DLogStatement {
+            N: dlog_statement.N.clone(),
+            g: dlog_statement.ni.clone(),
+            ni: dlog_statement.g.clone(),
+        };
+        correct_key_proof.verify(e, &sid.correct_key_proof_salt()).is_ok()
+            && e.n.bit_length() >= PAILLIER_MIN_BIT_LENGTH
+            && e.n.bit_length() <= PAILLIER_MAX_BIT_LENGTH
+            && dlog_statement.N.bit_length() >= PAILLIER_MIN_BIT_LENGTH
+            && dlog_statement.N.bit_length() <= PAILLIER_MAX_BIT_LENGTH
+            && composite_dlog_proof_base_h1.verify(dlog_statement, sid).is_ok()
+            && composite_dlog_proof_base_h2.verify(&dlog_statement_base_h2, sid).is_ok()
+    }
+
     pub fn phase1_verify_com_phase3_verify_correct_key_verify_dlog_phase2_distribute(
         &self,
         params: &Parameters,
         decom_vec: &[KeyGenDecommit],
         bc1_vec: &[KeyGenBroadcast],
+        sid: &SessionId,
     ) -> Result<(VerifiableSS<Secp256k1>, Vec<Scalar<Secp256k1>>, usize), ErrorType> {
         let mut bad_actors_vec = Vec::new();
         // test length:
@@ -136,32 +212,18 @@ impl Keys {
         // test paillier correct key, h1,h2 correct generation and test decommitments
         let correct_key_correct_decom_all = (0..bc1_vec.len())
             .map(|i| {
-                let dlog_statement_base_h2 = DLogStatement {
-                    N: bc1_vec[i].dlog_statement.N.clone(),
-                    g: bc1_vec[i].dlog_statement.ni.clone(),
-                    ni: bc1_vec[i].dlog_statement.g.clone(),
-                };
-                let test_res =
-                    HashCommitment::<Sha256>::create_commitment_with_user_defined_randomness(
-                        &BigInt::from_bytes(&decom_vec[i].y_i.to_bytes(true)),
-                        &decom_vec[i].blind_factor,
-                    ) == bc1_vec[i].com
-                        && bc1_vec[i]
-                            .correct_key_proof
-                            .verify(&bc1_vec[i].e, zk_paillier::zkproofs::SALT_STRING)
-                            .is_ok()
-                        && bc1_vec[i].e.n.bit_length() >= PAILLIER_MIN_BIT_LENGTH
-                        && bc1_vec[i].e.n.bit_length() <= PAILLIER_MAX_BIT_LENGTH
-                        && bc1_vec[i].dlog_statement.N.bit_length() >= PAILLIER_MIN_BIT_LENGTH
-                        && bc1_vec[i].dlog_statement.N.bit_length() <= PAILLIER_MAX_BIT_LENGTH
-                        && bc1_vec[i]
-                            .composite_dlog_proof_base_h1
-                            .verify(&bc1_vec[i].dlog_statement)
-                            .is_ok()
-                        && bc1_vec[i]
-                            .composite_dlog_proof_base_h2
-                            .verify(&dlog_statement_base_h2)
-                            .is_ok();
+                let test_res = sid.commit(
+                    &BigInt::from_bytes(&decom_vec[i].y_i.to_bytes(true)),
+                    &decom_vec[i].blind_factor,
+                ) == bc1_vec[i].com
+                    && Keys::verify_correct_key_verify_correct_h1h2(
+                        &bc1_vec[i].e,
+                        &bc1_vec[i].dlog_statement,
+                        &bc1_vec[i].correct_key_proof,
+                        &bc1_vec[i].composite_dlog_proof_base_h1,
+                        &bc1_vec[i].composite_dlog_proof_base_h2,
+                        sid,
+                    );
                 if !test_res {
                     bad_actors_vec.push(i);
                     false
@@ -193,6 +255,7 @@ impl Keys {
         // vss_scheme_vec: &[VerifiableSS<Secp256k1>],
         feldman_vss_vec: &Vec<FeldmanVSS>,
         index: usize,
+        sid: &SessionId,
     ) -> Result<(SharedKeys, Proof), ErrorType> {
         let mut bad_actors_vec = Vec::new();
         assert_eq!(y_vec.len(), usize::from(params.share_count));
@@ -226,7 +289,7 @@ impl Keys {
             let x_i = feldman_vss_vec
                 .iter()
                 .fold(Scalar::<Secp256k1>::zero(), |acc, x| acc + x.share.1.clone());
-            let dlog_proof = DLogProof::prove(&x_i);
+            let dlog_proof = DLogProof::prove(&x_i, sid);
             let proof = Proof { 
                 proof: dlog_proof,
                 sender: self.party_index,
@@ -279,6 +342,7 @@ impl Keys {
         dlog_proofs_vec: &[Proof],
         y_vec: &[Point<Secp256k1>],
         vss_vec: &[VerifiableSS<Secp256k1>],
+        sid: &SessionId,
     ) -> Result<(), ErrorType> {
         let mut bad_actors_vec = Vec::new();
         assert_eq!(y_vec.len(), usize::from(params.share_count));
@@ -286,7 +350,7 @@ impl Keys {
         let xi_commitments = Keys::get_commitments_to_xi(vss_vec);
         let xi_dlog_verify = (0..y_vec.len())
             .map(|i| {
-                let ver_res = DLogProof::verify(&dlog_proofs_vec[i].proof).is_ok();
+                let ver_res = dlog_proofs_vec[i].proof.verify(sid).is_ok();
                 let verify_against_vss = xi_commitments[i] == dlog_proofs_vec[i].proof.pk;
                 if !ver_res || !verify_against_vss {
                     bad_actors_vec.push(i);
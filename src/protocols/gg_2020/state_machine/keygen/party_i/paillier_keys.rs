@@ -1,7 +1,8 @@
 use paillier::{DecryptionKey, EncryptionKey, };
 use serde::{Serialize, Deserialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct PaillierKeys {
     pub dk: DecryptionKey,
     pub ek: EncryptionKey,
@@ -17,3 +18,17 @@ impl PaillierKeys {
     }
 }
 
+impl Zeroize for PaillierKeys {
+    fn zeroize(&mut self) {
+        self.dk.p.zeroize();
+        self.dk.q.zeroize();
+    }
+}
+
+impl Drop for PaillierKeys {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for PaillierKeys {}
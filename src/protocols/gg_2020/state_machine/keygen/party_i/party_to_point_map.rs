@@ -57,4 +57,28 @@ impl PartyToPointMap {
 
         fold_with_one(&denom_fun).invert().unwrap() * fold_with_one(&num_fun)
     }
+
+    /// Same as [calculate_lagrange_multiplier](Self::calculate_lagrange_multiplier), but
+    /// interpolates at point `at` instead of zero
+    pub fn calculate_lagrange_multiplier_at(
+        &self,
+        signing_parties: &[usize],
+        own_x: FE,
+        at: FE,
+    ) -> FE {
+        #[allow(clippy::cast_possible_truncation)]
+        let subset_of_fe_points = self
+            .map_signing_parties_to_points(signing_parties)
+            .into_iter()
+            .map(|x| Scalar::<Secp256k1>::from_bigint(&BigInt::from(x as u32)))
+            .collect::<Vec<FE>>();
+
+        subset_of_fe_points
+            .iter()
+            .filter(|x| **x != own_x)
+            .fold(
+                Scalar::<Secp256k1>::from_bigint(&BigInt::one()),
+                |acc: FE, x| acc * (&at - x) * (&own_x - x).invert().unwrap(),
+            )
+    }
 }
\ No newline at end of file
@@ -1,9 +1,24 @@
 use std::fmt::Debug;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
 use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SharedKeys {
     pub y: Point<Secp256k1>,
     pub x_i: Scalar<Secp256k1>,
 }
+
+impl Zeroize for SharedKeys {
+    fn zeroize(&mut self) {
+        self.x_i = Scalar::zero();
+    }
+}
+
+impl Drop for SharedKeys {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SharedKeys {}
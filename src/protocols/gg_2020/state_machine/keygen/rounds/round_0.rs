@@ -10,11 +10,13 @@ use crate::protocols::gg_2020::state_machine::keygen::{
     types::ProceedResult, 
     party_i::keys::Keys,
 }; 
+use crate::utilities::session_id::SessionId;
 
 pub struct Round0 {
     pub own_party_index: usize,
     pub other_parties: BTreeSet<usize>,
     pub key_params: Parameters,
+    pub session_id: SessionId,
 }
 
 impl Round0 {
@@ -24,7 +26,9 @@ impl Round0 {
     {
         let party_keys = Keys::create_safe_prime(self.own_party_index);
         let (bc1, decom1) =
-            party_keys.phase1_broadcast_phase3_proof_of_correct_key_proof_of_correct_h1h2();
+            party_keys.phase1_broadcast_phase3_proof_of_correct_key_proof_of_correct_h1h2(
+                &self.session_id,
+            );
 
         output.push(Msg {
             sender: self.own_party_index as u16,
@@ -38,6 +42,7 @@ impl Round0 {
             own_party_index: self.own_party_index,
             other_parties: self.other_parties.clone(),
             key_params: self.key_params,
+            session_id: self.session_id,
         })
     }
     pub fn is_expensive(&self) -> bool {
@@ -14,6 +14,7 @@ use crate::protocols::gg_2020::state_machine::keygen::{
     types::ProceedResult, 
     party_i::keys::Keys,
 }; 
+use crate::utilities::session_id::SessionId;
 
 pub struct Round1 {
     pub(super) keys: Keys,
@@ -23,6 +24,7 @@ pub struct Round1 {
     pub(super) own_party_index: usize,
     pub(super) other_parties: BTreeSet<usize>,
     pub(super) key_params: Parameters,
+    pub(super) session_id: SessionId,
 }
 
 impl Round1 {
@@ -47,6 +49,7 @@ impl Round1 {
             own_party_index: self.own_party_index,
             other_parties: self.other_parties.clone(),
             key_params: self.key_params,
+            session_id: self.session_id,
         })
     }
     pub fn is_expensive(&self) -> bool {
@@ -19,6 +19,7 @@ use crate::protocols::gg_2020::state_machine::keygen::{
     error::keygen_round_error::KeygenRoundError,
     party_i::keys::Keys,    
 };
+use crate::utilities::session_id::SessionId;
 
 
 
@@ -30,6 +31,7 @@ pub struct Round2 {
     pub(super) own_party_index: usize,
     pub(super) other_parties: BTreeSet<usize>,
     pub(super) key_params: Parameters,
+    pub(super) session_id: SessionId,
 }
 
 impl Round2 {
@@ -50,6 +52,7 @@ impl Round2 {
                 &self.key_params,
                 &received_decom,
                 &self.commitments,
+                &self.session_id,
             )
             .map_err(KeygenRoundError::Round2VerifyCommitments)?;
 
@@ -99,6 +102,7 @@ impl Round2 {
             key_params: self.key_params,
             own_point: own_point.clone(),
             other_points,
+            session_id: self.session_id,
         })
     }
     pub fn is_expensive(&self) -> bool {
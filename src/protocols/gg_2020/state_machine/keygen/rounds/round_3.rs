@@ -1,9 +1,7 @@
 use std::collections::{BTreeSet, HashMap};
 
-use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
 use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
-use sha2::Sha256;
 use round_based::containers::push::Push;
 use round_based::containers::{self, P2PMsgs, Store};
 use round_based::Msg;
@@ -24,6 +22,7 @@ use crate::protocols::gg_2020::state_machine::keygen::{
     error::keygen_round_error::KeygenRoundError,
     party_i::keys::Keys,    
 };
+use crate::utilities::session_id::SessionId;
 
 pub struct Round3 {
     pub(super) keys: Keys,
@@ -39,6 +38,7 @@ pub struct Round3 {
     pub(super) key_params: Parameters,
     pub(super) own_point: SecretShare<Secp256k1>,
     pub(super) other_points: HashMap<usize, SecretShare<Secp256k1>>,
+    pub(super) session_id: SessionId,
 }
 
 impl Round3 {
@@ -68,6 +68,7 @@ impl Round3 {
                 // &vss_schemes,
                 &feldman_vss_list,
                 self.own_party_index.into(),
+                &self.session_id,
             )
             .map_err(KeygenRoundError::Round3VerifyVssConstruct)?;
 
@@ -103,6 +104,7 @@ impl Round3 {
             key_params: self.key_params,
             secret_share: (self.own_point.0, private_share),
             party_to_point_map: PartyToPointMap { points },
+            session_id: self.session_id,
         })
     }
     pub fn is_expensive(&self) -> bool {
@@ -1,9 +1,7 @@
 use std::collections::BTreeSet;
 
-use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
 use curv::cryptographic_primitives::secret_sharing::feldman_vss::VerifiableSS;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Point};
-use sha2::Sha256;
 use paillier::EncryptionKey;
 use round_based::containers::{self, BroadcastMsgs, Store};
 use zk_paillier::zkproofs::DLogStatement;
@@ -22,6 +20,7 @@ use crate::protocols::gg_2020::state_machine::keygen::{
     party_i::shared_keys::SharedKeys,    
     local_key::LocalKey,
 };
+use crate::utilities::session_id::SessionId;
 
 pub struct Round4 {
     pub(super) keys: Keys,
@@ -36,6 +35,7 @@ pub struct Round4 {
     pub(super) key_params: Parameters,
     pub(super) secret_share: SecretShare<Secp256k1>,
     pub(super) party_to_point_map: PartyToPointMap,
+    pub(super) session_id: SessionId,
 }
 
 impl Round4 {
@@ -51,6 +51,7 @@ impl Round4 {
             &dlog_proofs,
             &self.y_vec,
             &self.vss_vec,
+            &self.session_id,
         )
         .map_err(KeygenRoundError::Round4VerifyDLogProof)?;
         let pk_vec = (0..self.key_params.share_count as usize)
@@ -85,7 +86,7 @@ impl Round4 {
         }
 
         let local_key = LocalKey {
-            paillier_dk: self.keys.paillier_keys.dk,
+            paillier_dk: self.keys.paillier_keys.dk.clone(),
             pk_vec,
 
             keys_linear: self.shared_keys.clone(),
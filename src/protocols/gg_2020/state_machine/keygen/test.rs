@@ -1,18 +1,42 @@
-
-use curv::elliptic::curves::Secp256k1;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::arithmetic::traits::*;
+use curv::BigInt;
 use round_based::dev::Simulation;
+use round_based::{Msg, StateMachine};
+use std::fmt;
+use std::time::Duration;
+use zeroize::Zeroize;
 
+use crate::utilities::session_id::SessionId;
 use crate::protocols::gg_2020::state_machine::keygen::{
+    error::keygen_error::KeygenError,
+    error::keygen_round_error::KeygenRoundError,
+    error::local_key_error::{
+        BackupError, LocalKeyFileError, LocalKeyIssue, PaillierKeysUpdateError,
+        ReconstructionError,
+    },
+    local_key::reconstruction::reconstruct_private_key,
+    local_key::validation::ValidationReport,
+    local_key::versioned::{migrate_legacy_local_key, LocalKeyFile},
     local_key::LocalKey, 
+    messages::address::Address,
+    messages::{ProtocolMessage, M},
     Keygen,
 };
+use crate::protocols::gg_2020::state_machine::sign::test::simulate_offline_stage;
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+
+const GOLDEN_LOCAL_KEY_V1: &[u8] = include_bytes!("testdata/local_key_v1.json");
+/// Plain serde_json of `LocalKey`, as written by `gg20_keygen` example of version 0.8
+const GOLDEN_LOCAL_KEY_LEGACY: &[u8] = include_bytes!("testdata/local_key_legacy.json");
 
 pub fn simulate_keygen(t: u16, n: u16) -> Vec<LocalKey<Secp256k1>> {
     let mut simulation = Simulation::new();
     simulation.enable_benchmarks(true);
 
+    let session_id = SessionId::random();
     for i in 1..=n {
-        simulation.add_party(Keygen::new(i, t, n).unwrap());
+        simulation.add_party(Keygen::new(i, t, n, session_id).unwrap());
     }
 
     let keys = simulation.run().unwrap();
@@ -23,6 +47,83 @@ pub fn simulate_keygen(t: u16, n: u16) -> Vec<LocalKey<Secp256k1>> {
     keys
 }
 
+/// Runs the protocol delivering messages in lockstep, returns every message sent
+pub fn collect_messages<SM>(mut parties: Vec<SM>) -> Vec<Msg<SM::MessageBody>>
+where
+    SM: StateMachine,
+    SM::MessageBody: Clone,
+    SM::Err: fmt::Debug,
+{
+    let mut sent = vec![];
+    for _ in 0..32 {
+        if parties.iter().all(|p| p.is_finished()) {
+            return sent;
+        }
+        for party in parties.iter_mut() {
+            if party.wants_to_proceed() {
+                party.proceed().unwrap();
+            }
+        }
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+            .collect();
+        for msg in &outgoing {
+            for party in parties.iter_mut() {
+                let i = party.party_ind();
+                if i != msg.sender && (msg.receiver.is_none() || msg.receiver == Some(i)) {
+                    party.handle_incoming(msg.clone()).unwrap();
+                }
+            }
+        }
+        sent.extend(outgoing);
+    }
+    panic!("protocol didn't complete")
+}
+
+/// Runs the protocol like [collect_messages], but lets `tamper` rewrite every message before
+/// it's delivered to party `i`. Expects every party to fail, returns their errors.
+pub fn collect_errors<SM, F>(mut parties: Vec<SM>, mut tamper: F) -> Vec<SM::Err>
+where
+    SM: StateMachine,
+    SM::MessageBody: Clone,
+    F: FnMut(u16, &mut Msg<SM::MessageBody>),
+{
+    let mut errors: Vec<Option<SM::Err>> = parties.iter().map(|_| None).collect();
+    for _ in 0..32 {
+        if errors.iter().all(Option::is_some) {
+            return errors.into_iter().flatten().collect();
+        }
+        assert!(
+            !parties.iter().any(|p| p.is_finished()),
+            "party completed the protocol"
+        );
+        for (party, error) in parties.iter_mut().zip(&mut errors) {
+            if error.is_none() && party.wants_to_proceed() {
+                *error = party.proceed().err();
+            }
+        }
+        let outgoing: Vec<_> = parties
+            .iter_mut()
+            .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+            .collect();
+        for msg in &outgoing {
+            for (party, error) in parties.iter_mut().zip(&mut errors) {
+                let i = party.party_ind();
+                if error.is_none()
+                    && i != msg.sender
+                    && (msg.receiver.is_none() || msg.receiver == Some(i))
+                {
+                    let mut msg = msg.clone();
+                    tamper(i, &mut msg);
+                    *error = party.handle_incoming(msg).err();
+                }
+            }
+        }
+    }
+    panic!("protocol neither completed nor failed")
+}
+
 #[test]
 fn simulate_keygen_t1_n2() {
     simulate_keygen(1, 2);
@@ -36,4 +137,419 @@ fn simulate_keygen_t1_n3() {
 #[test]
 fn simulate_keygen_t2_n3() {
     simulate_keygen(2, 3);
-}
\ No newline at end of file
+}
+
+#[test]
+fn validate_local_keys() {
+    let keys = simulate_keygen(1, 3);
+    for key in &keys {
+        let report = key.validate();
+        assert!(report.is_valid(), "{}", report);
+    }
+
+    let mut tampered = keys[0].clone();
+    tampered.keys_linear.x_i = Scalar::random();
+    tampered.pk_vec.swap(1, 2);
+    tampered.party_to_point_map.points.remove(&3);
+    tampered.paillier_key_vec[0] = keys[1].paillier_key_vec[1].clone();
+
+    let issues = tampered.validate().issues;
+    assert!(issues.contains(&LocalKeyIssue::SharePublicKeyMismatch { party: 1 }));
+    assert!(issues.contains(&LocalKeyIssue::PaillierKeyMismatch { party: 1 }));
+    assert!(issues.contains(&LocalKeyIssue::PartyNotMapped { party: 3 }));
+
+    let mut tampered = keys[2].clone();
+    tampered.pk_vec.swap(0, 1);
+    let issues = tampered.validate().issues;
+    assert!(issues.contains(&LocalKeyIssue::PublicKeyInterpolation));
+    assert!(issues.contains(&LocalKeyIssue::PkVecInconsistent { party: 3 }));
+}
+
+#[test]
+fn reconstruct_private_key_from_any_t_plus_1_keys() {
+    let keys = simulate_keygen(1, 3);
+    let private_key = reconstruct_private_key(&keys[..2]).unwrap();
+    assert_eq!(Point::generator() * &private_key, keys[0].public_key);
+
+    let subsets = [
+        vec![keys[1].clone(), keys[2].clone()],
+        vec![keys[2].clone(), keys[0].clone()],
+        keys.clone(),
+    ];
+    for subset in &subsets {
+        assert_eq!(reconstruct_private_key(subset).unwrap(), private_key);
+    }
+
+    assert_eq!(
+        reconstruct_private_key(&[]).unwrap_err(),
+        ReconstructionError::NoShares
+    );
+    assert_eq!(
+        reconstruct_private_key(&keys[..1]).unwrap_err(),
+        ReconstructionError::TooFewShares {
+            required: 2,
+            actual: 1
+        }
+    );
+    assert_eq!(
+        reconstruct_private_key(&[keys[0].clone(), keys[0].clone()]).unwrap_err(),
+        ReconstructionError::DuplicateParty { party: 1 }
+    );
+
+    let mut tampered = keys.clone();
+    tampered[1].keys_linear.x_i = Scalar::random();
+    assert_eq!(
+        reconstruct_private_key(&tampered).unwrap_err(),
+        ReconstructionError::InconsistentShares { parties: vec![2] }
+    );
+
+    // Valid key, but a share of a different private key
+    let foreign = simulate_keygen(1, 3);
+    let mixed = [keys[0].clone(), foreign[1].clone(), keys[2].clone()];
+    assert_eq!(
+        reconstruct_private_key(&mixed).unwrap_err(),
+        ReconstructionError::InconsistentShares { parties: vec![2] }
+    );
+}
+
+#[test]
+fn restore_share_from_verifiable_backup() {
+    let mut keys = simulate_keygen(1, 3);
+    let recovery_private_key = Scalar::<Secp256k1>::random();
+    let recovery_key = Point::generator() * &recovery_private_key;
+
+    let backup = keys[1].backup_share(&recovery_key).unwrap();
+    backup.verify(&recovery_key, &keys[0].pk_vec).unwrap();
+    assert_eq!(
+        keys[1].backup_share(&Point::zero()).unwrap_err(),
+        BackupError::ZeroRecoveryKey
+    );
+    assert_eq!(
+        backup
+            .verify(&(Point::generator() * Scalar::random()), &keys[0].pk_vec)
+            .unwrap_err(),
+        BackupError::InvalidProof
+    );
+    let foreign = simulate_keygen(1, 3);
+    assert_eq!(
+        backup.verify(&recovery_key, &foreign[0].pk_vec).unwrap_err(),
+        BackupError::PkVecMismatch
+    );
+    // Backup of another party's share doesn't pass as a backup of party 2
+    let mut swapped = keys[2].backup_share(&recovery_key).unwrap();
+    swapped.party = 2;
+    assert_eq!(
+        swapped.verify(&recovery_key, &keys[0].pk_vec).unwrap_err(),
+        BackupError::InvalidProof
+    );
+
+    let session_id = SessionId::random();
+    assert!(matches!(
+        backup.restore(&Scalar::random(), &session_id),
+        Err(BackupError::Decryption) | Err(BackupError::ShareMismatch { party: 2 })
+    ));
+    let mut tampered = backup.clone();
+    tampered.public_key = foreign[0].public_key.clone();
+    assert!(matches!(
+        tampered.restore(&recovery_private_key, &session_id),
+        Err(BackupError::InvalidLocalKey(ValidationReport { .. }))
+    ));
+
+    let (restored, update) = backup.restore(&recovery_private_key, &session_id).unwrap();
+    assert!(restored.validate().is_valid(), "{}", restored.validate());
+    assert_eq!(restored.keys_linear.x_i, keys[1].keys_linear.x_i);
+    assert_eq!(restored.secret_share.0, keys[1].secret_share.0);
+    assert_ne!(restored.paillier_key_vec[1].n, keys[1].paillier_key_vec[1].n);
+
+    assert_eq!(
+        keys[1]
+            .apply_paillier_keys_update(&update, &session_id)
+            .unwrap_err(),
+        PaillierKeysUpdateError::OwnParty
+    );
+    assert!(matches!(
+        keys[0].apply_paillier_keys_update(&update, &SessionId::random()),
+        Err(PaillierKeysUpdateError::InvalidProofs { party: 2 })
+    ));
+    // Keys are valid, but the update doesn't prove knowledge of share of party 3
+    let mut foreign = update.clone();
+    foreign.party = 3;
+    assert_eq!(
+        keys[0]
+            .apply_paillier_keys_update(&foreign, &session_id)
+            .unwrap_err(),
+        PaillierKeysUpdateError::NotShareHolder { party: 3 }
+    );
+    for key in [0, 2] {
+        keys[key]
+            .apply_paillier_keys_update(&update, &session_id)
+            .unwrap();
+    }
+    keys[1] = restored;
+    simulate_offline_stage(keys, &[2, 3]);
+}
+
+#[test]
+fn versioned_local_key_golden_file() {
+    let file = LocalKeyFile::from_bytes(GOLDEN_LOCAL_KEY_V1).unwrap();
+    let key = file.to_local_key().unwrap();
+
+    assert_eq!(key.own_party_index, 1);
+    assert_eq!(key.public_key, Point::generator().to_point());
+    assert_eq!(key.keys_linear.x_i, Scalar::from_bigint(&BigInt::from(2)));
+    assert_eq!(
+        key.pk_vec[1],
+        Point::generator() * Scalar::from_bigint(&BigInt::from(3))
+    );
+    assert_eq!(key.paillier_key_vec[1].n, BigInt::from(35));
+    assert_eq!(key.party_to_point_map.points.get(&2), Some(&2));
+    let report = key.validate();
+    assert!(report.is_valid(), "{}", report);
+
+    // Encoding the key back must reproduce the golden file exactly
+    assert_eq!(LocalKeyFile::new(&key, file.created_at).unwrap(), file);
+}
+
+#[test]
+fn versioned_local_key_round_trip() {
+    let keys = simulate_keygen(1, 2);
+    for key in &keys {
+        let bytes = key.to_versioned_bytes().unwrap();
+        let decoded = LocalKey::<Secp256k1>::from_versioned_bytes(&bytes).unwrap();
+        assert!(decoded.validate().is_valid());
+        assert_eq!(
+            LocalKeyFile::new(&decoded, 0).unwrap(),
+            LocalKeyFile::new(key, 0).unwrap()
+        );
+    }
+}
+
+#[test]
+fn legacy_local_key_is_migrated() {
+    // Legacy fixture holds the same key as the v1 golden file
+    let key = LocalKeyFile::from_bytes(GOLDEN_LOCAL_KEY_V1)
+        .unwrap()
+        .to_local_key()
+        .unwrap();
+
+    let migrated = LocalKey::<Secp256k1>::from_versioned_bytes(GOLDEN_LOCAL_KEY_LEGACY).unwrap();
+    assert_eq!(
+        LocalKeyFile::new(&migrated, 0).unwrap(),
+        LocalKeyFile::new(&key, 0).unwrap()
+    );
+
+    let rewritten =
+        LocalKeyFile::from_bytes(&migrate_legacy_local_key(GOLDEN_LOCAL_KEY_LEGACY).unwrap())
+            .unwrap();
+    assert_eq!(rewritten.key, LocalKeyFile::new(&key, 0).unwrap().key);
+}
+
+#[test]
+fn versioned_local_key_rejects_malformed_files() {
+    let golden: serde_json::Value = serde_json::from_slice(GOLDEN_LOCAL_KEY_V1).unwrap();
+    let decode = |value: &serde_json::Value| {
+        LocalKey::<Secp256k1>::from_versioned_bytes(&serde_json::to_vec(value).unwrap())
+    };
+
+    let mut file = golden.clone();
+    file["version"] = 2.into();
+    assert!(matches!(
+        decode(&file),
+        Err(LocalKeyFileError::UnsupportedVersion(2))
+    ));
+
+    let mut file = golden.clone();
+    file["curve"] = "ed25519".into();
+    assert!(matches!(
+        decode(&file),
+        Err(LocalKeyFileError::UnsupportedCurve(_))
+    ));
+
+    let mut file = golden.clone();
+    file["key"]["x_i"] = "02".into();
+    assert!(matches!(
+        decode(&file),
+        Err(LocalKeyFileError::InvalidField { field: "x_i" })
+    ));
+
+    let mut file = golden;
+    file["key"]["paillier_p"] = "03".into();
+    assert!(matches!(
+        decode(&file),
+        Err(LocalKeyFileError::InvalidField {
+            field: "paillier_p"
+        })
+    ));
+}
+
+#[test]
+fn local_key_zeroize_wipes_secrets() {
+    let mut key = LocalKeyFile::from_bytes(GOLDEN_LOCAL_KEY_V1)
+        .unwrap()
+        .to_local_key()
+        .unwrap();
+    key.zeroize();
+
+    assert_eq!(key.keys_linear.x_i, Scalar::zero());
+    assert_eq!(key.secret_share.1, Scalar::zero());
+    assert_eq!(key.paillier_dk.p, BigInt::from(0));
+    assert_eq!(key.paillier_dk.q, BigInt::from(0));
+    // Public data is left intact
+    assert_eq!(key.public_key, Point::generator().to_point());
+
+}
+
+#[test]
+fn local_key_debug_doesnt_reveal_secrets() {
+    let mut key = LocalKeyFile::from_bytes(GOLDEN_LOCAL_KEY_V1)
+        .unwrap()
+        .to_local_key()
+        .unwrap();
+    key.keys_linear.x_i = Scalar::random();
+    key.secret_share.1 = key.keys_linear.x_i.clone();
+    key.paillier_dk.p = BigInt::sample(1024);
+    key.paillier_dk.q = BigInt::sample(1024);
+
+    let secrets = [
+        key.keys_linear.x_i.to_bigint(),
+        key.paillier_dk.p.clone(),
+        key.paillier_dk.q.clone(),
+    ];
+    for formatted in [format!("{:?}", key), format!("{:#?}", key)].iter() {
+        for secret in secrets.iter() {
+            assert!(!formatted.contains(&secret.to_string()));
+            assert!(!formatted.contains(&secret.to_hex()));
+            assert!(!formatted.contains(&hex::encode(secret.to_bytes())));
+        }
+    }
+}
+
+#[test]
+fn simulate_keygen_with_echo_broadcast() {
+    let session_id = SessionId::random();
+    let mut simulation = Simulation::new();
+    for i in 1..=3 {
+        simulation.add_party(
+            Keygen::new(i, 1, 3, session_id)
+                .unwrap()
+                .with_echo_broadcast(),
+        );
+    }
+    let keys = simulation.run().unwrap();
+    assert!(keys.iter().all(|key| key.public_key == keys[0].public_key));
+}
+
+#[test]
+fn echo_broadcast_detects_equivocation_in_keygen() {
+    let session_id = SessionId::random();
+    let parties = (1..=3)
+        .map(|i| {
+            Keygen::new(i, 1, 3, session_id)
+                .unwrap()
+                .with_echo_broadcast()
+        })
+        .collect();
+    // Party 1 sends party 3 a round 1 broadcast that differs from the one seen by party 2.
+    // Keygen doesn't look at `recipient`, so only the echo round can notice it
+    let errors = collect_errors(parties, |i, msg| {
+        if let (1, 3, ProtocolMessage(M::Round1(m))) = (msg.sender, i, &mut msg.body) {
+            m.recipient = Address::Peer(3);
+        }
+    });
+    for error in errors {
+        match error {
+            KeygenError::ProceedRound(KeygenRoundError::Equivocation(e)) => {
+                assert_eq!(e.parties, vec![1])
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn keygen_fails_if_session_ids_differ() {
+    let mut simulation = Simulation::new();
+    for i in 1..=3 {
+        simulation.add_party(Keygen::new(i, 1, 3, SessionId::new(&[i as u8])).unwrap());
+    }
+    assert!(simulation.run().is_err());
+}
+
+#[test]
+fn round_timeout_reports_missing_parties() {
+    let session_id = SessionId::random();
+    let mut party1 = Keygen::new(1, 1, 3, session_id)
+        .unwrap()
+        .with_round_timeout(Duration::from_secs(10));
+    let mut party2 = Keygen::new(2, 1, 3, session_id).unwrap();
+    assert_eq!(party1.round_timeout(), Some(Duration::from_secs(10)));
+
+    for msg in party2.message_queue().drain(..).collect::<Vec<_>>() {
+        party1.handle_incoming(msg).unwrap();
+    }
+    match party1.round_timeout_reached() {
+        KeygenError::Timeout {
+            round,
+            missing_parties,
+        } => {
+            assert_eq!(round, 1);
+            assert_eq!(missing_parties, vec![3]);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn keygen_messages_round_trip_through_wire_format() {
+    let session_id = SessionId::random();
+    let parties = (1..=3)
+        .map(|i| Keygen::new(i, 1, 3, session_id).unwrap())
+        .collect();
+    for msg in collect_messages(parties) {
+        let bytes = msg.body.to_bytes();
+        let decoded = ProtocolMessage::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&msg.body).unwrap()
+        );
+
+        let mut truncated = bytes.clone();
+        truncated.pop();
+        assert!(ProtocolMessage::from_bytes(&truncated).is_err());
+    }
+}
+
+#[test]
+fn keygen_wire_format_size_regression() {
+    let session_id = SessionId::random();
+    let parties = (1..=3)
+        .map(|i| Keygen::new(i, 1, 3, session_id).unwrap())
+        .collect();
+    let (mut binary_total, mut json_total) = (0, 0);
+    for msg in collect_messages(parties) {
+        let binary = msg.body.to_bytes().len();
+        let limit = match msg.body.round() {
+            1 => 6 * 1024,
+            2 => 128,
+            3 => 256,
+            4 => 128,
+            _ => 256,
+        };
+        assert!(
+            binary <= limit,
+            "round {} message takes {} bytes, expected at most {}",
+            msg.body.round(),
+            binary,
+            limit
+        );
+        binary_total += binary;
+        json_total += serde_json::to_vec(&msg.body).unwrap().len();
+    }
+    assert!(
+        binary_total * 3 < json_total * 2,
+        "binary encoding ({} bytes) is not much smaller than json ({} bytes)",
+        binary_total,
+        json_total
+    );
+}
@@ -1,9 +1,17 @@
 use std::fmt;
 
+use curv::elliptic::curves::Curve;
+
+use crate::protocols::gg_2020::party_i;
 use crate::protocols::gg_2020::state_machine::keygen::{
+    local_key::LocalKey,
+    party_i::keys::Keys,
+    party_i::paillier_keys::PaillierKeys,
+    party_i::shared_keys::SharedKeys,
     Keygen, 
     R
 };
+use crate::utilities::fingerprint::Fingerprint;
 
 
 
@@ -15,6 +23,7 @@ impl fmt::Debug for Keygen {
             R::Round2(_) => "2",
             R::Round3(_) => "3",
             R::Round4(_) => "4",
+            R::Echo(_) => "[Echo]",
             R::Final(_) => "[Final]",
             R::Gone => "[Gone]",
         };
@@ -34,16 +43,79 @@ impl fmt::Debug for Keygen {
             Some(msgs) => format!("[{}/{}]", msgs.messages_received(), msgs.messages_total()),
             None => "[None]".into(),
         };
+        let msgs_echo = match self.msgs_echo.as_ref() {
+            Some(msgs) => format!("[{}/{}]", msgs.messages_received(), msgs.messages_total()),
+            None => "[None]".into(),
+        };
         write!(
             f,
-            "{{Keygen at round={} msgs1={} msgs2={} msgs3={} msgs4={} queue=[len={}]}}",
+            "{{Keygen at round={} msgs1={} msgs2={} msgs3={} msgs4={} msgs_echo={} queue=[len={}]}}",
             current_round,
             msgs1,
             msgs2,
             msgs3,
             msgs4,
+            msgs_echo,
             self.msgs_queue.len()
         )
     }
 }
 
+impl fmt::Debug for SharedKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedKeys")
+            .field("y", &self.y)
+            .field("x_i", &Fingerprint::of_scalar(&self.x_i))
+            .finish()
+    }
+}
+
+impl fmt::Debug for party_i::SharedKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SharedKeys")
+            .field("y", &self.y)
+            .field("x_i", &Fingerprint::of_scalar(&self.x_i))
+            .finish()
+    }
+}
+
+impl fmt::Debug for PaillierKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PaillierKeys")
+            .field("dk", &Fingerprint::of_bigint(&self.dk.p))
+            .field("ek", &self.ek)
+            .finish()
+    }
+}
+
+impl<E: Curve> fmt::Debug for Keys<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Keys")
+            .field("party_index", &self.party_index)
+            .field("u_i", &Fingerprint::of_scalar(&self.u_i))
+            .field("y_i", &self.y_i)
+            .field("paillier_keys", &self.paillier_keys)
+            .field("n_tilde", &self.n_tilde)
+            .field("h1", &self.h1)
+            .field("h2", &self.h2)
+            .field("xhi", &Fingerprint::of_bigint(&self.xhi))
+            .field("xhi_inv", &Fingerprint::of_bigint(&self.xhi_inv))
+            .finish()
+    }
+}
+
+impl<E: Curve> fmt::Debug for LocalKey<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LocalKey")
+            .field("own_party_index", &self.own_party_index)
+            .field("other_parties", &self.other_parties)
+            .field("key_params", &self.key_params)
+            .field("public_key", &self.public_key)
+            .field("pk_vec", &self.pk_vec)
+            .field("keys_linear", &self.keys_linear)
+            .field("paillier_dk", &Fingerprint::of_bigint(&self.paillier_dk.p))
+            .field("secret_share", &(self.secret_share.0, Fingerprint::of_scalar(&self.secret_share.1)))
+            .field("party_to_point_map", &self.party_to_point_map)
+            .finish_non_exhaustive()
+    }
+}
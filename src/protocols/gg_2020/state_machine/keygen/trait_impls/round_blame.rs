@@ -16,6 +16,7 @@ impl RoundBlame for Keygen {
         let store2_blame = self.msgs2.as_ref().map(|s| s.blame()).unwrap_or_default();
         let store3_blame = self.msgs3.as_ref().map(|s| s.blame()).unwrap_or_default();
         let store4_blame = self.msgs4.as_ref().map(|s| s.blame()).unwrap_or_default();
+        let store_echo_blame = self.msgs_echo.as_ref().map(|s| s.blame()).unwrap_or_default();
 
         let default = (0, vec![]);
         match &self.round {
@@ -24,6 +25,7 @@ impl RoundBlame for Keygen {
             R::Round2(_) => store2_blame,
             R::Round3(_) => store3_blame,
             R::Round4(_) => store4_blame,
+            R::Echo(_) => store_echo_blame,
             R::Final(_) | R::Gone => default,
         }
     }
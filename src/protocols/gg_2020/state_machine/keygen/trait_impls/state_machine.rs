@@ -5,6 +5,7 @@ use round_based::containers::*;
 use round_based::{Msg, StateMachine};
 
 use crate::protocols::gg_2020::state_machine::keygen::messages::address::Address;
+use crate::protocols::gg_2020::state_machine::traits::RoundBlame;
 use crate::protocols::gg_2020::state_machine::keygen::{
     Keygen,
     local_key::LocalKey,
@@ -27,6 +28,12 @@ impl StateMachine for Keygen {
 
     fn handle_incoming(&mut self, msg: Msg<ProtocolMessage>) -> KeygenResult<()> {
         let current_round = self.current_round();
+        let sender = msg.sender;
+        let echoed = match &msg.body {
+            ProtocolMessage(M::Echo(_)) => None,
+            body if self.echo.is_some() && msg.receiver.is_none() => Some(body.clone()),
+            _ => None,
+        };
 
         match msg.body {
             ProtocolMessage(M::Round1(m)) => {
@@ -38,8 +45,15 @@ impl StateMachine for Keygen {
                         msg_round: 1,
                     })?;
 
+                if m.sender != usize::from(msg.sender) {
+                    return Err(KeygenError::SenderMismatch {
+                        msg_round: 1,
+                        sender: msg.sender,
+                        claimed: m.sender,
+                    });
+                }
+
                 let mut m_mut = m.clone();
-                m_mut.sender = msg.sender as usize;
                 m_mut.recipient = Address::Broadcast;
                 if let Some(receiver) = msg.receiver {
                     m_mut.recipient = Address::Peer(receiver as usize);
@@ -52,7 +66,6 @@ impl StateMachine for Keygen {
                         body: m_mut,
                     })
                     .map_err(KeygenError::HandleMessage)?;
-                self.proceed_round(false)
             }
             ProtocolMessage(M::Round2(m)) => {
                 let store = self
@@ -63,8 +76,15 @@ impl StateMachine for Keygen {
                         msg_round: 2,
                     })?;
 
+                if m.sender != usize::from(msg.sender) {
+                    return Err(KeygenError::SenderMismatch {
+                        msg_round: 2,
+                        sender: msg.sender,
+                        claimed: m.sender,
+                    });
+                }
+
                 let mut m_mut = m.clone();
-                m_mut.sender = msg.sender as usize;
                 m_mut.recipient = Address::Broadcast;
                 if let Some(receiver) = msg.receiver {
                     m_mut.recipient = Address::Peer(receiver as usize);
@@ -77,7 +97,6 @@ impl StateMachine for Keygen {
                         body: m_mut,
                     })
                     .map_err(KeygenError::HandleMessage)?;
-                self.proceed_round(false)
             }
             ProtocolMessage(M::Round3(m)) => {
                 let store = self
@@ -88,8 +107,15 @@ impl StateMachine for Keygen {
                         msg_round: 3,
                     })?;
 
+                if m.sender != usize::from(msg.sender) {
+                    return Err(KeygenError::SenderMismatch {
+                        msg_round: 3,
+                        sender: msg.sender,
+                        claimed: m.sender,
+                    });
+                }
+
                 let mut m_mut = m.clone();
-                m_mut.sender = msg.sender as usize;
                 m_mut.recipient = Address::Broadcast;
                 if let Some(receiver) = msg.receiver {
                     m_mut.recipient = Address::Peer(receiver as usize);
@@ -102,7 +128,6 @@ impl StateMachine for Keygen {
                         body: m_mut,
                     })
                     .map_err(KeygenError::HandleMessage)?;
-                self.proceed_round(false)
             }
             ProtocolMessage(M::Round4(m)) => {
                 let store = self
@@ -113,8 +138,15 @@ impl StateMachine for Keygen {
                         msg_round: 4,
                     })?;
 
+                if m.sender != usize::from(msg.sender) {
+                    return Err(KeygenError::SenderMismatch {
+                        msg_round: 4,
+                        sender: msg.sender,
+                        claimed: m.sender,
+                    });
+                }
+
                 let mut m_mut = m.clone();
-                m_mut.sender = msg.sender as usize;
                 m_mut.recipient = Address::Broadcast;
                 if let Some(receiver) = msg.receiver {
                     m_mut.recipient = Address::Peer(receiver as usize);
@@ -127,9 +159,29 @@ impl StateMachine for Keygen {
                         body: m_mut,
                     })
                     .map_err(KeygenError::HandleMessage)?;
-                self.proceed_round(false)
+            }
+            ProtocolMessage(M::Echo(m)) => {
+                let store = self
+                    .msgs_echo
+                    .as_mut()
+                    .ok_or(KeygenError::ReceivedOutOfOrderMessage {
+                        current_round,
+                        msg_round: 5,
+                    })?;
+                store
+                    .push_msg(Msg {
+                        sender: msg.sender,
+                        receiver: msg.receiver,
+                        body: m,
+                    })
+                    .map_err(KeygenError::HandleMessage)?;
             }
         }
+
+        if let (Some(echo), Some(body)) = (self.echo.as_mut(), echoed) {
+            echo.record(sender, &body);
+        }
+        self.proceed_round(false)
     }
 
     fn message_queue(&mut self) -> &mut Vec<Msg<ProtocolMessage>> {
@@ -141,6 +193,7 @@ impl StateMachine for Keygen {
         let store2_wants_more = self.msgs2.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store3_wants_more = self.msgs3.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store4_wants_more = self.msgs4.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store_echo_wants_more = self.msgs_echo.as_ref().map(|s| s.wants_more()).unwrap_or(false);
 
         match &self.round {
             R::Round0(_) => true,
@@ -148,6 +201,7 @@ impl StateMachine for Keygen {
             R::Round2(_) => !store2_wants_more,
             R::Round3(_) => !store3_wants_more,
             R::Round4(_) => !store4_wants_more,
+            R::Echo(_) => !store_echo_wants_more,
             R::Final(_) | R::Gone => false,
         }
     }
@@ -157,11 +211,15 @@ impl StateMachine for Keygen {
     }
 
     fn round_timeout(&self) -> Option<Duration> {
-        None
+        self.round_timeout
     }
 
     fn round_timeout_reached(&mut self) -> Self::Err {
-        panic!("no timeout was set")
+        let (_, missing_parties) = self.round_blame();
+        KeygenError::Timeout {
+            round: self.current_round(),
+            missing_parties,
+        }
     }
 
     fn is_finished(&self) -> bool {
@@ -188,12 +246,17 @@ impl StateMachine for Keygen {
             R::Round2(_) => 2,
             R::Round3(_) => 3,
             R::Round4(_) => 4,
-            R::Final(_) | R::Gone => 5,
+            R::Echo(_) => 5,
+            R::Final(_) | R::Gone => self.total_rounds().unwrap_or(4) + 1,
         }
     }
 
     fn total_rounds(&self) -> Option<u16> {
-        Some(4)
+        if self.echo_broadcast {
+            Some(5)
+        } else {
+            Some(4)
+        }
     }
 
     fn party_ind(&self) -> u16 {
@@ -0,0 +1,210 @@
+//! Recovery of a lost share of a single party
+//!
+//! When party `r` loses its [LocalKey], its share can be restored without a new keygen. First
+//! `r` creates a [LostParty], which generates fresh Paillier key and `(N_tilde, h1, h2)`. Then
+//! at least `t+1` remaining parties run [ShareRecovery]: every helper `j` splits its term
+//! `λ_j(p_r) · x_j` of the lost share
+//! `x_r = Σ λ_j(p_r) · x_j` into random additive sub-shares, one per helper, and commits to them.
+//! Commitments of every helper must add up to `pk_j^{λ_j(p_r)}`, and every sub-share must match
+//! its commitment. Each helper sums up received sub-shares into a [RecoveryShare] and privately
+//! sends it to party `r`, who combines the shares with [LostParty::complete].
+//!
+//! No single helper learns `x_r`, and party `r` doesn't learn shares of the helpers. Resulting
+//! [LocalKey] is consistent with `pk_vec` of every other party. Along with the key, party `r`
+//! gets a [PaillierKeysUpdate] proving knowledge of `x_r`, which every other party (the helpers
+//! included) must [apply](LocalKey::apply_paillier_keys_update) before signing with `r`.
+//!
+//! [LocalKey]: super::keygen::local_key::LocalKey
+//! [PaillierKeysUpdate]: super::keygen::local_key::paillier_update::PaillierKeysUpdate
+//! [LocalKey::apply_paillier_keys_update]: super::keygen::local_key::LocalKey::apply_paillier_keys_update
+//! [LostParty]: lost_party::LostParty
+//! [LostParty::complete]: lost_party::LostParty::complete
+//! [RecoveryShare]: lost_party::RecoveryShare
+
+pub mod error;
+pub mod lost_party;
+pub mod messages;
+mod rounds;
+pub mod trait_impls;
+#[cfg(test)]
+mod test;
+
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::mem::replace;
+use std::time::Duration;
+
+use curv::elliptic::curves::Secp256k1;
+use round_based::containers::push::{Push, PushExt};
+use round_based::containers::{BroadcastMsgs, MessageStore, P2PMsgs, Store};
+use round_based::Msg;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+
+use self::error::{InternalError, RecoveryError};
+use self::messages::{HelperBroadcast, ProtocolMessage, SubShare, M};
+use self::rounds::{round_0::Round0, round_1::Round1, round_2::Round2, R};
+
+/// Helper in recovery of a lost share
+///
+/// Outputs [RecoveryShare](lost_party::RecoveryShare), which must be sent to the lost party over
+/// a private and authenticated channel.
+pub struct ShareRecovery {
+    round: R,
+
+    msgs1: Option<Store<BroadcastMsgs<HelperBroadcast>>>,
+    msgs2: Option<Store<P2PMsgs<SubShare>>>,
+
+    round_timeout: Option<Duration>,
+
+    msgs_queue: Vec<Msg<ProtocolMessage>>,
+
+    party_i: u16,
+    party_n: u16,
+}
+
+impl ShareRecovery {
+    /// Constructs a helper party
+    ///
+    /// Takes party index `i` (in range `[1; n]`), list `s_l` of helpers' indexes from keygen
+    /// protocol (`s_l[i-1]` must be an index of this party that was used in keygen protocol),
+    /// party local secret share `local_key`, and keygen index of the lost party `lost_party`.
+    ///
+    /// Returns error if given arguments are contradicting.
+    pub fn new(
+        i: u16,
+        s_l: Vec<u16>,
+        local_key: LocalKey<Secp256k1>,
+        lost_party: usize,
+    ) -> Result<Self, RecoveryError> {
+        let required = usize::from(local_key.key_params.threshold) + 1;
+        if s_l.len() < required {
+            return Err(RecoveryError::TooFewParties {
+                required,
+                actual: s_l.len(),
+            });
+        }
+        let n =
+            u16::try_from(s_l.len()).map_err(|_| RecoveryError::TooManyParties { n: s_l.len() })?;
+        if i == 0 || i > n {
+            return Err(RecoveryError::InvalidPartyIndex);
+        }
+
+        let keygen_n = local_key.key_params.share_count;
+        let points = &local_key.party_to_point_map.points;
+        if s_l
+            .iter()
+            .any(|&j| j == 0 || j > keygen_n || !points.contains_key(&usize::from(j)))
+        {
+            return Err(RecoveryError::InvalidSl);
+        }
+        if s_l.iter().collect::<BTreeSet<_>>().len() != s_l.len() {
+            return Err(RecoveryError::InvalidSl);
+        }
+        if usize::from(s_l[usize::from(i - 1)]) != local_key.own_party_index {
+            return Err(RecoveryError::InvalidSl);
+        }
+
+        if lost_party == 0
+            || lost_party > usize::from(keygen_n)
+            || !points.contains_key(&lost_party)
+            || s_l.iter().any(|&j| usize::from(j) == lost_party)
+        {
+            return Err(RecoveryError::InvalidLostParty { party: lost_party });
+        }
+
+        let mut state = Self {
+            round: R::Round0(Round0 {
+                i,
+                s_l,
+                local_key,
+                lost_party,
+            }),
+
+            msgs1: Some(Round1::expects_messages(i, n)),
+            msgs2: Some(Round2::expects_messages(i, n)),
+
+            round_timeout: None,
+
+            msgs_queue: vec![],
+
+            party_i: i,
+            party_n: n,
+        };
+
+        state.proceed_round(false)?;
+        Ok(state)
+    }
+
+    /// Sets a deadline for every round
+    ///
+    /// If party doesn't receive all messages of the round within `timeout`, recovery terminates
+    /// with [RecoveryError::Timeout] listing parties whose messages are missing.
+    pub fn with_round_timeout(mut self, timeout: Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    fn gmap_queue<'a, T, F>(&'a mut self, mut f: F) -> impl Push<Msg<T>> + 'a
+    where
+        F: FnMut(T) -> M + 'a,
+    {
+        (&mut self.msgs_queue).gmap(move |m: Msg<T>| m.map_body(|m| ProtocolMessage(f(m))))
+    }
+
+    /// Proceeds round state if it received enough messages and if it's cheap to compute or
+    /// `may_block == true`
+    fn proceed_round(&mut self, may_block: bool) -> Result<(), RecoveryError> {
+        let store1_wants_more = self.msgs1.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store2_wants_more = self.msgs2.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+
+        let next_state: R;
+        let try_again: bool = match replace(&mut self.round, R::Gone) {
+            R::Round0(round) if !round.is_expensive() || may_block => {
+                next_state = round.proceed(self.gmap_queue(M::Round1)).map(R::Round1)?;
+                true
+            }
+            s @ R::Round0(_) => {
+                next_state = s;
+                false
+            }
+            R::Round1(round) if !store1_wants_more && (!round.is_expensive() || may_block) => {
+                let store = self.msgs1.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveRoundMessages)?;
+                next_state = round
+                    .proceed(msgs, self.gmap_queue(M::Round2))
+                    .map(R::Round2)?;
+                true
+            }
+            s @ R::Round1(_) => {
+                next_state = s;
+                false
+            }
+            R::Round2(round) if !store2_wants_more && (!round.is_expensive() || may_block) => {
+                let store = self.msgs2.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveRoundMessages)?;
+                next_state = round.proceed(msgs).map(R::Final)?;
+                true
+            }
+            s @ R::Round2(_) => {
+                next_state = s;
+                false
+            }
+            s @ R::Final(_) | s @ R::Gone => {
+                next_state = s;
+                false
+            }
+        };
+
+        self.round = next_state;
+        if try_again {
+            self.proceed_round(may_block)
+        } else {
+            Ok(())
+        }
+    }
+}
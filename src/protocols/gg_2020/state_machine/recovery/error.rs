@@ -0,0 +1,93 @@
+use round_based::{containers::StoreErr, IsCritical};
+use thiserror::Error;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::validation::ValidationReport;
+
+/// Error type of lost share recovery
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RecoveryError {
+    /// Fewer than `t+1` helpers
+    #[error("at least {required} helpers are required, got {actual}")]
+    TooFewParties { required: usize, actual: usize },
+    /// Number of helpers doesn't fit into `u16`
+    #[error("too many helpers: {n}")]
+    TooManyParties { n: usize },
+    /// Party index `i` is not in range `[1; n]`
+    #[error("party index is not in range [1; n]")]
+    InvalidPartyIndex,
+    /// `s_l` contains duplicates, unknown parties, or doesn't map local party to its own keygen index
+    #[error("list of helpers s_l is invalid")]
+    InvalidSl,
+    /// Lost party is unknown or listed among the helpers
+    #[error("party {party} can't be recovered")]
+    InvalidLostParty { party: usize },
+
+    /// Some helpers hold a different public key or `pk_vec`
+    #[error("parties {parties:?} hold a different key")]
+    KeyMismatch { parties: Vec<u16> },
+    /// Commitments of some helpers don't add up to their term of the lost share
+    #[error("parties {parties:?} sent invalid sub-share commitments")]
+    InvalidCommitments { parties: Vec<u16> },
+    /// Some helpers sent sub-shares that don't match their commitments
+    #[error("parties {parties:?} sent invalid sub-shares")]
+    InvalidSubShares { parties: Vec<u16> },
+
+    /// Lost party didn't get exactly one share from every helper
+    #[error("expected exactly one share from every helper")]
+    MissingShares,
+    /// Shares were produced in different recoveries or for a different party
+    #[error("shares don't belong to the same recovery of this party")]
+    InconsistentShares,
+    /// Shares of listed helpers don't match their commitments
+    #[error("helpers {parties:?} sent invalid shares")]
+    InvalidShares { parties: Vec<u16> },
+    /// Recovered local key is inconsistent
+    #[error("recovered local key is invalid: {0}")]
+    InvalidLocalKey(ValidationReport),
+
+    /// Received message didn't pass pre-validation
+    #[error("received message didn't pass pre-validation: {0}")]
+    HandleMessage(#[source] StoreErr),
+    /// Received message which we didn't expect to receive now (e.g. message from previous round)
+    #[error(
+        "didn't expect to receive message from round {msg_round} (being at round {current_round})"
+    )]
+    ReceivedOutOfOrderMessage { current_round: u16, msg_round: u16 },
+    /// Party didn't receive all messages of the round in time
+    #[error("round {round} timed out, missing messages from parties {missing_parties:?}")]
+    Timeout {
+        round: u16,
+        missing_parties: Vec<u16>,
+    },
+    /// `pick_output` called twice
+    #[error("pick_output called twice")]
+    DoublePickOutput,
+
+    /// Some internal assertions were failed, which is a bug
+    #[doc(hidden)]
+    #[error("internal error: {0:?}")]
+    InternalError(InternalError),
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InternalError {
+    /// Messages store reported that it received all messages it wanted to receive, but refused
+    /// to return message container
+    RetrieveRoundMessages(StoreErr),
+    #[doc(hidden)]
+    StoreGone,
+}
+
+impl IsCritical for RecoveryError {
+    fn is_critical(&self) -> bool {
+        true
+    }
+}
+
+impl From<InternalError> for RecoveryError {
+    fn from(err: InternalError) -> Self {
+        Self::InternalError(err)
+    }
+}
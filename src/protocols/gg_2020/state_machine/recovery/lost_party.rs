@@ -0,0 +1,305 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use curv::arithmetic::traits::*;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::{
+    ShamirSecretSharing, VerifiableSS,
+};
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+use zk_paillier::zkproofs::DLogStatement;
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    local_key::{paillier_update::PaillierKeysUpdate, LocalKey},
+    messages::parameters::Parameters,
+    party_i::keys::Keys,
+    party_i::party_to_point_map::PartyToPointMap,
+    party_i::shared_keys::SharedKeys,
+};
+use crate::protocols::gg_2020::state_machine::recovery::error::RecoveryError;
+use crate::utilities::fingerprint::Fingerprint;
+use crate::utilities::secret_scalar::SecretScalar;
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire;
+
+/// Share of the lost party produced by a single helper
+///
+/// Contains a secret value, so it must be delivered to the lost party over a private and
+/// authenticated channel.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecoveryShare {
+    /// Keygen index of the lost party
+    pub lost_party: usize,
+    /// Index of the helper in range `[1; s_l.len()]`
+    pub helper: u16,
+    /// Keygen indexes of the helpers
+    pub s_l: Vec<u16>,
+    pub public_key: Point<Secp256k1>,
+    pub pk_vec: Vec<Point<Secp256k1>>,
+    pub key_params: Parameters,
+    pub party_to_point_map: PartyToPointMap,
+    pub paillier_key_vec: Vec<EncryptionKey>,
+    pub h1_h2_n_tilde_vec: Vec<DLogStatement>,
+    /// `commitments[j-1][k-1]` commits to sub-share sent by helper `j` to helper `k`
+    pub commitments: Vec<Vec<Point<Secp256k1>>>,
+    /// Sum of sub-shares received by the helper
+    pub share: Scalar<Secp256k1>,
+}
+
+impl Zeroize for RecoveryShare {
+    fn zeroize(&mut self) {
+        self.share = Scalar::zero();
+    }
+}
+
+impl Drop for RecoveryShare {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for RecoveryShare {}
+
+impl fmt::Debug for RecoveryShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecoveryShare")
+            .field("lost_party", &self.lost_party)
+            .field("helper", &self.helper)
+            .field("s_l", &self.s_l)
+            .field("public_key", &self.public_key)
+            .field("commitments", &self.commitments)
+            .field("share", &Fingerprint::of_scalar(&self.share))
+            .finish()
+    }
+}
+
+/// Party which lost its [LocalKey] and awaits shares from the helpers
+pub struct LostParty {
+    party: usize,
+    keys: Keys,
+    session_id: SessionId,
+}
+
+impl LostParty {
+    /// Generates fresh Paillier key and `(N_tilde, h1, h2)` of party `party` (its index in
+    /// keygen)
+    ///
+    /// Keys are announced once the share is recovered, in [PaillierKeysUpdate] proven under
+    /// `session_id`.
+    pub fn new(party: usize, session_id: &SessionId) -> Self {
+        Self {
+            party,
+            keys: Keys::create_safe_prime(party),
+            session_id: *session_id,
+        }
+    }
+
+    /// Combines shares received from every helper into the local key
+    ///
+    /// Every share is checked against the helpers' commitments, and commitments of every
+    /// helper are checked against its `pk_vec` entry. Returns [RecoveryError::InvalidShares]
+    /// listing helpers who failed the checks.
+    ///
+    /// Along with the key, returns an update which proves knowledge of the recovered share. It
+    /// must be sent to every other party, see [LocalKey::apply_paillier_keys_update].
+    pub fn complete(
+        &self,
+        shares: &[RecoveryShare],
+    ) -> Result<(LocalKey<Secp256k1>, PaillierKeysUpdate), RecoveryError> {
+        let first = shares.first().ok_or(RecoveryError::MissingShares)?;
+        if first.lost_party != self.party
+            || !is_well_formed(first)
+            || shares.iter().any(|s| !same_recovery(s, first))
+        {
+            return Err(RecoveryError::InconsistentShares);
+        }
+        let helpers: BTreeSet<usize> = shares.iter().map(|s| usize::from(s.helper)).collect();
+        if shares.len() != first.s_l.len()
+            || helpers != (1..=first.s_l.len()).collect::<BTreeSet<_>>()
+        {
+            return Err(RecoveryError::MissingShares);
+        }
+
+        let mut invalid = BTreeSet::new();
+        for (j, (&keygen_j, commitments)) in (1..).zip(first.s_l.iter().zip(&first.commitments)) {
+            let expected = &first.pk_vec[usize::from(keygen_j) - 1]
+                * lost_share_multiplier(
+                    &first.party_to_point_map,
+                    &first.s_l,
+                    usize::from(keygen_j),
+                    self.party,
+                );
+            let sum = commitments.iter().fold(Point::zero(), |acc, c| acc + c);
+            if sum != expected {
+                invalid.insert(j);
+            }
+        }
+        for share in shares {
+            let k = usize::from(share.helper);
+            let expected = first
+                .commitments
+                .iter()
+                .fold(Point::zero(), |acc, c| acc + &c[k - 1]);
+            if Point::generator() * &share.share != expected {
+                invalid.insert(share.helper);
+            }
+        }
+        if !invalid.is_empty() {
+            return Err(RecoveryError::InvalidShares {
+                parties: invalid.into_iter().collect(),
+            });
+        }
+
+        let x_i = SecretScalar::new(shares.iter().fold(Scalar::zero(), |acc, s| acc + &s.share));
+        let vss_scheme =
+            vss_from_pk_vec(&first.pk_vec, &first.party_to_point_map, &first.key_params)
+                .ok_or(RecoveryError::InconsistentShares)?;
+
+        let update = PaillierKeysUpdate::new(&self.keys, &x_i, &self.session_id);
+        let mut paillier_key_vec = first.paillier_key_vec.clone();
+        let mut h1_h2_n_tilde_vec = first.h1_h2_n_tilde_vec.clone();
+        paillier_key_vec[self.party - 1] = update.e.clone();
+        h1_h2_n_tilde_vec[self.party - 1] = update.dlog_statement.clone();
+
+        let local_key = LocalKey {
+            paillier_dk: self.keys.paillier_keys.dk.clone(),
+            pk_vec: first.pk_vec.clone(),
+            keys_linear: SharedKeys {
+                y: first.public_key.clone(),
+                x_i: (*x_i).clone(),
+            },
+            paillier_key_vec,
+            h1_h2_n_tilde_vec,
+            vss_scheme,
+
+            own_party_index: self.party,
+            other_parties: (1..=first.pk_vec.len())
+                .filter(|j| *j != self.party)
+                .collect::<BTreeSet<_>>(),
+            public_key: first.public_key.clone(),
+            key_params: first.key_params.clone(),
+            secret_share: (first.party_to_point_map.points[&self.party], (*x_i).clone()),
+            party_to_point_map: first.party_to_point_map.clone(),
+        };
+        local_key
+            .validate()
+            .into_result()
+            .map_err(RecoveryError::InvalidLocalKey)?;
+
+        Ok((local_key, update))
+    }
+}
+
+/// Lagrange coefficient of keygen party `party` for interpolating share of `lost_party` from
+/// shares of parties `s_l`
+pub(super) fn lost_share_multiplier(
+    points: &PartyToPointMap,
+    s_l: &[u16],
+    party: usize,
+    lost_party: usize,
+) -> Scalar<Secp256k1> {
+    let parties: Vec<usize> = s_l.iter().map(|&j| usize::from(j)).collect();
+    let point = |p: usize| Scalar::from_bigint(&BigInt::from(points.points[&p] as u64));
+    points.calculate_lagrange_multiplier_at(&parties, point(party), point(lost_party))
+}
+
+/// Checks that indexes in the share are in range, so the share can be processed without panics
+fn is_well_formed(share: &RecoveryShare) -> bool {
+    let n = usize::from(share.key_params.share_count);
+    let m = share.s_l.len();
+    let t = usize::from(share.key_params.threshold);
+    let mapped = |j: usize| share.party_to_point_map.points.contains_key(&j);
+
+    share.pk_vec.len() == n
+        && share.paillier_key_vec.len() == n
+        && share.h1_h2_n_tilde_vec.len() == n
+        && t < n
+        && m > t
+        && share.lost_party >= 1
+        && share.lost_party <= n
+        && mapped(share.lost_party)
+        && (1..=t + 1).all(mapped)
+        && share
+            .s_l
+            .iter()
+            .all(|&j| j >= 1 && usize::from(j) <= n && mapped(usize::from(j)))
+        && share.commitments.len() == m
+        && share.commitments.iter().all(|c| c.len() == m)
+        && distinct_points(share)
+}
+
+/// Checks that the helpers and the lost party are mapped to distinct non-zero points
+fn distinct_points(share: &RecoveryShare) -> bool {
+    let points = &share.party_to_point_map.points;
+    let parties = share
+        .s_l
+        .iter()
+        .map(|&j| usize::from(j))
+        .chain(Some(share.lost_party));
+    let mut seen = BTreeSet::new();
+    parties
+        .map(|j| points[&j])
+        .all(|point| point != 0 && seen.insert(point))
+}
+
+/// Checks that both shares were produced in the same recovery
+fn same_recovery(share: &RecoveryShare, other: &RecoveryShare) -> bool {
+    share.lost_party == other.lost_party
+        && share.s_l == other.s_l
+        && share.public_key == other.public_key
+        && share.pk_vec == other.pk_vec
+        && share.key_params.threshold == other.key_params.threshold
+        && share.key_params.share_count == other.key_params.share_count
+        && share.party_to_point_map.points == other.party_to_point_map.points
+        && wire::to_bytes(&share.paillier_key_vec) == wire::to_bytes(&other.paillier_key_vec)
+        && wire::to_bytes(&share.h1_h2_n_tilde_vec) == wire::to_bytes(&other.h1_h2_n_tilde_vec)
+        && share.commitments == other.commitments
+}
+
+/// Commitments to coefficients of the polynomial sharing the whole key, interpolated from
+/// `pk_vec` entries of the first `t+1` parties
+///
+/// Unlike `vss_scheme` of the lost party from keygen, which can't be recovered, it commits to the
+/// public key, so the recovered share can be checked against it.
+fn vss_from_pk_vec(
+    pk_vec: &[Point<Secp256k1>],
+    points: &PartyToPointMap,
+    params: &Parameters,
+) -> Option<VerifiableSS<Secp256k1>> {
+    let t = usize::from(params.threshold);
+    let one = || Scalar::<Secp256k1>::from_bigint(&BigInt::one());
+    let xs: Vec<Scalar<Secp256k1>> = (1..=t + 1)
+        .map(|j| Scalar::from_bigint(&BigInt::from(points.points[&j] as u64)))
+        .collect();
+
+    let mut commitments = vec![Point::<Secp256k1>::zero(); t + 1];
+    for (k, x_k) in xs.iter().enumerate() {
+        // Coefficients of Lagrange basis polynomial L_k(x) = Π (x - x_l) / (x_k - x_l)
+        let mut basis = vec![one()];
+        let mut denominator = one();
+        for (_, x_l) in xs.iter().enumerate().filter(|(l, _)| *l != k) {
+            let mut next = vec![Scalar::zero(); basis.len() + 1];
+            for (m, c) in basis.iter().enumerate() {
+                next[m + 1] = &next[m + 1] + c;
+                next[m] = &next[m] - &(c * x_l);
+            }
+            basis = next;
+            denominator = denominator * (x_k - x_l);
+        }
+        let scale = denominator.invert()?;
+        for (commitment, c) in commitments.iter_mut().zip(&basis) {
+            *commitment = &*commitment + &pk_vec[k] * &(c * &scale);
+        }
+    }
+
+    Some(VerifiableSS {
+        parameters: ShamirSecretSharing {
+            threshold: params.threshold,
+            share_count: params.share_count,
+        },
+        commitments,
+    })
+}
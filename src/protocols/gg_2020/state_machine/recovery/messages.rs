@@ -0,0 +1,93 @@
+use std::fmt;
+
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use serde::{Deserialize, Serialize};
+
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+use crate::utilities::fingerprint::Fingerprint;
+use crate::utilities::wire::{self, wire_struct, Reader, Wire, WireError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolMessage(pub(super) M);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) enum M {
+    Round1(HelperBroadcast),
+    Round2(SubShare),
+}
+
+/// Key held by the helper along with commitments to its sub-shares
+///
+/// `commitments[k-1]` commits to the sub-share sent to helper `k`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelperBroadcast {
+    pub public_key: Point<Secp256k1>,
+    pub pk_vec: Vec<Point<Secp256k1>>,
+    pub commitments: Vec<Point<Secp256k1>>,
+}
+
+wire_struct!(HelperBroadcast {
+    public_key,
+    pk_vec,
+    commitments,
+});
+
+/// Sub-share of the lost share, sent privately to a single helper
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubShare(pub Scalar<Secp256k1>);
+
+wire_struct!(SubShare(_));
+
+impl fmt::Debug for SubShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SubShare")
+            .field(&Fingerprint::of_scalar(&self.0))
+            .finish()
+    }
+}
+
+impl RoundMessage for ProtocolMessage {
+    fn round(&self) -> u16 {
+        match self.0 {
+            M::Round1(_) => 1,
+            M::Round2(_) => 2,
+        }
+    }
+}
+
+impl ProtocolMessage {
+    /// Encodes the message in compact binary format, see [wire](crate::utilities::wire)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        wire::to_bytes(self)
+    }
+
+    /// Decodes the message produced by [to_bytes](Self::to_bytes), rejecting non-canonical input
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        wire::from_bytes(bytes)
+    }
+}
+
+/// Tag of every message is the number of its round
+impl Wire for ProtocolMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.round() as u8);
+        match &self.0 {
+            M::Round1(m) => m.encode(out),
+            M::Round2(m) => m.encode(out),
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let m = match reader.take_tag()? {
+            1 => M::Round1(Wire::decode(reader)?),
+            2 => M::Round2(Wire::decode(reader)?),
+            tag => {
+                return Err(WireError::UnknownTag {
+                    ty: "share recovery message",
+                    tag,
+                })
+            }
+        };
+        Ok(ProtocolMessage(m))
+    }
+}
@@ -0,0 +1,73 @@
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use round_based::containers::push::Push;
+use round_based::Msg;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::gg_2020::state_machine::recovery::error::RecoveryError;
+use crate::protocols::gg_2020::state_machine::recovery::lost_party::lost_share_multiplier;
+use crate::protocols::gg_2020::state_machine::recovery::messages::HelperBroadcast;
+use crate::protocols::gg_2020::state_machine::recovery::rounds::round_1::Round1;
+use crate::utilities::secret_scalar::SecretScalar;
+
+pub struct Round0 {
+    /// Index of this party in range `[1; n]`
+    pub i: u16,
+    /// Keygen indexes of the helpers
+    pub s_l: Vec<u16>,
+    pub local_key: LocalKey<Secp256k1>,
+    /// Keygen index of the lost party
+    pub lost_party: usize,
+}
+
+impl Round0 {
+    pub fn proceed<O>(self, mut output: O) -> Result<Round1, RecoveryError>
+    where
+        O: Push<Msg<HelperBroadcast>>,
+    {
+        let local_key = self.local_key;
+        let lost_party = self.lost_party;
+        let term = SecretScalar::new(
+            lost_share_multiplier(
+                &local_key.party_to_point_map,
+                &self.s_l,
+                local_key.own_party_index,
+                lost_party,
+            ) * &local_key.keys_linear.x_i,
+        );
+
+        // Sub-shares are random except the last one, which makes them add up to the term
+        let mut sub_shares: Vec<SecretScalar> = (1..self.s_l.len())
+            .map(|_| SecretScalar::new(Scalar::random()))
+            .collect();
+        let last = sub_shares
+            .iter()
+            .fold(term.clone(), |acc, d| SecretScalar::new(&*acc - &**d));
+        sub_shares.push(last);
+
+        let broadcast = HelperBroadcast {
+            public_key: local_key.public_key.clone(),
+            pk_vec: local_key.pk_vec.clone(),
+            commitments: sub_shares
+                .iter()
+                .map(|d| Point::generator() * &**d)
+                .collect(),
+        };
+        output.push(Msg {
+            sender: self.i,
+            receiver: None,
+            body: broadcast.clone(),
+        });
+
+        Ok(Round1 {
+            i: self.i,
+            s_l: self.s_l,
+            local_key,
+            lost_party,
+            sub_shares,
+            broadcast,
+        })
+    }
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,98 @@
+use curv::elliptic::curves::{Point, Secp256k1};
+use round_based::containers::push::Push;
+use round_based::containers::{self, BroadcastMsgs, Store};
+use round_based::Msg;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::gg_2020::state_machine::recovery::error::RecoveryError;
+use crate::protocols::gg_2020::state_machine::recovery::lost_party::lost_share_multiplier;
+use crate::protocols::gg_2020::state_machine::recovery::messages::{HelperBroadcast, SubShare};
+use crate::protocols::gg_2020::state_machine::recovery::rounds::round_2::Round2;
+use crate::utilities::secret_scalar::SecretScalar;
+
+pub struct Round1 {
+    pub(super) i: u16,
+    pub(super) s_l: Vec<u16>,
+    pub(super) local_key: LocalKey<Secp256k1>,
+    pub(super) lost_party: usize,
+
+    pub(super) sub_shares: Vec<SecretScalar>,
+    pub(super) broadcast: HelperBroadcast,
+}
+
+impl Round1 {
+    pub fn proceed<O>(
+        self,
+        input: BroadcastMsgs<HelperBroadcast>,
+        mut output: O,
+    ) -> Result<Round2, RecoveryError>
+    where
+        O: Push<Msg<SubShare>>,
+    {
+        let broadcasts = input.into_vec_including_me(self.broadcast);
+
+        let key_mismatch: Vec<u16> = (1..)
+            .zip(&broadcasts)
+            .filter(|(_, b)| {
+                b.public_key != self.local_key.public_key || b.pk_vec != self.local_key.pk_vec
+            })
+            .map(|(j, _)| j)
+            .collect();
+        if !key_mismatch.is_empty() {
+            return Err(RecoveryError::KeyMismatch {
+                parties: key_mismatch,
+            });
+        }
+
+        // Sub-shares of every helper must add up to its term of the lost share
+        let invalid_commitments: Vec<u16> = (1..)
+            .zip(self.s_l.iter().zip(&broadcasts))
+            .filter(|(_, (&keygen_j, b))| {
+                if b.commitments.len() != self.s_l.len() {
+                    return true;
+                }
+                let expected = &self.local_key.pk_vec[usize::from(keygen_j) - 1]
+                    * lost_share_multiplier(
+                        &self.local_key.party_to_point_map,
+                        &self.s_l,
+                        usize::from(keygen_j),
+                        self.lost_party,
+                    );
+                let sum = b.commitments.iter().fold(Point::zero(), |acc, c| acc + c);
+                sum != expected
+            })
+            .map(|(j, _)| j)
+            .collect();
+        if !invalid_commitments.is_empty() {
+            return Err(RecoveryError::InvalidCommitments {
+                parties: invalid_commitments,
+            });
+        }
+
+        for (k, sub_share) in (1..).zip(&self.sub_shares) {
+            if k == self.i {
+                continue;
+            }
+            output.push(Msg {
+                sender: self.i,
+                receiver: Some(k),
+                body: SubShare((**sub_share).clone()),
+            });
+        }
+
+        Ok(Round2 {
+            i: self.i,
+            s_l: self.s_l,
+            local_key: self.local_key,
+            lost_party: self.lost_party,
+            commitments: broadcasts.into_iter().map(|b| b.commitments).collect(),
+            own_sub_share: self.sub_shares[usize::from(self.i) - 1].clone(),
+        })
+    }
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+    pub fn expects_messages(i: u16, n: u16) -> Store<BroadcastMsgs<HelperBroadcast>> {
+        containers::BroadcastMsgsStore::new(i, n)
+    }
+}
@@ -0,0 +1,57 @@
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use round_based::containers::{self, P2PMsgs, Store};
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::gg_2020::state_machine::recovery::error::RecoveryError;
+use crate::protocols::gg_2020::state_machine::recovery::lost_party::RecoveryShare;
+use crate::protocols::gg_2020::state_machine::recovery::messages::SubShare;
+use crate::utilities::secret_scalar::SecretScalar;
+
+pub struct Round2 {
+    pub(super) i: u16,
+    pub(super) s_l: Vec<u16>,
+    pub(super) local_key: LocalKey<Secp256k1>,
+    pub(super) lost_party: usize,
+
+    /// `commitments[j-1][k-1]` commits to sub-share sent by helper `j` to helper `k`
+    pub(super) commitments: Vec<Vec<Point<Secp256k1>>>,
+    pub(super) own_sub_share: SecretScalar,
+}
+
+impl Round2 {
+    pub fn proceed(self, input: P2PMsgs<SubShare>) -> Result<RecoveryShare, RecoveryError> {
+        let sub_shares = input.into_vec_including_me(SubShare((*self.own_sub_share).clone()));
+        let i = usize::from(self.i);
+
+        let invalid: Vec<u16> = (1..)
+            .zip(self.commitments.iter().zip(&sub_shares))
+            .filter(|(_, (commitments, d))| Point::generator() * &d.0 != commitments[i - 1])
+            .map(|(j, _)| j)
+            .collect();
+        if !invalid.is_empty() {
+            return Err(RecoveryError::InvalidSubShares { parties: invalid });
+        }
+
+        let share = sub_shares.iter().fold(Scalar::zero(), |acc, d| acc + &d.0);
+        let local_key = self.local_key;
+        Ok(RecoveryShare {
+            lost_party: self.lost_party,
+            helper: self.i,
+            s_l: self.s_l,
+            public_key: local_key.public_key.clone(),
+            pk_vec: local_key.pk_vec.clone(),
+            key_params: local_key.key_params.clone(),
+            party_to_point_map: local_key.party_to_point_map.clone(),
+            paillier_key_vec: local_key.paillier_key_vec.clone(),
+            h1_h2_n_tilde_vec: local_key.h1_h2_n_tilde_vec.clone(),
+            commitments: self.commitments,
+            share,
+        })
+    }
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+    pub fn expects_messages(i: u16, n: u16) -> Store<P2PMsgs<SubShare>> {
+        containers::P2PMsgsStore::new(i, n)
+    }
+}
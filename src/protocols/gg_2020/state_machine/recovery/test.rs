@@ -0,0 +1,230 @@
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use round_based::dev::Simulation;
+use round_based::{Msg, StateMachine};
+
+use crate::protocols::gg_2020::state_machine::keygen::error::local_key_error::PaillierKeysUpdateError;
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::gg_2020::state_machine::keygen::test::simulate_keygen;
+use crate::protocols::gg_2020::state_machine::recovery::{
+    error::RecoveryError,
+    lost_party::{LostParty, RecoveryShare},
+    messages::{ProtocolMessage, SubShare, M},
+    ShareRecovery,
+};
+use crate::protocols::gg_2020::state_machine::sign::test::simulate_offline_stage;
+use crate::utilities::session_id::SessionId;
+
+fn simulate_recovery(
+    local_keys: &[LocalKey<Secp256k1>],
+    s_l: &[u16],
+    lost_party: usize,
+) -> Vec<RecoveryShare> {
+    let mut simulation = Simulation::new();
+
+    for (i, &keygen_i) in (1..).zip(s_l) {
+        simulation.add_party(
+            ShareRecovery::new(
+                i,
+                s_l.to_vec(),
+                local_keys[usize::from(keygen_i - 1)].clone(),
+                lost_party,
+            )
+            .unwrap(),
+        );
+    }
+
+    simulation.run().unwrap()
+}
+
+/// Runs all the helpers until they send their sub-shares
+fn step_to_sub_shares(
+    local_keys: &[LocalKey<Secp256k1>],
+    s_l: &[u16],
+    lost_party: usize,
+) -> (Vec<ShareRecovery>, Vec<Msg<ProtocolMessage>>) {
+    let mut parties: Vec<ShareRecovery> = (1..)
+        .zip(s_l)
+        .map(|(i, &keygen_i)| {
+            ShareRecovery::new(
+                i,
+                s_l.to_vec(),
+                local_keys[usize::from(keygen_i - 1)].clone(),
+                lost_party,
+            )
+            .unwrap()
+        })
+        .collect();
+    for party in parties.iter_mut() {
+        party.proceed().unwrap();
+    }
+
+    let round1: Vec<_> = parties
+        .iter_mut()
+        .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+        .collect();
+    for party in parties.iter_mut() {
+        for msg in round1.iter().filter(|m| m.sender != party.party_ind()) {
+            party.handle_incoming(msg.clone()).unwrap();
+        }
+    }
+    let round2: Vec<_> = parties
+        .iter_mut()
+        .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+        .collect();
+    (parties, round2)
+}
+
+#[test]
+fn recovered_key_matches_lost_one_and_signs() {
+    let mut local_keys = simulate_keygen(1, 4);
+    let session_id = SessionId::random();
+    let lost_party = LostParty::new(2, &session_id);
+
+    let shares = simulate_recovery(&local_keys, &[3, 1], 2);
+    let (recovered, update) = lost_party.complete(&shares).unwrap();
+
+    assert!(recovered.validate().is_valid(), "{}", recovered.validate());
+    assert_eq!(recovered.keys_linear.x_i, local_keys[1].keys_linear.x_i);
+    assert_eq!(recovered.secret_share.0, local_keys[1].secret_share.0);
+    assert_eq!(recovered.vss_scheme.commitments[0], recovered.public_key);
+    assert_eq!(recovered.paillier_key_vec[1].n, update.e.n);
+
+    // Update proves knowledge of the recovered share, so it can't pass for another party
+    assert!(matches!(
+        local_keys[0].apply_paillier_keys_update(&update, &SessionId::random()),
+        Err(PaillierKeysUpdateError::InvalidProofs { party: 2 })
+    ));
+    let mut foreign = update.clone();
+    foreign.party = 4;
+    assert_eq!(
+        local_keys[0]
+            .apply_paillier_keys_update(&foreign, &session_id)
+            .unwrap_err(),
+        PaillierKeysUpdateError::NotShareHolder { party: 4 }
+    );
+
+    // Helpers and the party which didn't help apply the update alike
+    for key in [0, 2, 3] {
+        local_keys[key]
+            .apply_paillier_keys_update(&update, &session_id)
+            .unwrap();
+    }
+    local_keys[1] = recovered;
+    simulate_offline_stage(local_keys.clone(), &[2, 4]);
+    simulate_offline_stage(local_keys, &[1, 2]);
+}
+
+#[test]
+fn recovers_with_more_than_threshold_helpers() {
+    let mut local_keys = simulate_keygen(2, 4);
+    let session_id = SessionId::random();
+    let lost_party = LostParty::new(2, &session_id);
+
+    let mut shares = simulate_recovery(&local_keys, &[4, 1, 3], 2);
+    shares.reverse();
+    let (recovered, update) = lost_party.complete(&shares).unwrap();
+    assert_eq!(recovered.keys_linear.x_i, local_keys[1].keys_linear.x_i);
+
+    for key in [0, 2, 3] {
+        local_keys[key]
+            .apply_paillier_keys_update(&update, &session_id)
+            .unwrap();
+    }
+    local_keys[1] = recovered;
+    simulate_offline_stage(local_keys, &[2, 4, 1]);
+}
+
+#[test]
+fn rejects_invalid_helpers() {
+    let local_keys = simulate_keygen(1, 3);
+    let new = |i, s_l: Vec<u16>, keygen_i: usize| {
+        ShareRecovery::new(i, s_l, local_keys[keygen_i - 1].clone(), 2)
+    };
+
+    assert!(matches!(
+        new(1, vec![1], 1),
+        Err(RecoveryError::TooFewParties {
+            required: 2,
+            actual: 1
+        })
+    ));
+    assert!(matches!(
+        new(1, vec![1, 2], 1),
+        Err(RecoveryError::InvalidLostParty { party: 2 })
+    ));
+    assert!(matches!(
+        new(1, vec![3, 1], 1),
+        Err(RecoveryError::InvalidSl)
+    ));
+    assert!(matches!(
+        ShareRecovery::new(1, vec![1, 3], local_keys[0].clone(), 4),
+        Err(RecoveryError::InvalidLostParty { party: 4 })
+    ));
+}
+
+#[test]
+fn blames_helper_sending_invalid_sub_share() {
+    let local_keys = simulate_keygen(1, 3);
+    let (mut parties, mut round2) = step_to_sub_shares(&local_keys, &[1, 3], 2);
+
+    assert_eq!(round2.len(), 2);
+    let tampered = round2.iter_mut().find(|m| m.sender == 2).unwrap();
+    if let ProtocolMessage(M::Round2(SubShare(d))) = &mut tampered.body {
+        *d = &*d + Scalar::random();
+    }
+    let bytes = tampered.body.to_bytes();
+    assert_eq!(
+        ProtocolMessage::from_bytes(&bytes).unwrap().to_bytes(),
+        bytes
+    );
+
+    let err = parties[0].handle_incoming(tampered.clone()).unwrap_err();
+    assert!(
+        matches!(&err, RecoveryError::InvalidSubShares { parties } if parties == &[2]),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn lost_party_rejects_invalid_shares() {
+    let local_keys = simulate_keygen(1, 3);
+    let session_id = SessionId::random();
+    let lost_party = LostParty::new(2, &session_id);
+
+    let shares = simulate_recovery(&local_keys, &[1, 3], 2);
+
+    let mut tampered = shares.clone();
+    tampered[1].share = &tampered[1].share + Scalar::random();
+    let err = lost_party.complete(&tampered).unwrap_err();
+    assert!(
+        matches!(&err, RecoveryError::InvalidShares { parties } if parties == &[2]),
+        "{:?}",
+        err
+    );
+
+    // Commitments of helper 1 don't add up to its term, and no longer match share of helper 2
+    let mut tampered = shares.clone();
+    for share in tampered.iter_mut() {
+        share.commitments[0][1] = &share.commitments[0][1] + Point::generator();
+    }
+    let err = lost_party.complete(&tampered).unwrap_err();
+    assert!(
+        matches!(&err, RecoveryError::InvalidShares { parties } if parties == &[1, 2]),
+        "{:?}",
+        err
+    );
+
+    let mut inconsistent = shares.clone();
+    inconsistent[0].pk_vec.swap(0, 2);
+    assert!(matches!(
+        lost_party.complete(&inconsistent),
+        Err(RecoveryError::InconsistentShares)
+    ));
+    assert!(matches!(
+        lost_party.complete(&shares[..1]),
+        Err(RecoveryError::MissingShares)
+    ));
+
+    assert!(lost_party.complete(&shares).is_ok());
+}
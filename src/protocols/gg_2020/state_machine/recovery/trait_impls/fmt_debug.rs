@@ -0,0 +1,33 @@
+use std::fmt;
+
+use round_based::containers::MessageStore;
+
+use crate::protocols::gg_2020::state_machine::recovery::{rounds::R, ShareRecovery};
+
+impl fmt::Debug for ShareRecovery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let current_round = match &self.round {
+            R::Round0(_) => "0",
+            R::Round1(_) => "1",
+            R::Round2(_) => "2",
+            R::Final(_) => "[Final]",
+            R::Gone => "[Gone]",
+        };
+        let msgs1 = match self.msgs1.as_ref() {
+            Some(msgs) => format!("[{}/{}]", msgs.messages_received(), msgs.messages_total()),
+            None => "[None]".into(),
+        };
+        let msgs2 = match self.msgs2.as_ref() {
+            Some(msgs) => format!("[{}/{}]", msgs.messages_received(), msgs.messages_total()),
+            None => "[None]".into(),
+        };
+        write!(
+            f,
+            "{{ShareRecovery at round={} msgs1={} msgs2={} queue=[len={}]}}",
+            current_round,
+            msgs1,
+            msgs2,
+            self.msgs_queue.len()
+        )
+    }
+}
@@ -8,5 +8,7 @@ pub mod trait_impls;
 #[cfg(test)]
 pub mod test;
 
+pub use rounds::{CompletedOfflineStage, PartialSignature};
+
 
 
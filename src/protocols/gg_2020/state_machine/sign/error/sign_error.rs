@@ -38,6 +38,17 @@ pub enum SignError {
     #[error("received message didn't pass pre-validation: {0}")]
     HandleMessage(#[source] StoreErr),
 
+    /// Party didn't receive all messages of the round in time, see
+    /// [OfflineStage::with_round_timeout](crate::protocols::gg_2020::state_machine::sign::stages::offline_stage::OfflineStage::with_round_timeout)
+    ///
+    /// `missing_parties` are indexes of parties in the offline stage (positions in `s_l`
+    /// starting from 1), not their keygen indexes.
+    #[error("round {round} timed out, missing messages from parties {missing_parties:?}")]
+    Timeout {
+        round: u16,
+        missing_parties: Vec<u16>,
+    },
+
     /// [OfflineStage::pick_output] called twice
     #[error("pick_output called twice")]
     DoublePickOutput,
@@ -49,6 +60,29 @@ pub enum SignError {
 
 
 
+impl SignError {
+    /// Signers to restart signing with after [SignError::Timeout]
+    ///
+    /// Takes `s_l` that offline stage was started with, and returns it without the parties
+    /// that timed out. Returns `None` if error is not a timeout. Parties must agree on the new
+    /// signer set (every honest party observes the same missing parties only if they all got
+    /// stuck in the same round), and restart offline stage with a fresh session id.
+    pub fn remaining_signers(&self, s_l: &[u16]) -> Option<Vec<u16>> {
+        match self {
+            SignError::Timeout {
+                missing_parties, ..
+            } => Some(
+                (1..)
+                    .zip(s_l)
+                    .filter(|(i, _)| !missing_parties.contains(i))
+                    .map(|(_, &keygen_i)| keygen_i)
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
 impl From<InternalError> for SignError {
     fn from(err: InternalError) -> Self {
         SignError::Bug(err)
@@ -65,6 +99,7 @@ impl IsCritical for SignError {
             SignError::ProceedRound(_) => true,
             SignError::ReceivedOutOfOrderMessage { .. } => false,
             SignError::HandleMessage(_) => false,
+            SignError::Timeout { .. } => true,
             SignError::DoublePickOutput => true,
             SignError::Bug(_) => true,
         }
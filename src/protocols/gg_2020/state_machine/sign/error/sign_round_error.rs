@@ -1,3 +1,4 @@
+use crate::protocols::gg_2020::state_machine::echo::Equivocation;
 use crate::protocols::gg_2020::ErrorType;
 use thiserror::Error;
 
@@ -19,4 +20,6 @@ pub enum SignRoundError {
     Round6CheckSig(crate::Error),
     #[error("round 7: {0:?}")]
     Round7(crate::Error),
+    #[error("echo round: {0}")]
+    Equivocation(#[source] Equivocation),
 }
\ No newline at end of file
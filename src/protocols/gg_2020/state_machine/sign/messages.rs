@@ -5,25 +5,24 @@ use curv::{
         Secp256k1, 
         Point
     }, 
-    cryptographic_primitives::proofs::{
-        sigma_valid_pedersen::PedersenProof, 
-        sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof
-    }
 };
 use round_based::containers::push::Push;
 use round_based::Msg;
 use serde::{Deserialize, Serialize};
-use sha2::Sha256;
 
 use crate::{
+    protocols::gg_2020::state_machine::traits::RoundMessage,
+    protocols::gg_2020::state_machine::echo::EchoMessage,
     protocols::gg_2020::party_i::{
         SignBroadcastPhase1, 
         SignDecommitPhase1, 
     },
     utilities::{
+        wire::{self, wire_struct, Reader, Wire, WireError},
         mta::MessageA,
         mta::MessageB,
         zk_pdl_with_slack::PDLwSlackProof,
+        zk_sigma::{HomoELGamalProof, PedersenProof},
     }
 };
 
@@ -41,6 +40,68 @@ pub enum OfflineM {
     M4(SignDecommitPhase1),
     M5((RDash, Vec<PDLwSlackProof>)),
     M6((SI, HEGProof)),
+    Echo(EchoMessage),
+}
+
+impl RoundMessage for OfflineProtocolMessage {
+    fn round(&self) -> u16 {
+        match self.0 {
+            OfflineM::M1(_) => 1,
+            OfflineM::M2(_) => 2,
+            OfflineM::M3(_) => 3,
+            OfflineM::M4(_) => 4,
+            OfflineM::M5(_) => 5,
+            OfflineM::M6(_) => 6,
+            OfflineM::Echo(_) => 7,
+        }
+    }
+}
+
+impl OfflineProtocolMessage {
+    /// Encodes the message in compact binary format, see [wire](crate::utilities::wire)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        wire::to_bytes(self)
+    }
+
+    /// Decodes the message produced by [to_bytes](Self::to_bytes), rejecting non-canonical input
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        wire::from_bytes(bytes)
+    }
+}
+
+/// Tag of every message is the number of its round
+impl Wire for OfflineProtocolMessage {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.round() as u8);
+        match &self.0 {
+            OfflineM::M1(m) => m.encode(out),
+            OfflineM::M2(m) => m.encode(out),
+            OfflineM::M3(m) => m.encode(out),
+            OfflineM::M4(m) => m.encode(out),
+            OfflineM::M5(m) => m.encode(out),
+            OfflineM::M6(m) => m.encode(out),
+            OfflineM::Echo(m) => m.encode(out),
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let m = match reader.take_tag()? {
+            1 => OfflineM::M1(Wire::decode(reader)?),
+            2 => OfflineM::M2(Wire::decode(reader)?),
+            3 => OfflineM::M3(Wire::decode(reader)?),
+            4 => OfflineM::M4(Wire::decode(reader)?),
+            5 => OfflineM::M5(Wire::decode(reader)?),
+            6 => OfflineM::M6(Wire::decode(reader)?),
+            7 => OfflineM::Echo(Wire::decode(reader)?),
+            tag => {
+                return Err(WireError::UnknownTag {
+                    ty: "offline stage message",
+                    tag,
+                })
+            }
+        };
+        Ok(OfflineProtocolMessage(m))
+    }
 }
 
 pub struct MsgQueue(pub Vec<Msg<OfflineProtocolMessage>>);
@@ -68,36 +129,53 @@ make_pushable! {
     M4 SignDecommitPhase1,
     M5 (RDash, Vec<PDLwSlackProof>),
     M6 (SI, HEGProof),
+    Echo EchoMessage,
 }
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GammaI(pub MessageB);
 
+wire_struct!(GammaI(_));
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WI(pub MessageB);
 
+wire_struct!(WI(_));
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DeltaI(pub Scalar<Secp256k1>);
 
+wire_struct!(DeltaI(_));
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TI(pub Point<Secp256k1>);
 
+wire_struct!(TI(_));
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct TIProof(pub PedersenProof<Secp256k1, Sha256>);
+pub struct TIProof(pub PedersenProof);
+
+wire_struct!(TIProof(_));
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RDash(pub Point<Secp256k1>);
 
+wire_struct!(RDash(_));
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SI(pub Point<Secp256k1>);
 
+wire_struct!(SI(_));
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct HEGProof(pub HomoELGamalProof<Secp256k1, Sha256>);
+pub struct HEGProof(pub HomoELGamalProof);
+
+wire_struct!(HEGProof(_));
@@ -12,6 +12,7 @@ pub mod round_7;
 use serde::{Deserialize, Serialize};
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
 use sha2::Sha256;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::utilities::mta::MessageB;
 use curv::cryptographic_primitives::proofs::{
@@ -19,10 +20,9 @@ use curv::cryptographic_primitives::proofs::{
     sigma_valid_pedersen::PedersenProof,
 };
 
-use crate::protocols::gg_2020::{
-    party_i::SignKeys,
-    state_machine::keygen::local_key::LocalKey,
-};
+use crate::protocols::gg_2020::state_machine::echo;
+
+pub type EchoRound = echo::EchoRound<CompletedOfflineStage>;
 
 
 
@@ -33,22 +33,43 @@ use crate::protocols::gg_2020::{
 
 
 
-#[derive(Clone)]
+/// Output of offline stage, a presignature that can be used to sign exactly one message
+///
+/// Serializable so it can be computed ahead of time and stored, but it carries secret data and
+/// must be stored as securely as the local key. Signing two different messages with the same
+/// presignature reveals the secret key.
+///
+/// Holds only what online stage needs, not the local key the presignature was computed with.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CompletedOfflineStage {
     i: u16,
-    local_key: LocalKey<Secp256k1>,
-    sign_keys: SignKeys,
-    t_vec: Vec<Point<Secp256k1>>,
+    public_key: Point<Secp256k1>,
+    k_i: Scalar<Secp256k1>,
     R: Point<Secp256k1>,
     sigma_i: Scalar<Secp256k1>,
 }
 
 impl CompletedOfflineStage {
     pub fn public_key(&self) -> &Point<Secp256k1> {
-        &self.local_key.public_key
+        &self.public_key
+    }
+}
+
+impl Zeroize for CompletedOfflineStage {
+    fn zeroize(&mut self) {
+        self.k_i = Scalar::zero();
+        self.sigma_i = Scalar::zero();
     }
 }
 
+impl Drop for CompletedOfflineStage {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for CompletedOfflineStage {}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PartialSignature(Scalar<Secp256k1>);
 
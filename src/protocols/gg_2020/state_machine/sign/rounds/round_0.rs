@@ -3,6 +3,9 @@ use round_based::{
     Msg,
     containers::push::Push,
 };
+use zeroize::Zeroizing;
+
+use crate::utilities::session_id::SessionId;
 
 use crate::{
     utilities::mta::MessageA, 
@@ -32,6 +35,9 @@ pub struct Round0 {
 
     /// Party local secret share
     pub local_key: LocalKey<Secp256k1>,
+
+    /// Identifier of this signing session
+    pub session_id: SessionId,
 }
 
 impl Round0 {
@@ -49,10 +55,16 @@ impl Round0 {
                 .map(|&i| usize::from(i) - 1)
                 .collect::<Vec<_>>(),
         );
-        let (bc1, decom1) = sign_keys.phase1_broadcast();
+        let (bc1, decom1) = sign_keys.phase1_broadcast(&self.session_id);
 
         let party_ek = self.local_key.paillier_key_vec[usize::from(self.local_key.own_party_index - 1)].clone();
-        let m_a = MessageA::a(&sign_keys.k_i, &party_ek, &self.local_key.h1_h2_n_tilde_vec);
+        let (m_a, m_a_randomness) = MessageA::a(
+            &sign_keys.k_i,
+            &party_ek,
+            &self.local_key.h1_h2_n_tilde_vec,
+            &self.session_id,
+        );
+        let m_a = (m_a, Zeroizing::new(m_a_randomness));
 
         output.push(Msg {
             sender: self.i,
@@ -68,6 +80,7 @@ impl Round0 {
             sign_keys,
             phase1_com: bc1,
             phase1_decom: decom1,
+            session_id: self.session_id,
         };
 
         Ok(round1)
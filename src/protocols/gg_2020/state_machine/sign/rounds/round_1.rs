@@ -13,6 +13,10 @@ use round_based::{
     },
 };
 
+use zeroize::Zeroizing;
+
+use crate::utilities::session_id::SessionId;
+
 use crate::{
     utilities::mta::{MessageA, MessageB}, 
     protocols::gg_2020::party_i::{
@@ -40,10 +44,11 @@ pub struct Round1 {
     pub(super) i: u16,
     pub(super) s_l: Vec<u16>,
     pub(super) local_key: LocalKey<Secp256k1>,
-    pub(super) m_a: (MessageA, BigInt),
+    pub(super) m_a: (MessageA, Zeroizing<BigInt>),
     pub(super) sign_keys: SignKeys,
     pub(super) phase1_com: SignBroadcastPhase1,
     pub(super) phase1_decom: SignDecommitPhase1,
+    pub(super) session_id: SessionId,
 }
 
 impl Round1 {
@@ -81,6 +86,7 @@ impl Round1 {
                 &self.local_key.paillier_key_vec[l_s[ind]],
                 m_a_vec[ind].clone(),
                 &self.local_key.h1_h2_n_tilde_vec,
+                &self.session_id,
             )
             .map_err(|e| {
                 SignRoundError::Round1(ErrorType {
@@ -94,6 +100,7 @@ impl Round1 {
                 &self.local_key.paillier_key_vec[l_s[ind]],
                 m_a_vec[ind].clone(),
                 &self.local_key.h1_h2_n_tilde_vec,
+                &self.session_id,
             )
             .map_err(|e| {
                 SignRoundError::Round1(ErrorType {
@@ -130,6 +137,7 @@ impl Round1 {
             bc_vec,
             m_a_vec,
             phase1_decom: self.phase1_decom,
+            session_id: self.session_id,
         })
     }
 
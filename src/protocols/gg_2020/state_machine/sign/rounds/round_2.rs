@@ -15,6 +15,10 @@ use round_based::{
     },
 };
 
+use zeroize::Zeroizing;
+
+use crate::utilities::session_id::SessionId;
+
 use crate::{
     utilities::mta::MessageA, 
     protocols::gg_2020::party_i::{
@@ -47,12 +51,13 @@ pub struct Round2 {
     pub(super) s_l: Vec<u16>,
     pub(super) local_key: LocalKey<Secp256k1>,
     pub(super) sign_keys: SignKeys,
-    pub(super) m_a: (MessageA, BigInt),
+    pub(super) m_a: (MessageA, Zeroizing<BigInt>),
     pub(super) beta_vec: Vec<Scalar<Secp256k1>>,
     pub(super) ni_vec: Vec<Scalar<Secp256k1>>,
     pub(super) bc_vec: Vec<SignBroadcastPhase1>,
     pub(super) m_a_vec: Vec<MessageA>,
     pub(super) phase1_decom: SignDecommitPhase1,
+    pub(super) session_id: SessionId,
 }
 
 impl Round2 {
@@ -87,7 +92,11 @@ impl Round2 {
             let m_b = m_b_gamma_s[j].clone();
 
             let alpha_ij_gamma = m_b
-                .verify_proofs_get_alpha(&self.local_key.paillier_dk, &self.sign_keys.k_i)
+                .verify_proofs_get_alpha(
+                    &self.local_key.paillier_dk,
+                    &self.sign_keys.k_i,
+                    &self.session_id,
+                )
                 .map_err(|e| {
                     SignRoundError::Round3(ErrorType {
                         error_type: e.to_string(),
@@ -96,7 +105,11 @@ impl Round2 {
                 })?;
             let m_b = m_b_w_s[j].clone();
             let alpha_ij_wi = m_b
-                .verify_proofs_get_alpha(&self.local_key.paillier_dk, &self.sign_keys.k_i)
+                .verify_proofs_get_alpha(
+                    &self.local_key.paillier_dk,
+                    &self.sign_keys.k_i,
+                    &self.session_id,
+                )
                 .map_err(|e| {
                     SignRoundError::Round3(ErrorType {
                         error_type: e.to_string(),
@@ -112,7 +125,7 @@ impl Round2 {
         let delta_i = self.sign_keys.phase2_delta_i(&alpha_vec, &self.beta_vec);
 
         let sigma_i = self.sign_keys.phase2_sigma_i(&miu_vec, &self.ni_vec);
-        let (t_i, l_i, t_i_proof) = SignKeys::phase3_compute_t_i(&sigma_i);
+        let (t_i, l_i, t_i_proof) = SignKeys::phase3_compute_t_i(&sigma_i, &self.session_id);
         output.push(Msg {
             sender: self.i,
             receiver: None,
@@ -138,6 +151,7 @@ impl Round2 {
             sigma_i,
             t_i_proof,
             phase1_decom: self.phase1_decom,
+            session_id: self.session_id,
         })
     }
 
@@ -5,7 +5,6 @@ use curv::{
         Scalar
     }, 
     BigInt, 
-    cryptographic_primitives::proofs::sigma_valid_pedersen::PedersenProof
 };
 use round_based::{
     Msg,
@@ -16,13 +15,15 @@ use round_based::{
         Store
     },
 };
-use sha2::Sha256;
+use zeroize::Zeroizing;
 
 use crate::{
     utilities::mta::{
         MessageA, 
         MessageB
     }, 
+    utilities::session_id::SessionId,
+    utilities::zk_sigma::PedersenProof,
     protocols::gg_2020::party_i::{
         SignBroadcastPhase1, 
         SignDecommitPhase1
@@ -52,7 +53,7 @@ pub struct Round3 {
     pub(super) s_l: Vec<u16>,
     pub(super) local_key: LocalKey<Secp256k1>,
     pub(super) sign_keys: SignKeys,
-    pub(super) m_a: (MessageA, BigInt),
+    pub(super) m_a: (MessageA, Zeroizing<BigInt>),
     pub(super) mb_gamma_s: Vec<MessageB>,
     pub(super) bc_vec: Vec<SignBroadcastPhase1>,
     pub(super) m_a_vec: Vec<MessageA>,
@@ -60,9 +61,10 @@ pub struct Round3 {
     pub(super) t_i: Point<Secp256k1>,
     pub(super) l_i: Scalar<Secp256k1>,
     pub(super) sigma_i: Scalar<Secp256k1>,
-    pub(super) t_i_proof: PedersenProof<Secp256k1, Sha256>,
+    pub(super) t_i_proof: PedersenProof,
 
     pub(super) phase1_decom: SignDecommitPhase1,
+    pub(super) session_id: SessionId,
 }
 
 impl Round3 {
@@ -91,7 +93,7 @@ impl Round3 {
         let delta_inv = SignKeys::phase3_reconstruct_delta(&delta_vec);
         let ttag = self.s_l.len();
         for proof in t_proof_vec.iter().take(ttag) {
-            PedersenProof::verify(proof).map_err(|e| {
+            proof.verify(&self.session_id).map_err(|e| {
                 SignRoundError::Round3(ErrorType {
                     error_type: e.to_string(),
                     bad_actors: vec![],
@@ -120,6 +122,7 @@ impl Round3 {
             phase1_decom: self.phase1_decom,
             delta_inv,
             t_vec,
+            session_id: self.session_id,
         })
     }
 
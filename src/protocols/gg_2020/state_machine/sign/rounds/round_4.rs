@@ -13,11 +13,14 @@ use round_based::{
     },
 };
 
+use zeroize::Zeroizing;
+
 use crate::{
     utilities::{
         mta::{
             MessageA, MessageB
         },
+        session_id::SessionId,
         zk_pdl_with_slack::PDLwSlackProof
     }, 
     protocols::gg_2020::party_i::{
@@ -48,7 +51,7 @@ pub struct Round4 {
     pub(super) s_l: Vec<u16>,
     pub(super) local_key: LocalKey<Secp256k1>,
     pub(super) sign_keys: SignKeys,
-    pub(super) m_a: (MessageA, BigInt),
+    pub(super) m_a: (MessageA, Zeroizing<BigInt>),
     pub(super) mb_gamma_s: Vec<MessageB>,
     pub(super) bc_vec: Vec<SignBroadcastPhase1>,
     pub(super) m_a_vec: Vec<MessageA>,
@@ -58,6 +61,7 @@ pub struct Round4 {
     pub(super) delta_inv: Scalar<Secp256k1>,
     pub(super) t_vec: Vec<Point<Secp256k1>>,
     pub(super) phase1_decom: SignDecommitPhase1,
+    pub(super) session_id: SessionId,
 }
 
 impl Round4 {
@@ -79,6 +83,7 @@ impl Round4 {
             decom_vec,
             &self.bc_vec,
             usize::from(self.i - 1),
+            &self.session_id,
         )
         .map_err(SignRoundError::Round5)?;
 
@@ -103,6 +108,7 @@ impl Round4 {
                 &self.sign_keys.k_i,
                 &self.m_a.1,
                 &self.local_key.h1_h2_n_tilde_vec[l_s[ind]],
+                &self.session_id,
             );
 
             phase5_proofs_vec.push(proof);
@@ -127,6 +133,7 @@ impl Round4 {
             R,
             R_dash,
             phase5_proofs_vec,
+            session_id: self.session_id,
         })
     }
 
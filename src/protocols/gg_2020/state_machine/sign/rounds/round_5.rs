@@ -15,6 +15,7 @@ use round_based::{
 
 use crate::utilities::{
     mta::MessageA,
+    session_id::SessionId,
     zk_pdl_with_slack::PDLwSlackProof
 };
 
@@ -54,6 +55,7 @@ pub struct Round5 {
     pub(super) R: Point<Secp256k1>,
     pub(super) R_dash: Point<Secp256k1>,
     pub(super) phase5_proofs_vec: Vec<PDLwSlackProof>,
+    pub(super) session_id: SessionId,
 }
 
 impl Round5 {
@@ -88,6 +90,7 @@ impl Round5 {
                 &self.local_key.h1_h2_n_tilde_vec,
                 &l_s,
                 i,
+                &self.session_id,
             )
             .map_err(SignRoundError::Round5)?;
         }
@@ -103,6 +106,7 @@ impl Round5 {
             &self.t_i,
             &self.sigma_i,
             &self.l_i,
+            &self.session_id,
         );
 
         output.push(Msg {
@@ -115,11 +119,12 @@ impl Round5 {
             S_i,
             homo_elgamal_proof,
             s_l: self.s_l,
+            t_vec: self.t_vec,
+            session_id: self.session_id,
             protocol_output: CompletedOfflineStage {
                 i: self.i,
-                local_key: self.local_key,
-                sign_keys: self.sign_keys,
-                t_vec: self.t_vec,
+                public_key: self.local_key.public_key.clone(),
+                k_i: self.sign_keys.k_i.clone(),
                 R: self.R,
                 sigma_i: self.sigma_i,
             },
@@ -1,9 +1,7 @@
 #![allow(non_snake_case)]
 
 use std::iter;
-use sha2::Sha256;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, };
-use curv::cryptographic_primitives::proofs::sigma_correct_homomorphic_elgamal_enc::HomoELGamalProof;
 use round_based::containers::{self, BroadcastMsgs, Store};
 
 use crate::protocols::gg_2020::{
@@ -18,11 +16,15 @@ use crate::protocols::gg_2020::{
     },
     
 };
+use crate::utilities::session_id::SessionId;
+use crate::utilities::zk_sigma::HomoELGamalProof;
 
 pub struct Round6 {
     pub(super) S_i: Point<Secp256k1>,
-    pub(super) homo_elgamal_proof: HomoELGamalProof<Secp256k1, Sha256>,
+    pub(super) homo_elgamal_proof: HomoELGamalProof,
     pub(super) s_l: Vec<u16>,
+    pub(super) t_vec: Vec<Point<Secp256k1>>,
+    pub(super) session_id: SessionId,
     /// Round 6 guards protocol output until final checks are taken the place
     pub(super) protocol_output: CompletedOfflineStage,
 }
@@ -45,10 +47,11 @@ impl Round6 {
             &S_i_vec,
             &hegp_vec,
             &R_vec,
-            &self.protocol_output.t_vec,
+            &self.t_vec,
+            &self.session_id,
         )
         .map_err(SignRoundError::Round6VerifyProof)?;
-        LocalSignature::phase6_check_S_i_sum(&self.protocol_output.local_key.public_key, &S_i_vec)
+        LocalSignature::phase6_check_S_i_sum(&self.protocol_output.public_key, &S_i_vec)
             .map_err(SignRoundError::Round6CheckSig)?;
 
         Ok(self.protocol_output)
@@ -1,8 +1,8 @@
-use curv::BigInt;
 use crate::protocols::gg_2020::party_i::{
     LocalSignature, 
     SignatureRecid,
 };
+use crate::utilities::message_digest::MessageDigest;
 use crate::protocols::gg_2020::state_machine::sign::{
     types::SignRoundResult,
     error::sign_round_error::SignRoundError
@@ -18,15 +18,15 @@ pub struct Round7 {
 
 impl Round7 {
     pub fn new(
-        message: &BigInt,
+        message: &MessageDigest,
         completed_offline_stage: CompletedOfflineStage,
     ) -> SignRoundResult<(Self, PartialSignature)> {
         let local_signature = LocalSignature::phase7_local_sig(
-            &completed_offline_stage.sign_keys.k_i,
+            &completed_offline_stage.k_i,
             message,
             &completed_offline_stage.R,
             &completed_offline_stage.sigma_i,
-            &completed_offline_stage.local_key.public_key,
+            &completed_offline_stage.public_key,
         );
         let partial = PartialSignature(local_signature.s_i.clone());
         Ok((Self { local_signature }, partial))
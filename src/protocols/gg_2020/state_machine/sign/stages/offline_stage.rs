@@ -1,10 +1,12 @@
 use std::convert::TryFrom;
 use std::mem::replace;
+use std::time::Duration;
 use curv::elliptic::curves::secp256_k1::Secp256k1;
 use round_based::containers::{BroadcastMsgs, MessageStore, P2PMsgs, Store};
 
 use crate::{
     utilities::mta::MessageA,
+    utilities::session_id::SessionId,
     utilities::zk_pdl_with_slack::PDLwSlackProof,
     protocols::gg_2020::{
         party_i::{
@@ -12,11 +14,13 @@ use crate::{
             SignDecommitPhase1, 
         },
         state_machine::{
+            echo::{EchoMessage, EchoTranscript},
             keygen::local_key::LocalKey,
             sign::{
                 error::{
                     internal_error::InternalError,
                     sign_error::SignError,
+                    sign_round_error::SignRoundError,
                 },
                 types::SignResult,
                 messages::{
@@ -29,6 +33,7 @@ use crate::{
                     SI,
                     HEGProof,
                     MsgQueue,
+                    OfflineM,
                 },
                 rounds::{
                     round_0::Round0,
@@ -38,7 +43,8 @@ use crate::{
                     round_4::Round4,
                     round_5::Round5,
                     round_6::Round6, 
-                    CompletedOfflineStage
+                    CompletedOfflineStage,
+                    EchoRound,
                 },
             },
         },
@@ -56,6 +62,7 @@ pub enum OfflineR {
     R4(Round4),
     R5(Round5),
     R6(Round6),
+    Echo(EchoRound),
     Finished(CompletedOfflineStage),
     Gone,
 }
@@ -69,6 +76,12 @@ pub struct OfflineStage {
     pub(crate) msgs4: Option<Store<BroadcastMsgs<SignDecommitPhase1>>>,
     pub(crate) msgs5: Option<Store<BroadcastMsgs<(RDash, Vec<PDLwSlackProof>)>>>,
     pub(crate) msgs6: Option<Store<BroadcastMsgs<(SI, HEGProof)>>>,
+    pub(crate) msgs_echo: Option<Store<BroadcastMsgs<EchoMessage>>>,
+
+    pub(crate) echo: Option<EchoTranscript>,
+    pub(crate) echo_broadcast: bool,
+
+    pub(crate) round_timeout: Option<Duration>,
 
     pub(crate) msgs_queue: MsgQueue,
 
@@ -86,8 +99,16 @@ impl OfflineStage {
     /// (`s_l[i]` must be an index of party `i` that was used by this party in keygen protocol), and
     /// party local secret share `local_key`.
     ///
+    /// `session_id` is bound into every proof and commitment sent during the protocol. All the
+    /// parties must use the same session id, and it must be unique for every signing.
+    ///
     /// Returns error if given arguments are contradicting.
-    pub fn new(i: u16, s_l: Vec<u16>, local_key: LocalKey<Secp256k1>) -> SignResult<Self> {
+    pub fn new(
+        i: u16,
+        s_l: Vec<u16>,
+        local_key: LocalKey<Secp256k1>,
+        session_id: SessionId,
+    ) -> SignResult<Self> {
         if s_l.len() < 2 {
             return Err(SignError::TooFewParties);
         }
@@ -114,7 +135,12 @@ impl OfflineStage {
         let n = u16::try_from(s_l.len()).map_err(|_| SignError::TooManyParties { n: s_l.len() })?;
 
         Ok(Self {
-            round: OfflineR::R0(Round0 { i, s_l, local_key }),
+            round: OfflineR::R0(Round0 {
+                i,
+                s_l,
+                local_key,
+                session_id,
+            }),
 
             msgs1: Some(Round1::expects_messages(i, n)),
             msgs2: Some(Round2::expects_messages(i, n)),
@@ -122,6 +148,12 @@ impl OfflineStage {
             msgs4: Some(Round4::expects_messages(i, n)),
             msgs5: Some(Round5::expects_messages(i, n)),
             msgs6: Some(Round6::expects_messages(i, n)),
+            msgs_echo: None,
+
+            echo: None,
+            echo_broadcast: false,
+
+            round_timeout: None,
 
             msgs_queue: MsgQueue(vec![]),
 
@@ -130,6 +162,39 @@ impl OfflineStage {
         })
     }
 
+    /// Enables echo broadcast
+    ///
+    /// Adds an extra round in which parties exchange digests of all broadcast messages they
+    /// received. If any party received different broadcast messages than others, offline stage
+    /// fails with [SignRoundError::Equivocation] listing parties whose messages were inconsistent.
+    /// All parties must agree on enabling echo broadcast.
+    pub fn with_echo_broadcast(mut self) -> Self {
+        self.msgs_echo = Some(EchoRound::expects_messages(self.party_i, self.party_n));
+        self.echo = Some(EchoTranscript::new());
+        self.echo_broadcast = true;
+        self
+    }
+
+    /// Sets a deadline for every round
+    ///
+    /// If party doesn't receive all messages of the round within `timeout`, offline stage
+    /// terminates with [SignError::Timeout] listing parties whose messages are missing. Signing
+    /// can then be restarted without them, see [SignError::remaining_signers].
+    pub fn with_round_timeout(mut self, timeout: Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    /// Records broadcast messages queued starting from `from` into echo transcript
+    fn record_own_broadcasts(&mut self, from: usize) {
+        if let Some(echo) = self.echo.as_mut() {
+            self.msgs_queue.0[from..]
+                .iter()
+                .filter(|m| m.receiver.is_none() && !matches!(m.body.0, OfflineM::Echo(_)))
+                .for_each(|m| echo.record(m.sender, &m.body));
+        }
+    }
+
     // fn proceed_state(&mut self, may_block: bool) -> Result<()> {
     //     self.proceed_round(may_block)?;
     //     self.proceed_decommit_round(may_block)
@@ -142,7 +207,9 @@ impl OfflineStage {
         let store4_wants_more = self.msgs4.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store5_wants_more = self.msgs5.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store6_wants_more = self.msgs6.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store_echo_wants_more = self.msgs_echo.as_ref().map(|s| s.wants_more()).unwrap_or(false);
 
+        let queued = self.msgs_queue.0.len();
         let next_state: OfflineR;
         let try_again: bool = match replace(&mut self.round, OfflineR::Gone) {
             OfflineR::R0(round) if !round.is_expensive() || may_block => {
@@ -236,16 +303,37 @@ impl OfflineStage {
                 let msgs = store
                     .finish()
                     .map_err(InternalError::RetrieveMessagesFromStore)?;
-                next_state = round
+                let completed = round
                     .proceed(msgs)
-                    .map(OfflineR::Finished)
                     .map_err(SignError::ProceedRound)?;
+                next_state = match self.echo.take() {
+                    Some(echo) => {
+                        let (i, n) = (self.party_i, self.party_n);
+                        OfflineR::Echo(EchoRound::new(completed, &echo, i, n, &mut self.msgs_queue))
+                    }
+                    None => OfflineR::Finished(completed),
+                };
                 false
             }
             s @ OfflineR::R6(_) => {
                 next_state = s;
                 false
             }
+            OfflineR::Echo(round) if !store_echo_wants_more => {
+                let store = self.msgs_echo.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveMessagesFromStore)?;
+                next_state = round
+                    .proceed(msgs)
+                    .map(OfflineR::Finished)
+                    .map_err(|e| SignError::ProceedRound(SignRoundError::Equivocation(e)))?;
+                false
+            }
+            s @ OfflineR::Echo(_) => {
+                next_state = s;
+                false
+            }
             s @ OfflineR::Finished(_) | s @ OfflineR::Gone => {
                 next_state = s;
                 false
@@ -253,6 +341,7 @@ impl OfflineStage {
         };
 
         self.round = next_state;
+        self.record_own_broadcasts(queued);
         if try_again {
             self.proceed_round(may_block)
         } else {
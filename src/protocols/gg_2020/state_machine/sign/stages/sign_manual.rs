@@ -1,5 +1,4 @@
-use curv::BigInt;
-
+use crate::utilities::message_digest::MessageDigest;
 use crate::protocols::gg_2020::{
     party_i::SignatureRecid,
     state_machine::sign::{
@@ -18,8 +17,12 @@ pub struct SignManual {
 }
 
 impl SignManual {
+    /// Computes partial signature of the message digest
+    ///
+    /// Hash the message with one of [MessageDigest] constructors first: signing a raw message
+    /// isn't supported.
     pub fn new(
-        message: BigInt,
+        message: MessageDigest,
         completed_offline_stage: CompletedOfflineStage,
     ) -> Result<(Self, PartialSignature), SignLocalCompleteError> {
         Round7::new(&message, completed_offline_stage)
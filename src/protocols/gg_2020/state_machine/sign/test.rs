@@ -1,35 +1,42 @@
 
-use curv::BigInt;
-use curv::arithmetic::Converter;
-use curv::cryptographic_primitives::hashing::{Digest, DigestExt};
 use curv::elliptic::curves::Secp256k1;
+use curv::BigInt;
 use round_based::dev::Simulation;
-use sha2::Sha256;
+use round_based::StateMachine;
+use std::time::Duration;
 
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
 use crate::protocols::gg_2020::{
     state_machine::keygen::local_key::LocalKey,
     party_i::verify,
-    state_machine::keygen::test::simulate_keygen,
+    state_machine::keygen::test::{collect_errors, collect_messages, simulate_keygen},
+    state_machine::traits::RoundMessage,
     state_machine::sign::{
+        error::sign_error::SignError,
+        error::sign_round_error::SignRoundError,
+        messages::{OfflineM, OfflineProtocolMessage},
         rounds::CompletedOfflineStage,
         stages::offline_stage::OfflineStage,
         stages::sign_manual::SignManual,
     }
 };
 
-fn simulate_offline_stage(
+pub fn simulate_offline_stage(
     local_keys: Vec<LocalKey<Secp256k1>>,
     s_l: &[u16],
 ) -> Vec<CompletedOfflineStage> {
     let mut simulation = Simulation::new();
     simulation.enable_benchmarks(true);
 
+    let session_id = SessionId::random();
     for (i, &keygen_i) in (1..).zip(s_l) {
         simulation.add_party(
             OfflineStage::new(
                 i,
                 s_l.to_vec(),
                 local_keys[usize::from(keygen_i - 1)].clone(),
+                session_id,
             )
             .unwrap(),
         );
@@ -44,14 +51,12 @@ fn simulate_offline_stage(
 }
 
 fn simulate_signing(offline: Vec<CompletedOfflineStage>, message: &[u8]) {
-    let message = Sha256::new()
-        .chain_bigint(&BigInt::from_bytes(message))
-        .result_bigint();
+    let message = MessageDigest::sha256(message);
     let pk = offline[0].public_key().clone();
 
     let parties = offline
         .iter()
-        .map(|o| SignManual::new(message.clone(), o.clone()))
+        .map(|o| SignManual::new(message, o.clone()))
         .collect::<Result<Vec<_>, _>>()
         .unwrap();
     let (parties, local_sigs): (Vec<_>, Vec<_>) = parties.into_iter().unzip();
@@ -65,11 +70,11 @@ fn simulate_signing(offline: Vec<CompletedOfflineStage>, message: &[u8]) {
         v
     };
 
-    assert!(parties
-        .into_iter()
-        .enumerate()
-        .map(|(i, p)| p.complete(&local_sigs_except(i)).unwrap())
-        .all(|signature| verify(&signature, &pk, &message).is_ok()));
+    for (i, p) in parties.into_iter().enumerate() {
+        let signature = p.complete(&local_sigs_except(i)).unwrap();
+        assert!(verify(&signature, &pk, &message).is_ok());
+        assert_eq!(signature.recover_public_key(&message).unwrap(), pk);
+    }
 }
 
 #[test]
@@ -114,3 +119,214 @@ fn simulate_signing_t2_n3_s3() {
     let offline_stage = simulate_offline_stage(local_keys, &[1, 2, 3]);
     simulate_signing(offline_stage, b"ZenGo")
 }
+
+#[test]
+fn recovery_id_recovers_public_key_over_many_signings() {
+    let local_keys = simulate_keygen(1, 2);
+    for i in 0..16u32 {
+        let offline_stage = simulate_offline_stage(local_keys.clone(), &[1, 2]);
+        assert_eq!(offline_stage[0].public_key(), &local_keys[0].public_key);
+        // simulate_signing checks that every signature recovers the public key
+        simulate_signing(offline_stage, &i.to_be_bytes());
+    }
+}
+
+#[test]
+fn simulate_signing_with_echo_broadcast() {
+    let local_keys = simulate_keygen(1, 3);
+    let s_l = [1, 3];
+    let session_id = SessionId::random();
+    let mut simulation = Simulation::new();
+    for (i, &keygen_i) in (1..).zip(&s_l) {
+        let stage = OfflineStage::new(
+            i,
+            s_l.to_vec(),
+            local_keys[usize::from(keygen_i - 1)].clone(),
+            session_id,
+        )
+        .unwrap()
+        .with_echo_broadcast();
+        simulation.add_party(stage);
+    }
+    let offline_stage = simulation.run().unwrap();
+    simulate_signing(offline_stage, b"ZenGo");
+}
+
+#[test]
+fn echo_broadcast_detects_equivocation_in_offline_stage() {
+    let local_keys = simulate_keygen(1, 3);
+    let s_l = vec![1, 2, 3];
+    let session_id = SessionId::random();
+    let parties = (1..=3)
+        .map(|i| {
+            OfflineStage::new(
+                i,
+                s_l.clone(),
+                local_keys[usize::from(i - 1)].clone(),
+                session_id,
+            )
+            .unwrap()
+            .with_echo_broadcast()
+        })
+        .collect();
+    // Party 1 sends party 3 a round 1 broadcast that differs from the one seen by party 2.
+    // Range proof stays valid when `s` is shifted by N^2, so the protocol itself doesn't notice
+    let nn = local_keys[0].paillier_key_vec[0].nn.clone();
+    let errors = collect_errors(parties, |i, msg| {
+        if let (1, 3, OfflineProtocolMessage(OfflineM::M1((m_a, _)))) =
+            (msg.sender, i, &mut msg.body)
+        {
+            let mut proof = serde_json::to_value(&m_a.range_proofs[0]).unwrap();
+            let s: BigInt = serde_json::from_value(proof["s"].take()).unwrap();
+            proof["s"] = serde_json::to_value(s + &nn).unwrap();
+            m_a.range_proofs[0] = serde_json::from_value(proof).unwrap();
+        }
+    });
+    for error in errors {
+        match error {
+            SignError::ProceedRound(SignRoundError::Equivocation(e)) => {
+                assert_eq!(e.parties, vec![1])
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn offline_stage_fails_if_session_ids_differ() {
+    let local_keys = simulate_keygen(1, 3);
+    let s_l = [1, 2];
+    let mut simulation = Simulation::new();
+    for (i, &keygen_i) in (1..).zip(&s_l) {
+        let stage = OfflineStage::new(
+            i,
+            s_l.to_vec(),
+            local_keys[usize::from(keygen_i - 1)].clone(),
+            SessionId::new(&[i as u8]),
+        )
+        .unwrap();
+        simulation.add_party(stage);
+    }
+    assert!(simulation.run().is_err());
+}
+
+#[test]
+fn offline_stage_restarts_without_timed_out_parties() {
+    let local_keys = simulate_keygen(1, 3);
+    let s_l = vec![1, 2, 3];
+    let session_id = SessionId::random();
+    let stage = |i: u16| {
+        OfflineStage::new(
+            i,
+            s_l.clone(),
+            local_keys[usize::from(s_l[usize::from(i - 1)] - 1)].clone(),
+            session_id,
+        )
+        .unwrap()
+    };
+    let mut party1 = stage(1).with_round_timeout(Duration::from_secs(10));
+    let mut party2 = stage(2);
+    assert_eq!(party1.round_timeout(), Some(Duration::from_secs(10)));
+
+    // Party 3 is silent
+    for party in [&mut party1, &mut party2].iter_mut() {
+        if party.wants_to_proceed() {
+            party.proceed().unwrap();
+        }
+    }
+    for msg in party2.message_queue().drain(..).collect::<Vec<_>>() {
+        if msg.receiver.is_none() || msg.receiver == Some(1) {
+            party1.handle_incoming(msg).unwrap();
+        }
+    }
+
+    let err = party1.round_timeout_reached();
+    assert!(matches!(
+        &err,
+        SignError::Timeout { round: 1, missing_parties } if missing_parties == &vec![3]
+    ));
+    let s_l = err.remaining_signers(&s_l).unwrap();
+    assert_eq!(s_l, vec![1, 2]);
+
+    let offline_stage = simulate_offline_stage(local_keys, &s_l);
+    simulate_signing(offline_stage, b"ZenGo");
+}
+
+#[test]
+fn remaining_signers_are_keygen_indexes() {
+    let err = SignError::Timeout {
+        round: 3,
+        missing_parties: vec![1, 3],
+    };
+    assert_eq!(err.remaining_signers(&[3, 1, 2, 5]), Some(vec![1, 5]));
+    assert_eq!(SignError::InvalidSl.remaining_signers(&[1, 2]), None);
+}
+
+fn offline_stage_messages(s_l: &[u16]) -> Vec<OfflineProtocolMessage> {
+    let local_keys = simulate_keygen(1, 3);
+    let session_id = SessionId::random();
+    let parties = (1..)
+        .zip(s_l)
+        .map(|(i, &keygen_i)| {
+            OfflineStage::new(
+                i,
+                s_l.to_vec(),
+                local_keys[usize::from(keygen_i - 1)].clone(),
+                session_id,
+            )
+            .unwrap()
+        })
+        .collect();
+    collect_messages(parties)
+        .into_iter()
+        .map(|msg| msg.body)
+        .collect()
+}
+
+#[test]
+fn offline_stage_messages_round_trip_through_wire_format() {
+    for msg in offline_stage_messages(&[1, 2, 3]) {
+        let bytes = msg.to_bytes();
+        let decoded = OfflineProtocolMessage::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&msg).unwrap()
+        );
+
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(OfflineProtocolMessage::from_bytes(&extended).is_err());
+    }
+}
+
+#[test]
+fn offline_stage_wire_format_size_regression() {
+    let (mut binary_total, mut json_total) = (0, 0);
+    for msg in offline_stage_messages(&[1, 2, 3]) {
+        let binary = msg.to_bytes().len();
+        let limit = match msg.round() {
+            1 => 5 * 1024,
+            2 => 2 * 1024,
+            3 => 256,
+            4 => 128,
+            5 => 7 * 1024,
+            _ => 256,
+        };
+        assert!(
+            binary <= limit,
+            "round {} message takes {} bytes, expected at most {}",
+            msg.round(),
+            binary,
+            limit
+        );
+        binary_total += binary;
+        json_total += serde_json::to_vec(&msg).unwrap().len();
+    }
+    assert!(
+        binary_total * 3 < json_total * 2,
+        "binary encoding ({} bytes) is not much smaller than json ({} bytes)",
+        binary_total,
+        json_total
+    );
+}
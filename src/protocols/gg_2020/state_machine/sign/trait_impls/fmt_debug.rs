@@ -2,7 +2,9 @@ use std::fmt;
 
 use round_based::containers::{BroadcastMsgsStore, MessageStore, P2PMsgsStore};
 
+use crate::protocols::gg_2020::party_i::{LocalSignature, SignKeys};
 use crate::protocols::gg_2020::state_machine::sign::stages::offline_stage::{self, OfflineStage};
+use crate::utilities::fingerprint::Fingerprint;
 
 impl fmt::Debug for OfflineStage {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -19,6 +21,7 @@ pub struct OfflineStageProgress {
     round3_msgs: ReceivedMessages,
     round4_msgs: ReceivedMessages,
     round5_msgs: ReceivedMessages,
+    echo_msgs: ReceivedMessages,
 
     msgs_queue: OutgoingMessages,
 }
@@ -34,6 +37,7 @@ impl From<&OfflineStage> for OfflineStageProgress {
                 offline_stage::OfflineR::R4(_) => OfflineR::R4,
                 offline_stage::OfflineR::R5(_) => OfflineR::R5,
                 offline_stage::OfflineR::R6(_) => OfflineR::R6,
+                offline_stage::OfflineR::Echo(_) => OfflineR::Echo,
                 offline_stage::OfflineR::Finished(_) => OfflineR::Finished,
                 offline_stage::OfflineR::Gone => OfflineR::Gone,
             },
@@ -43,6 +47,7 @@ impl From<&OfflineStage> for OfflineStageProgress {
             round3_msgs: ReceivedMessages::from_broadcast(state.msgs3.as_ref()),
             round4_msgs: ReceivedMessages::from_broadcast(state.msgs4.as_ref()),
             round5_msgs: ReceivedMessages::from_broadcast(state.msgs5.as_ref()),
+            echo_msgs: ReceivedMessages::from_broadcast(state.msgs_echo.as_ref()),
 
             msgs_queue: OutgoingMessages {
                 len: state.msgs_queue.0.len(),
@@ -60,6 +65,7 @@ pub enum OfflineR {
     R4,
     R5,
     R6,
+    Echo,
     Finished,
     Gone,
 }
@@ -125,3 +131,27 @@ impl fmt::Debug for ReceivedMessages {
 pub struct OutgoingMessages {
     len: usize,
 }
+
+impl fmt::Debug for SignKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SignKeys")
+            .field("w_i", &Fingerprint::of_scalar(&self.w_i))
+            .field("g_w_i", &self.g_w_i)
+            .field("k_i", &Fingerprint::of_scalar(&self.k_i))
+            .field("gamma_i", &Fingerprint::of_scalar(&self.gamma_i))
+            .field("g_gamma_i", &self.g_gamma_i)
+            .finish()
+    }
+}
+
+impl fmt::Debug for LocalSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LocalSignature")
+            .field("r", &self.r)
+            .field("R", &self.R)
+            .field("s_i", &Fingerprint::of_scalar(&self.s_i))
+            .field("m", &self.m)
+            .field("y", &self.y)
+            .finish()
+    }
+}
@@ -18,6 +18,7 @@ impl RoundBlame for OfflineStage {
         let store4_blame = self.msgs4.as_ref().map(|s| s.blame()).unwrap_or_default();
         let store5_blame = self.msgs5.as_ref().map(|s| s.blame()).unwrap_or_default();
         let store6_blame = self.msgs6.as_ref().map(|s| s.blame()).unwrap_or_default();
+        let store_echo_blame = self.msgs_echo.as_ref().map(|s| s.blame()).unwrap_or_default();
 
         let default = (0, vec![]);
         match &self.round {
@@ -28,6 +29,7 @@ impl RoundBlame for OfflineStage {
             OfflineR::R4(_) => store4_blame,
             OfflineR::R5(_) => store5_blame,
             OfflineR::R6(_) => store6_blame,
+            OfflineR::Echo(_) => store_echo_blame,
             OfflineR::Finished(_) => store6_blame,
             OfflineR::Gone => default,
         }
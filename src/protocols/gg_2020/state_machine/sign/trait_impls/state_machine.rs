@@ -16,6 +16,7 @@ use crate::protocols::gg_2020::state_machine::sign::{
     stages::offline_stage::OfflineR,
     rounds::CompletedOfflineStage
 };
+use crate::protocols::gg_2020::state_machine::traits::RoundBlame;
 
 impl StateMachine for OfflineStage {
     type MessageBody = OfflineProtocolMessage;
@@ -24,6 +25,12 @@ impl StateMachine for OfflineStage {
 
     fn handle_incoming(&mut self, msg: Msg<Self::MessageBody>) -> Result<(), Self::Err> {
         let current_round = self.current_round();
+        let sender = msg.sender;
+        let echoed = match &msg.body {
+            OfflineProtocolMessage(OfflineM::Echo(_)) => None,
+            body if self.echo.is_some() && msg.receiver.is_none() => Some(body.clone()),
+            _ => None,
+        };
 
         match msg.body {
             OfflineProtocolMessage(OfflineM::M1(m)) => {
@@ -122,6 +129,26 @@ impl StateMachine for OfflineStage {
                     })
                     .map_err(SignError::HandleMessage)?;
             }
+            OfflineProtocolMessage(OfflineM::Echo(m)) => {
+                let store = self
+                    .msgs_echo
+                    .as_mut()
+                    .ok_or(SignError::ReceivedOutOfOrderMessage {
+                        current_round,
+                        msg_round: 7,
+                    })?;
+                store
+                    .push_msg(Msg {
+                        sender: msg.sender,
+                        receiver: msg.receiver,
+                        body: m,
+                    })
+                    .map_err(SignError::HandleMessage)?;
+            }
+        }
+
+        if let (Some(echo), Some(body)) = (self.echo.as_mut(), echoed) {
+            echo.record(sender, &body);
         }
         self.proceed_round(false)
     }
@@ -137,6 +164,7 @@ impl StateMachine for OfflineStage {
         let store4_wants_more = self.msgs4.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store5_wants_more = self.msgs5.as_ref().map(|s| s.wants_more()).unwrap_or(false);
         let store6_wants_more = self.msgs6.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store_echo_wants_more = self.msgs_echo.as_ref().map(|s| s.wants_more()).unwrap_or(false);
 
         match &self.round {
             OfflineR::R0(_) => true,
@@ -146,6 +174,7 @@ impl StateMachine for OfflineStage {
             OfflineR::R4(_) => !store4_wants_more,
             OfflineR::R5(_) => !store5_wants_more,
             OfflineR::R6(_) => !store6_wants_more,
+            OfflineR::Echo(_) => !store_echo_wants_more,
             OfflineR::Finished(_) | OfflineR::Gone => false,
         }
     }
@@ -155,11 +184,15 @@ impl StateMachine for OfflineStage {
     }
 
     fn round_timeout(&self) -> Option<Duration> {
-        None
+        self.round_timeout
     }
 
     fn round_timeout_reached(&mut self) -> Self::Err {
-        panic!("no timeout was set")
+        let (_, missing_parties) = self.round_blame();
+        SignError::Timeout {
+            round: self.current_round(),
+            missing_parties,
+        }
     }
 
     fn is_finished(&self) -> bool {
@@ -188,12 +221,17 @@ impl StateMachine for OfflineStage {
             OfflineR::R4(_) => 4,
             OfflineR::R5(_) => 5,
             OfflineR::R6(_) => 6,
-            OfflineR::Finished(_) | OfflineR::Gone => 7,
+            OfflineR::Echo(_) => 7,
+            OfflineR::Finished(_) | OfflineR::Gone => self.total_rounds().unwrap_or(6) + 1,
         }
     }
 
     fn total_rounds(&self) -> Option<u16> {
-        Some(6)
+        if self.echo_broadcast {
+            Some(7)
+        } else {
+            Some(6)
+        }
     }
 
     fn party_ind(&self) -> u16 {
@@ -4,3 +4,9 @@ pub trait RoundBlame {
     /// Returns a numbers of messages yet to recieve and list of parties to send messages for the current round
     fn round_blame(&self) -> (u16, Vec<u16>);
 }
+
+/// Protocol message which knows the round it belongs to
+pub trait RoundMessage {
+    /// Number of the round (starting from 1) in which the message is sent
+    fn round(&self) -> u16;
+}
@@ -0,0 +1,198 @@
+//! Threshold BIP340 Schnorr signing with key shares obtained from GG20 [keygen]
+//!
+//! Signing follows FROST: in the first round every signer commits to a pair of single-use
+//! nonces, in the second round it sends its share of the signature. Nonces are bound to the
+//! whole set of commitments, the message and the session id, so they must never be reused
+//! across signings. The same [LocalKey] can be used both for ECDSA and Schnorr signing.
+//!
+//! BIP340 requires both the public key and the nonce to have even y coordinate. If they don't,
+//! signers negate their key shares and nonces respectively, so the resulting signature verifies
+//! against x-only encoding of [LocalKey::public_key].
+//!
+//! [keygen]: crate::protocols::gg_2020::state_machine::keygen::Keygen
+
+pub mod bip340;
+pub mod error;
+pub mod messages;
+mod rounds;
+pub mod trait_impls;
+#[cfg(test)]
+mod test;
+
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::mem::replace;
+use std::time::Duration;
+
+use curv::elliptic::curves::Secp256k1;
+use round_based::containers::push::{Push, PushExt};
+use round_based::containers::{BroadcastMsgs, MessageStore, Store};
+use round_based::Msg;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
+
+use self::error::{InternalError, SchnorrSignError};
+use self::messages::{NonceCommitment, ProtocolMessage, SignatureShare, M};
+use self::rounds::{round_0::Round0, round_1::Round1, round_2::Round2, R};
+
+pub use self::bip340::SchnorrSignature;
+
+/// Party of threshold BIP340 signing protocol
+pub struct SchnorrSign {
+    round: R,
+
+    msgs1: Option<Store<BroadcastMsgs<NonceCommitment>>>,
+    msgs2: Option<Store<BroadcastMsgs<SignatureShare>>>,
+
+    round_timeout: Option<Duration>,
+
+    msgs_queue: Vec<Msg<ProtocolMessage>>,
+
+    party_i: u16,
+    party_n: u16,
+}
+
+impl SchnorrSign {
+    /// Constructs a party of threshold Schnorr signing
+    ///
+    /// Takes party index `i` (in range `[1; n]`), list `s_l` of parties' indexes from keygen
+    /// protocol (`s_l[i-1]` must be an index of this party that was used in keygen protocol),
+    /// party local secret share `local_key`, and the 32-byte `message` to be signed.
+    ///
+    /// `session_id` is bound into the nonces of every signer. All the parties must use the same
+    /// session id, and it must be unique for every signing.
+    ///
+    /// Returns error if given arguments are contradicting.
+    pub fn new(
+        i: u16,
+        s_l: Vec<u16>,
+        local_key: LocalKey<Secp256k1>,
+        message: MessageDigest,
+        session_id: SessionId,
+    ) -> Result<Self, SchnorrSignError> {
+        let required = usize::from(local_key.vss_scheme.parameters.threshold) + 1;
+        if s_l.len() < required {
+            return Err(SchnorrSignError::TooFewParties {
+                required,
+                actual: s_l.len(),
+            });
+        }
+        let n = u16::try_from(s_l.len())
+            .map_err(|_| SchnorrSignError::TooManyParties { n: s_l.len() })?;
+        if i == 0 || i > n {
+            return Err(SchnorrSignError::InvalidPartyIndex);
+        }
+
+        let keygen_n = local_key.key_params.share_count;
+        let points = &local_key.party_to_point_map.points;
+        if s_l
+            .iter()
+            .any(|&j| j == 0 || j > keygen_n || !points.contains_key(&usize::from(j)))
+        {
+            return Err(SchnorrSignError::InvalidSl);
+        }
+        if s_l.iter().collect::<BTreeSet<_>>().len() != s_l.len() {
+            return Err(SchnorrSignError::InvalidSl);
+        }
+        if usize::from(s_l[usize::from(i - 1)]) != local_key.own_party_index {
+            return Err(SchnorrSignError::InvalidSl);
+        }
+
+        let mut state = Self {
+            round: R::Round0(Round0 {
+                i,
+                s_l,
+                local_key,
+                message,
+                session_id,
+            }),
+
+            msgs1: Some(Round1::expects_messages(i, n)),
+            msgs2: Some(Round2::expects_messages(i, n)),
+
+            round_timeout: None,
+
+            msgs_queue: vec![],
+
+            party_i: i,
+            party_n: n,
+        };
+
+        state.proceed_round(false)?;
+        Ok(state)
+    }
+
+    /// Sets a deadline for every round
+    ///
+    /// If party doesn't receive all messages of the round within `timeout`, signing terminates
+    /// with [SchnorrSignError::Timeout] listing parties whose messages are missing.
+    pub fn with_round_timeout(mut self, timeout: Duration) -> Self {
+        self.round_timeout = Some(timeout);
+        self
+    }
+
+    fn gmap_queue<'a, T, F>(&'a mut self, mut f: F) -> impl Push<Msg<T>> + 'a
+    where
+        F: FnMut(T) -> M + 'a,
+    {
+        (&mut self.msgs_queue).gmap(move |m: Msg<T>| m.map_body(|m| ProtocolMessage(f(m))))
+    }
+
+    /// Proceeds round state if it received enough messages and if it's cheap to compute or
+    /// `may_block == true`
+    fn proceed_round(&mut self, may_block: bool) -> Result<(), SchnorrSignError> {
+        let store1_wants_more = self.msgs1.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store2_wants_more = self.msgs2.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+
+        let next_state: R;
+        let try_again: bool = match replace(&mut self.round, R::Gone) {
+            R::Round0(round) if !round.is_expensive() || may_block => {
+                next_state = R::Round1(round.proceed(self.gmap_queue(M::Round1)));
+                true
+            }
+            s @ R::Round0(_) => {
+                next_state = s;
+                false
+            }
+            R::Round1(round) if !store1_wants_more && (!round.is_expensive() || may_block) => {
+                let store = self.msgs1.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveRoundMessages)?;
+                next_state = round
+                    .proceed(msgs, self.gmap_queue(M::Round2))
+                    .map(R::Round2)?;
+                true
+            }
+            s @ R::Round1(_) => {
+                next_state = s;
+                false
+            }
+            R::Round2(round) if !store2_wants_more && (!round.is_expensive() || may_block) => {
+                let store = self.msgs2.take().ok_or(InternalError::StoreGone)?;
+                let msgs = store
+                    .finish()
+                    .map_err(InternalError::RetrieveRoundMessages)?;
+                next_state = round.proceed(msgs).map(R::Final)?;
+                true
+            }
+            s @ R::Round2(_) => {
+                next_state = s;
+                false
+            }
+            s @ R::Final(_) | s @ R::Gone => {
+                next_state = s;
+                false
+            }
+        };
+
+        self.round = next_state;
+        if try_again {
+            self.proceed_round(may_block)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! BIP340 signatures and their verification
+
+use std::fmt;
+
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::utilities::message_digest::MessageDigest;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Bip340Error {
+    #[error("public key is not an x coordinate of a curve point")]
+    InvalidPublicKey,
+    #[error("s is not less than group order")]
+    SOutOfRange,
+    #[error("signature doesn't match the public key and message")]
+    InvalidSignature,
+}
+
+/// 64-byte BIP340 signature `R.x || s`
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchnorrSignature(#[serde(with = "hex_64")] [u8; 64]);
+
+impl SchnorrSignature {
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    pub(super) fn new(r: &Point<Secp256k1>, s: &Scalar<Secp256k1>) -> Self {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&x_only(r));
+        bytes[32..].copy_from_slice(&scalar_bytes(s));
+        Self(bytes)
+    }
+}
+
+impl fmt::Debug for SchnorrSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SchnorrSignature({})", hex::encode(self.0))
+    }
+}
+
+/// X-only encoding of the point, i.e. its x coordinate as 32 big-endian bytes
+///
+/// This is how BIP340 and Taproot encode public keys.
+pub fn x_only(point: &Point<Secp256k1>) -> [u8; 32] {
+    let mut x = [0u8; 32];
+    x.copy_from_slice(&point.to_bytes(true)[1..]);
+    x
+}
+
+pub(super) fn has_even_y(point: &Point<Secp256k1>) -> bool {
+    point.to_bytes(true)[0] == 0x02
+}
+
+/// `SHA256(SHA256(tag) || SHA256(tag) || data)`
+pub(super) fn tagged_hash(tag: &str, data: &[&[u8]]) -> [u8; 32] {
+    let tag = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new().chain(&tag).chain(&tag);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+/// `e = H_challenge(R.x || P.x || m) mod q`
+pub(super) fn challenge(
+    r_x: &[u8; 32],
+    public_key_x: &[u8; 32],
+    message: &MessageDigest,
+) -> Scalar<Secp256k1> {
+    let e = tagged_hash("BIP0340/challenge", &[r_x, public_key_x, message.as_bytes()]);
+    Scalar::from_bigint(&BigInt::from_bytes(&e))
+}
+
+/// Verifies BIP340 signature of the message under x-only public key
+pub fn verify(
+    public_key: &[u8; 32],
+    message: &MessageDigest,
+    signature: &SchnorrSignature,
+) -> Result<(), Bip340Error> {
+    let mut compressed = [0x02; 33];
+    compressed[1..].copy_from_slice(public_key);
+    let public_key_point =
+        Point::<Secp256k1>::from_bytes(&compressed).map_err(|_| Bip340Error::InvalidPublicKey)?;
+
+    let mut r_x = [0u8; 32];
+    r_x.copy_from_slice(&signature.0[..32]);
+    let s = BigInt::from_bytes(&signature.0[32..]);
+    if &s >= Scalar::<Secp256k1>::group_order() {
+        return Err(Bip340Error::SOutOfRange);
+    }
+    let s = Scalar::<Secp256k1>::from_bigint(&s);
+
+    let e = challenge(&r_x, public_key, message);
+    let r = Point::generator() * s - public_key_point * e;
+    if r.is_zero() || !has_even_y(&r) || x_only(&r) != r_x {
+        return Err(Bip340Error::InvalidSignature);
+    }
+    Ok(())
+}
+
+fn scalar_bytes(scalar: &Scalar<Secp256k1>) -> [u8; 32] {
+    let bytes = scalar.to_bigint().to_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|b| **b == 0).count()..];
+    let mut padded = [0u8; 32];
+    padded[32 - bytes.len()..].copy_from_slice(bytes);
+    padded
+}
+
+mod hex_64 {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8; 64], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 64], D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let bytes = hex::decode(&s).map_err(D::Error::custom)?;
+        let mut out = [0u8; 64];
+        if bytes.len() != 64 {
+            return Err(D::Error::invalid_length(bytes.len(), &"64 bytes"));
+        }
+        out.copy_from_slice(&bytes);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,104 @@
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Scalar, Secp256k1};
+
+use crate::protocols::schnorr_bip340::bip340::{verify, Bip340Error, SchnorrSignature};
+use crate::utilities::message_digest::MessageDigest;
+
+struct Vector {
+    public_key: &'static str,
+    message: &'static str,
+    signature: &'static str,
+}
+
+/// Test vectors 0-3 from BIP340
+const VECTORS: &[Vector] = &[
+    Vector {
+        public_key: "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+        message: "0000000000000000000000000000000000000000000000000000000000000000",
+        signature: "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA8215\
+                    25F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+    },
+    Vector {
+        public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+        message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+        signature: "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE3341\
+                    8906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+    },
+    Vector {
+        public_key: "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+        message: "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+        signature: "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1B\
+                    AB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+    },
+    Vector {
+        public_key: "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+        message: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+        signature: "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC\
+                    97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+    },
+];
+
+fn parse(vector: &Vector) -> ([u8; 32], MessageDigest, SchnorrSignature) {
+    let mut public_key = [0u8; 32];
+    public_key.copy_from_slice(&hex::decode(vector.public_key).unwrap());
+    let message = MessageDigest::from_slice(&hex::decode(vector.message).unwrap()).unwrap();
+    let mut signature = [0u8; 64];
+    signature.copy_from_slice(&hex::decode(vector.signature).unwrap());
+    (public_key, message, SchnorrSignature::from_bytes(signature))
+}
+
+#[test]
+fn reference_vectors_are_valid() {
+    for vector in VECTORS {
+        let (public_key, message, signature) = parse(vector);
+        assert_eq!(verify(&public_key, &message, &signature), Ok(()));
+    }
+}
+
+#[test]
+fn tampered_signatures_are_rejected() {
+    let (public_key, message, signature) = parse(&VECTORS[1]);
+
+    let other_message = MessageDigest::from_slice(&[0x24; 32]).unwrap();
+    assert_eq!(
+        verify(&public_key, &other_message, &signature),
+        Err(Bip340Error::InvalidSignature)
+    );
+
+    // s replaced with q - s
+    let mut negated = *signature.as_bytes();
+    let s = BigInt::from_bytes(&negated[32..]);
+    let q_minus_s = (Scalar::<Secp256k1>::group_order() - s).to_bytes();
+    negated[32..].copy_from_slice(&q_minus_s);
+    assert_eq!(
+        verify(&public_key, &message, &SchnorrSignature::from_bytes(negated)),
+        Err(Bip340Error::InvalidSignature)
+    );
+
+    let mut s_is_order = *signature.as_bytes();
+    s_is_order[32..].copy_from_slice(&Scalar::<Secp256k1>::group_order().to_bytes());
+    assert_eq!(
+        verify(&public_key, &message, &SchnorrSignature::from_bytes(s_is_order)),
+        Err(Bip340Error::SOutOfRange)
+    );
+
+    // r equal to field modulus
+    let mut r_is_p = *signature.as_bytes();
+    r_is_p[..32].copy_from_slice(
+        &hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F").unwrap(),
+    );
+    assert_eq!(
+        verify(&public_key, &message, &SchnorrSignature::from_bytes(r_is_p)),
+        Err(Bip340Error::InvalidSignature)
+    );
+
+    // Vector 5 from BIP340: public key is not on the curve
+    let mut not_on_curve = [0u8; 32];
+    not_on_curve.copy_from_slice(
+        &hex::decode("EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34").unwrap(),
+    );
+    assert_eq!(
+        verify(&not_on_curve, &message, &signature),
+        Err(Bip340Error::InvalidPublicKey)
+    );
+}
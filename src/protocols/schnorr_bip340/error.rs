@@ -0,0 +1,75 @@
+use round_based::{containers::StoreErr, IsCritical};
+use thiserror::Error;
+
+/// Error type of threshold Schnorr signing
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SchnorrSignError {
+    /// Fewer than `t+1` signers
+    #[error("at least {required} signers are required, got {actual}")]
+    TooFewParties { required: usize, actual: usize },
+    /// Number of signers doesn't fit into `u16`
+    #[error("too many signers: {n}")]
+    TooManyParties { n: usize },
+    /// Party index `i` is not in range `[1; n]`
+    #[error("party index is not in range [1; n]")]
+    InvalidPartyIndex,
+    /// `s_l` contains duplicates, unknown parties, or doesn't map local party to its own keygen index
+    #[error("list of signers s_l is invalid")]
+    InvalidSl,
+
+    /// Some parties committed to a nonce equal to the point at infinity
+    #[error("parties {parties:?} sent invalid nonce commitments")]
+    InvalidNonceCommitments { parties: Vec<u16> },
+    /// Some parties sent signature shares that don't match their nonces and key shares
+    #[error("parties {parties:?} sent invalid signature shares")]
+    InvalidSignatureShares { parties: Vec<u16> },
+    /// Aggregated signature doesn't verify, which shouldn't happen once every share is valid
+    #[error("aggregated signature is invalid")]
+    InvalidSignature,
+
+    /// Received message didn't pass pre-validation
+    #[error("received message didn't pass pre-validation: {0}")]
+    HandleMessage(#[source] StoreErr),
+    /// Received message which we didn't expect to receive now (e.g. message from previous round)
+    #[error(
+        "didn't expect to receive message from round {msg_round} (being at round {current_round})"
+    )]
+    ReceivedOutOfOrderMessage { current_round: u16, msg_round: u16 },
+    /// Party didn't receive all messages of the round in time
+    #[error("round {round} timed out, missing messages from parties {missing_parties:?}")]
+    Timeout {
+        round: u16,
+        missing_parties: Vec<u16>,
+    },
+    /// `pick_output` called twice
+    #[error("pick_output called twice")]
+    DoublePickOutput,
+
+    /// Some internal assertions were failed, which is a bug
+    #[doc(hidden)]
+    #[error("internal error: {0:?}")]
+    InternalError(InternalError),
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InternalError {
+    /// Messages store reported that it received all messages it wanted to receive, but refused
+    /// to return message container
+    RetrieveRoundMessages(StoreErr),
+    #[doc(hidden)]
+    StoreGone,
+}
+
+impl IsCritical for SchnorrSignError {
+    fn is_critical(&self) -> bool {
+        true
+    }
+}
+
+impl From<InternalError> for SchnorrSignError {
+    fn from(err: InternalError) -> Self {
+        Self::InternalError(err)
+    }
+}
@@ -0,0 +1,13 @@
+pub mod round_0;
+pub mod round_1;
+pub mod round_2;
+
+use super::bip340::SchnorrSignature;
+
+pub enum R {
+    Round0(round_0::Round0),
+    Round1(round_1::Round1),
+    Round2(round_2::Round2),
+    Final(SchnorrSignature),
+    Gone,
+}
@@ -0,0 +1,52 @@
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use round_based::containers::push::Push;
+use round_based::Msg;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::schnorr_bip340::messages::NonceCommitment;
+use crate::protocols::schnorr_bip340::rounds::round_1::Round1;
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
+
+pub struct Round0 {
+    /// Index of this party in range `[1; n]`, where `n` is number of signers
+    pub i: u16,
+    /// `s_l[j]` is keygen index of `j+1`-th signer
+    pub s_l: Vec<u16>,
+    pub local_key: LocalKey<Secp256k1>,
+    pub message: MessageDigest,
+    pub session_id: SessionId,
+}
+
+impl Round0 {
+    pub fn proceed<O>(self, mut output: O) -> Round1
+    where
+        O: Push<Msg<NonceCommitment>>,
+    {
+        let hiding_nonce = Scalar::<Secp256k1>::random();
+        let binding_nonce = Scalar::<Secp256k1>::random();
+        let commitment = NonceCommitment {
+            hiding: Point::generator() * &hiding_nonce,
+            binding: Point::generator() * &binding_nonce,
+        };
+        output.push(Msg {
+            sender: self.i,
+            receiver: None,
+            body: commitment.clone(),
+        });
+
+        Round1 {
+            i: self.i,
+            s_l: self.s_l,
+            local_key: self.local_key,
+            message: self.message,
+            session_id: self.session_id,
+            hiding_nonce,
+            binding_nonce,
+            commitment,
+        }
+    }
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,159 @@
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use round_based::containers::push::Push;
+use round_based::containers::{self, BroadcastMsgs, Store};
+use round_based::Msg;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::schnorr_bip340::bip340::{challenge, has_even_y, tagged_hash, x_only};
+use crate::protocols::schnorr_bip340::error::SchnorrSignError;
+use crate::protocols::schnorr_bip340::messages::{NonceCommitment, SignatureShare};
+use crate::protocols::schnorr_bip340::rounds::round_2::Round2;
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire;
+
+const BINDING_FACTOR_TAG: &str = "multi-party-ecdsa/frost-bip340/binding";
+
+pub struct Round1 {
+    pub(super) i: u16,
+    pub(super) s_l: Vec<u16>,
+    pub(super) local_key: LocalKey<Secp256k1>,
+    pub(super) message: MessageDigest,
+    pub(super) session_id: SessionId,
+
+    pub(super) hiding_nonce: Scalar<Secp256k1>,
+    pub(super) binding_nonce: Scalar<Secp256k1>,
+    pub(super) commitment: NonceCommitment,
+}
+
+impl Round1 {
+    pub fn proceed<O>(
+        self,
+        input: BroadcastMsgs<NonceCommitment>,
+        mut output: O,
+    ) -> Result<Round2, SchnorrSignError>
+    where
+        O: Push<Msg<SignatureShare>>,
+    {
+        let commitments = input.into_vec_including_me(self.commitment.clone());
+        let invalid: Vec<u16> = (1..)
+            .zip(&commitments)
+            .filter(|(_, c)| c.hiding.is_zero() || c.binding.is_zero())
+            .map(|(j, _)| j)
+            .collect();
+        if !invalid.is_empty() {
+            return Err(SchnorrSignError::InvalidNonceCommitments { parties: invalid });
+        }
+
+        // Binding factor of every signer commits to the whole set of nonces, so nonce of one
+        // signer can't be chosen depending on nonces of others
+        let transcript = [wire::to_bytes(&self.s_l), wire::to_bytes(&commitments)].concat();
+        let binding_factors: Vec<Scalar<Secp256k1>> = self
+            .s_l
+            .iter()
+            .map(|keygen_i| {
+                let rho = tagged_hash(
+                    BINDING_FACTOR_TAG,
+                    &[
+                        self.session_id.as_bytes(),
+                        self.message.as_bytes(),
+                        &transcript,
+                        &keygen_i.to_be_bytes(),
+                    ],
+                );
+                Scalar::from_bigint(&BigInt::from_bytes(&rho))
+            })
+            .collect();
+        let nonces: Vec<Point<Secp256k1>> = commitments
+            .iter()
+            .zip(&binding_factors)
+            .map(|(c, rho)| &c.hiding + &c.binding * rho)
+            .collect();
+        let r = nonces
+            .iter()
+            .fold(Point::<Secp256k1>::zero(), |acc, r_j| acc + r_j);
+        if r.is_zero() {
+            return Err(SchnorrSignError::InvalidSignature);
+        }
+
+        // BIP340 requires both R and public key to have even y. Instead of negating them, every
+        // signer negates its nonce and key share
+        let nonce_sign = sign_of(&r);
+        let key_sign = sign_of(&self.local_key.public_key);
+        let r = &r * &nonce_sign;
+        let public_key = &self.local_key.public_key * &key_sign;
+        let c = challenge(&x_only(&r), &x_only(&public_key), &self.message);
+
+        let signers: Vec<usize> = self.s_l.iter().map(|&j| usize::from(j)).collect();
+        let points = &self.local_key.party_to_point_map;
+        let lagrange: Vec<Scalar<Secp256k1>> = signers
+            .iter()
+            .map(|j| {
+                let x_j = Scalar::from_bigint(&BigInt::from(points.points[j] as u64));
+                points.calculate_lagrange_multiplier(&signers, x_j)
+            })
+            .collect();
+
+        let own = usize::from(self.i - 1);
+        let k_i =
+            (&self.hiding_nonce + &self.binding_nonce * &binding_factors[own]) * &nonce_sign;
+        let x_i = &self.local_key.keys_linear.x_i * &key_sign;
+        let z_i = k_i + &c * &lagrange[own] * x_i;
+
+        output.push(Msg {
+            sender: self.i,
+            receiver: None,
+            body: SignatureShare(z_i.clone()),
+        });
+
+        let nonces = nonces.into_iter().map(|r_j| r_j * &nonce_sign).collect();
+        let key_shares = signers
+            .iter()
+            .zip(&lagrange)
+            .map(|(&j, lambda_j)| &self.local_key.pk_vec[j - 1] * lambda_j * &key_sign)
+            .collect();
+
+        Ok(Round2 {
+            message: self.message,
+            r,
+            public_key,
+            challenge: c,
+            nonces,
+            key_shares,
+            own_share: SignatureShare(z_i),
+        })
+    }
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+    pub fn expects_messages(i: u16, n: u16) -> Store<BroadcastMsgs<NonceCommitment>> {
+        containers::BroadcastMsgsStore::new(i, n)
+    }
+}
+
+impl Zeroize for Round1 {
+    fn zeroize(&mut self) {
+        self.hiding_nonce = Scalar::zero();
+        self.binding_nonce = Scalar::zero();
+    }
+}
+
+impl Drop for Round1 {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for Round1 {}
+
+/// `1` if y coordinate of the point is even, `-1` otherwise
+fn sign_of(point: &Point<Secp256k1>) -> Scalar<Secp256k1> {
+    if has_even_y(point) {
+        Scalar::from_bigint(&BigInt::one())
+    } else {
+        -Scalar::from_bigint(&BigInt::one())
+    }
+}
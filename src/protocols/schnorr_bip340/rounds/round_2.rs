@@ -0,0 +1,56 @@
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use round_based::containers::{self, BroadcastMsgs, Store};
+
+use crate::protocols::schnorr_bip340::bip340::{self, x_only, SchnorrSignature};
+use crate::protocols::schnorr_bip340::error::SchnorrSignError;
+use crate::protocols::schnorr_bip340::messages::SignatureShare;
+use crate::utilities::message_digest::MessageDigest;
+
+pub struct Round2 {
+    pub(super) message: MessageDigest,
+    /// Aggregated nonce, with even y
+    pub(super) r: Point<Secp256k1>,
+    /// Public key, with even y
+    pub(super) public_key: Point<Secp256k1>,
+    pub(super) challenge: Scalar<Secp256k1>,
+    /// Nonce of every signer, negated along with `r`
+    pub(super) nonces: Vec<Point<Secp256k1>>,
+    /// `λ_j * X_j` of every signer, negated along with `public_key`
+    pub(super) key_shares: Vec<Point<Secp256k1>>,
+    pub(super) own_share: SignatureShare,
+}
+
+impl Round2 {
+    pub fn proceed(
+        self,
+        input: BroadcastMsgs<SignatureShare>,
+    ) -> Result<SchnorrSignature, SchnorrSignError> {
+        let shares = input.into_vec_including_me(self.own_share);
+
+        // z_j * G = R_j + c * λ_j * X_j
+        let invalid: Vec<u16> = (1..)
+            .zip(shares.iter().zip(self.nonces.iter().zip(&self.key_shares)))
+            .filter(|(_, (z_j, (r_j, key_share)))| {
+                Point::generator() * &z_j.0 != r_j + key_share * &self.challenge
+            })
+            .map(|(j, _)| j)
+            .collect();
+        if !invalid.is_empty() {
+            return Err(SchnorrSignError::InvalidSignatureShares { parties: invalid });
+        }
+
+        let s = shares
+            .iter()
+            .fold(Scalar::<Secp256k1>::zero(), |acc, z_j| acc + &z_j.0);
+        let signature = SchnorrSignature::new(&self.r, &s);
+        bip340::verify(&x_only(&self.public_key), &self.message, &signature)
+            .map_err(|_| SchnorrSignError::InvalidSignature)?;
+        Ok(signature)
+    }
+    pub fn is_expensive(&self) -> bool {
+        false
+    }
+    pub fn expects_messages(i: u16, n: u16) -> Store<BroadcastMsgs<SignatureShare>> {
+        containers::BroadcastMsgsStore::new(i, n)
+    }
+}
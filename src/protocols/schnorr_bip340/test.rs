@@ -0,0 +1,192 @@
+use curv::elliptic::curves::{Scalar, Secp256k1};
+use round_based::dev::Simulation;
+use round_based::StateMachine;
+
+use crate::protocols::gg_2020::state_machine::keygen::local_key::LocalKey;
+use crate::protocols::gg_2020::state_machine::keygen::test::simulate_keygen;
+use crate::protocols::schnorr_bip340::bip340::{has_even_y, verify, x_only};
+use crate::protocols::schnorr_bip340::error::SchnorrSignError;
+use crate::protocols::schnorr_bip340::messages::{ProtocolMessage, SignatureShare, M};
+use crate::protocols::schnorr_bip340::{SchnorrSign, SchnorrSignature};
+use crate::utilities::message_digest::MessageDigest;
+use crate::utilities::session_id::SessionId;
+
+fn simulate_signing(
+    local_keys: &[LocalKey<Secp256k1>],
+    s_l: &[u16],
+    message: MessageDigest,
+) -> Vec<SchnorrSignature> {
+    let mut simulation = Simulation::new();
+
+    let session_id = SessionId::random();
+    for (i, &keygen_i) in (1..).zip(s_l) {
+        simulation.add_party(
+            SchnorrSign::new(
+                i,
+                s_l.to_vec(),
+                local_keys[usize::from(keygen_i - 1)].clone(),
+                message,
+                session_id,
+            )
+            .unwrap(),
+        );
+    }
+
+    simulation.run().unwrap()
+}
+
+/// Checks the signature both with our verifier and with libsecp256k1
+fn assert_valid(public_key: &[u8; 32], message: &MessageDigest, signature: &SchnorrSignature) {
+    assert_eq!(verify(public_key, message, signature), Ok(()));
+
+    let public_key = secp256k1::XOnlyPublicKey::from_slice(public_key).unwrap();
+    let signature = secp256k1::schnorr::Signature::from_slice(signature.as_bytes()).unwrap();
+    let message = secp256k1::Message::from_slice(message.as_bytes()).unwrap();
+    secp256k1::SECP256K1
+        .verify_schnorr(&signature, &message, &public_key)
+        .unwrap();
+}
+
+/// Turns key shares of `x` into key shares of `-x`, flipping parity of the public key
+fn negate_keys(local_keys: &[LocalKey<Secp256k1>]) -> Vec<LocalKey<Secp256k1>> {
+    local_keys
+        .iter()
+        .map(|key| {
+            let mut key = key.clone();
+            key.keys_linear.x_i = -key.keys_linear.x_i.clone();
+            key.keys_linear.y = -key.keys_linear.y.clone();
+            key.secret_share.1 = -key.secret_share.1.clone();
+            key.public_key = -key.public_key.clone();
+            key.pk_vec = key.pk_vec.iter().map(|p| -p.clone()).collect();
+            key.vss_scheme.commitments = key
+                .vss_scheme
+                .commitments
+                .iter()
+                .map(|c| -c.clone())
+                .collect();
+            key
+        })
+        .collect()
+}
+
+#[test]
+fn signs_with_keys_of_both_parities() {
+    let local_keys = simulate_keygen(1, 3);
+    let negated_keys = negate_keys(&local_keys);
+    assert_ne!(
+        has_even_y(&local_keys[0].public_key),
+        has_even_y(&negated_keys[0].public_key)
+    );
+
+    for keys in [&local_keys, &negated_keys] {
+        let public_key = x_only(&keys[0].public_key);
+        for (j, s_l) in [[1, 3], [2, 3], [3, 1]].iter().enumerate() {
+            // Several messages, so nonces of both parities are likely to show up
+            for k in 0..4 {
+                let message = MessageDigest::sha256(format!("message {} {}", j, k).as_bytes());
+                let signatures = simulate_signing(keys, s_l, message);
+                assert!(signatures.iter().all(|s| s == &signatures[0]));
+                assert_valid(&public_key, &message, &signatures[0]);
+            }
+        }
+    }
+}
+
+#[test]
+fn signs_with_more_than_threshold_parties() {
+    let local_keys = simulate_keygen(2, 4);
+    let public_key = x_only(&local_keys[0].public_key);
+    let message = MessageDigest::sha256(b"four signers");
+    let signatures = simulate_signing(&local_keys, &[4, 2, 1, 3], message);
+    assert_valid(&public_key, &message, &signatures[0]);
+}
+
+#[test]
+fn rejects_invalid_signers() {
+    let local_keys = simulate_keygen(1, 3);
+    let message = MessageDigest::sha256(b"message");
+    let session_id = SessionId::random();
+    let new = |i, s_l: Vec<u16>, keygen_i: usize| {
+        SchnorrSign::new(i, s_l, local_keys[keygen_i - 1].clone(), message, session_id)
+    };
+
+    assert!(matches!(
+        new(1, vec![1], 1),
+        Err(SchnorrSignError::TooFewParties {
+            required: 2,
+            actual: 1
+        })
+    ));
+    assert!(matches!(
+        new(3, vec![1, 2], 1),
+        Err(SchnorrSignError::InvalidPartyIndex)
+    ));
+    assert!(matches!(
+        new(1, vec![1, 1], 1),
+        Err(SchnorrSignError::InvalidSl)
+    ));
+    assert!(matches!(
+        new(1, vec![1, 4], 1),
+        Err(SchnorrSignError::InvalidSl)
+    ));
+    assert!(matches!(
+        new(1, vec![2, 1], 1),
+        Err(SchnorrSignError::InvalidSl)
+    ));
+}
+
+#[test]
+fn blames_party_sending_invalid_share() {
+    let local_keys = simulate_keygen(1, 3);
+    let message = MessageDigest::sha256(b"message");
+    let session_id = SessionId::random();
+    let s_l = vec![1, 2, 3];
+    let mut parties: Vec<SchnorrSign> = (1..)
+        .zip(&s_l)
+        .map(|(i, &keygen_i)| {
+            let local_key = local_keys[usize::from(keygen_i - 1)].clone();
+            SchnorrSign::new(i, s_l.clone(), local_key, message, session_id).unwrap()
+        })
+        .collect();
+
+    let round1: Vec<_> = parties
+        .iter_mut()
+        .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+        .collect();
+    for party in parties.iter_mut() {
+        for msg in round1.iter().filter(|m| m.sender != party.party_ind()) {
+            party.handle_incoming(msg.clone()).unwrap();
+        }
+    }
+
+    let mut round2: Vec<_> = parties[1..]
+        .iter_mut()
+        .flat_map(|p| p.message_queue().drain(..).collect::<Vec<_>>())
+        .collect();
+    assert_eq!(round2.len(), 2);
+    if let ProtocolMessage(M::Round2(SignatureShare(z))) = &mut round2[1].body {
+        *z = &*z + Scalar::random();
+    }
+
+    parties[0].handle_incoming(round2[0].clone()).unwrap();
+    let err = parties[0].handle_incoming(round2[1].clone()).unwrap_err();
+    assert!(
+        matches!(&err, SchnorrSignError::InvalidSignatureShares { parties } if parties == &[3]),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn messages_survive_wire_round_trip() {
+    let local_keys = simulate_keygen(1, 2);
+    let message = MessageDigest::sha256(b"message");
+    let session_id = SessionId::random();
+    let mut party =
+        SchnorrSign::new(1, vec![1, 2], local_keys[0].clone(), message, session_id).unwrap();
+
+    let msg = party.message_queue().pop().unwrap();
+    let bytes = msg.body.to_bytes();
+    assert_eq!(ProtocolMessage::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+    assert!(ProtocolMessage::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
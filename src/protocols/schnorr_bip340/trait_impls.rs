@@ -0,0 +1,3 @@
+pub mod fmt_debug;
+pub mod round_blame;
+pub mod state_machine;
@@ -0,0 +1,20 @@
+use round_based::containers::MessageStore;
+
+use crate::protocols::gg_2020::state_machine::traits::RoundBlame;
+use crate::protocols::schnorr_bip340::{rounds::R, SchnorrSign};
+
+impl RoundBlame for SchnorrSign {
+    /// Returns number of unwilling parties and a vector of their party indexes.
+    fn round_blame(&self) -> (u16, Vec<u16>) {
+        let store1_blame = self.msgs1.as_ref().map(|s| s.blame()).unwrap_or_default();
+        let store2_blame = self.msgs2.as_ref().map(|s| s.blame()).unwrap_or_default();
+
+        let default = (0, vec![]);
+        match &self.round {
+            R::Round0(_) => default,
+            R::Round1(_) => store1_blame,
+            R::Round2(_) => store2_blame,
+            R::Final(_) | R::Gone => default,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::mem::replace;
+use std::time::Duration;
+
+use round_based::containers::MessageStore;
+use round_based::{Msg, StateMachine};
+
+use crate::protocols::gg_2020::state_machine::traits::RoundBlame;
+use crate::protocols::schnorr_bip340::{
+    error::SchnorrSignError,
+    messages::{ProtocolMessage, M},
+    rounds::R,
+    SchnorrSign, SchnorrSignature,
+};
+
+impl StateMachine for SchnorrSign {
+    type MessageBody = ProtocolMessage;
+    type Err = SchnorrSignError;
+    type Output = SchnorrSignature;
+
+    fn handle_incoming(&mut self, msg: Msg<Self::MessageBody>) -> Result<(), Self::Err> {
+        let current_round = self.current_round();
+
+        match msg.body {
+            ProtocolMessage(M::Round1(m)) => {
+                let store = self
+                    .msgs1
+                    .as_mut()
+                    .ok_or(SchnorrSignError::ReceivedOutOfOrderMessage {
+                        current_round,
+                        msg_round: 1,
+                    })?;
+                store
+                    .push_msg(Msg {
+                        sender: msg.sender,
+                        receiver: msg.receiver,
+                        body: m,
+                    })
+                    .map_err(SchnorrSignError::HandleMessage)?;
+            }
+            ProtocolMessage(M::Round2(m)) => {
+                let store = self
+                    .msgs2
+                    .as_mut()
+                    .ok_or(SchnorrSignError::ReceivedOutOfOrderMessage {
+                        current_round,
+                        msg_round: 2,
+                    })?;
+                store
+                    .push_msg(Msg {
+                        sender: msg.sender,
+                        receiver: msg.receiver,
+                        body: m,
+                    })
+                    .map_err(SchnorrSignError::HandleMessage)?;
+            }
+        }
+
+        self.proceed_round(false)
+    }
+
+    fn message_queue(&mut self) -> &mut Vec<Msg<Self::MessageBody>> {
+        &mut self.msgs_queue
+    }
+
+    fn wants_to_proceed(&self) -> bool {
+        let store1_wants_more = self.msgs1.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+        let store2_wants_more = self.msgs2.as_ref().map(|s| s.wants_more()).unwrap_or(false);
+
+        match &self.round {
+            R::Round0(_) => true,
+            R::Round1(_) => !store1_wants_more,
+            R::Round2(_) => !store2_wants_more,
+            R::Final(_) | R::Gone => false,
+        }
+    }
+
+    fn proceed(&mut self) -> Result<(), Self::Err> {
+        self.proceed_round(true)
+    }
+
+    fn round_timeout(&self) -> Option<Duration> {
+        self.round_timeout
+    }
+
+    fn round_timeout_reached(&mut self) -> Self::Err {
+        let (_, missing_parties) = self.round_blame();
+        SchnorrSignError::Timeout {
+            round: self.current_round(),
+            missing_parties,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(self.round, R::Final(_))
+    }
+
+    fn pick_output(&mut self) -> Option<Result<Self::Output, Self::Err>> {
+        match self.round {
+            R::Final(_) => (),
+            R::Gone => return Some(Err(SchnorrSignError::DoublePickOutput)),
+            _ => return None,
+        }
+
+        match replace(&mut self.round, R::Gone) {
+            R::Final(result) => Some(Ok(result)),
+            _ => unreachable!("guaranteed by match expression above"),
+        }
+    }
+
+    fn current_round(&self) -> u16 {
+        match &self.round {
+            R::Round0(_) => 0,
+            R::Round1(_) => 1,
+            R::Round2(_) => 2,
+            R::Final(_) | R::Gone => 3,
+        }
+    }
+
+    fn total_rounds(&self) -> Option<u16> {
+        Some(2)
+    }
+
+    fn party_ind(&self) -> u16 {
+        self.party_i
+    }
+
+    fn parties(&self) -> u16 {
+        self.party_n
+    }
+}
@@ -0,0 +1,124 @@
+//! Relay server that parties use as a broadcast channel
+//!
+//! Parties join a room identified by an arbitrary string, obtain a unique index in it, publish
+//! messages and subscribe to everything published into the room via server-sent events. Relay
+//! doesn't look into messages: confidentiality and authenticity of protocol messages must be
+//! provided on top of it (see [transport](crate::transport)).
+//!
+//! Compared to the `gg20_sm_manager` example it replaces, relay:
+//! * expires rooms after [room_ttl](RelayConfig::room_ttl) of inactivity, and keeps them (along
+//!   with their history) until then, so disconnected parties can resume,
+//! * limits number of participants per room,
+//! * keeps only the last [history_limit](RelayConfig::history_limit) messages of every room,
+//! * optionally persists rooms to an embedded store, so parties can resume after restart,
+//! * optionally requires a bearer token per room.
+//!
+//! ## Endpoints
+//! * `GET /rooms/<room_id>/subscribe` — stream of `new-message` events. Event id is an index of
+//!   the message in the room, a reconnecting client sets `Last-Event-ID` header to receive only
+//!   messages it hasn't seen. If some of them were already evicted from the history, or the
+//!   header refers to a message the room has never had (e.g. the room expired meanwhile),
+//!   stream ends with `history-truncated` event carrying the id of the oldest message the
+//!   client can still receive.
+//! * `POST /rooms/<room_id>/issue_unique_idx` — issues an index in range `[1; max_participants]`
+//! * `POST /rooms/<room_id>/broadcast` — publishes a message. Request may carry
+//!   `Idempotency-Key` header (up to 64 bytes): message is published only once per key, so the
+//!   request can be safely retried.
+//!
+//! If room has a token, every request must carry `Authorization: Bearer <token>` header.
+//!
+//! ```rust,no_run
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! use multi_party_ecdsa::relay::{self, RelayConfig};
+//!
+//! let config = RelayConfig::default().with_room_token("keygen-42", "secret token");
+//! relay::build(config)?.launch().await?;
+//! # Ok(()) }
+//! ```
+
+use std::sync::Arc;
+
+use rocket::data::{Limits, ToByteUnit};
+use rocket::fairing::AdHoc;
+use rocket::figment::Figment;
+use rocket::{Build, Rocket};
+use thiserror::Error;
+
+mod auth;
+mod config;
+mod room;
+mod routes;
+mod store;
+
+pub use config::RelayConfig;
+pub use room::{HistoryTruncated, Room, Rooms, Subscription};
+pub use store::Store;
+
+/// Builds relay server with default Rocket configuration (see `Rocket.toml` and `ROCKET_*`
+/// environment variables)
+pub fn build(config: RelayConfig) -> Result<Rocket<Build>, RelayError> {
+    build_with_figment(rocket::Config::figment(), config)
+}
+
+/// Builds relay server on top of given Rocket configuration
+pub fn build_with_figment(
+    figment: Figment,
+    config: RelayConfig,
+) -> Result<Rocket<Build>, RelayError> {
+    let store = match &config.persistence {
+        Some(path) => Some(Store::open(path)?),
+        None => None,
+    };
+    let rooms = Arc::new(Rooms::new(&config, store));
+
+    let figment = figment.merge((
+        "limits",
+        Limits::new().limit("string", config.max_message_size.bytes()),
+    ));
+    let cleanup_interval = config.cleanup_interval;
+    let rooms_to_clean = rooms.clone();
+
+    Ok(rocket::custom(figment)
+        .mount(
+            "/",
+            rocket::routes![routes::subscribe, routes::issue_idx, routes::broadcast],
+        )
+        .manage(rooms)
+        .manage(config)
+        .attach(AdHoc::on_liftoff("Expired rooms cleanup", move |rocket| {
+            let mut shutdown = rocket.shutdown();
+            Box::pin(async move {
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(cleanup_interval);
+                    loop {
+                        tokio::select! {
+                            _ = interval.tick() => (),
+                            _ = &mut shutdown => return,
+                        }
+                        match rooms_to_clean.remove_expired().await {
+                            Ok(0) => (),
+                            Ok(n) => log::debug!("removed {} expired rooms", n),
+                            Err(e) => log::error!("remove expired rooms: {}", e),
+                        }
+                    }
+                });
+            })
+        })))
+}
+
+/// Error of relay server
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RelayError {
+    #[error("open persistent store: {0}")]
+    OpenStore(#[source] sled::Error),
+    #[error("persistent store: {0}")]
+    Store(#[source] sled::Error),
+    #[error("persistent store contains malformed entry in room {room:?}")]
+    MalformedStore { room: String },
+    #[error("room is full")]
+    RoomIsFull,
+}
+
+#[cfg(test)]
+pub(crate) mod test;
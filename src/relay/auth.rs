@@ -0,0 +1,41 @@
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+
+use super::RelayConfig;
+
+/// Token from `Authorization: Bearer <token>` header
+pub struct BearerToken(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for BearerToken {
+    type Error = &'static str;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one("Authorization") {
+            Some(header) => match header.strip_prefix("Bearer ") {
+                Some(token) => Outcome::Success(BearerToken(Some(token.trim().to_owned()))),
+                None => Outcome::Failure((Status::Unauthorized, "expected bearer token")),
+            },
+            None => Outcome::Success(BearerToken(None)),
+        }
+    }
+}
+
+/// Checks that request to `room_id` is allowed
+pub fn authorize(config: &RelayConfig, room_id: &str, token: &BearerToken) -> Result<(), Status> {
+    match (config.room_token_hash(room_id), &token.0) {
+        (Some(expected), Some(token)) => {
+            let actual: [u8; 32] = Sha256::digest(token.as_bytes()).into();
+            if bool::from(actual[..].ct_eq(&expected[..])) {
+                Ok(())
+            } else {
+                Err(Status::Unauthorized)
+            }
+        }
+        (Some(_), None) => Err(Status::Unauthorized),
+        (None, _) if config.require_token => Err(Status::Unauthorized),
+        (None, _) => Ok(()),
+    }
+}
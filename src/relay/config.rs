@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+/// Configuration of relay server
+#[derive(Clone, Debug)]
+pub struct RelayConfig {
+    /// Room is removed once nobody is subscribed to it and there was no activity in the room for
+    /// this long
+    pub room_ttl: Duration,
+    /// How often expired rooms are looked for
+    pub cleanup_interval: Duration,
+    /// Max number of indexes issued in a room, and max number of simultaneous subscriptions to it
+    pub max_participants: u16,
+    /// Max number of messages kept in a room. Once limit is reached, the oldest message is
+    /// evicted on every new one.
+    pub history_limit: usize,
+    /// Max size of a single message in bytes
+    pub max_message_size: u64,
+    /// Path to embedded store. If set, rooms survive server restart.
+    pub persistence: Option<PathBuf>,
+    /// Whether a room without a token can be used
+    pub require_token: bool,
+    /// SHA256 hashes of room tokens
+    room_tokens: HashMap<String, [u8; 32]>,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            room_ttl: Duration::from_secs(60 * 60),
+            cleanup_interval: Duration::from_secs(60),
+            max_participants: 64,
+            history_limit: 100_000,
+            max_message_size: 100 * 1024 * 1024,
+            persistence: None,
+            require_token: false,
+            room_tokens: HashMap::new(),
+        }
+    }
+}
+
+impl RelayConfig {
+    /// Requires every request to `room_id` to carry bearer `token`
+    pub fn with_room_token(mut self, room_id: impl Into<String>, token: impl AsRef<[u8]>) -> Self {
+        self.room_tokens
+            .insert(room_id.into(), Sha256::digest(token.as_ref()).into());
+        self
+    }
+
+    pub(super) fn room_token_hash(&self, room_id: &str) -> Option<&[u8; 32]> {
+        self.room_tokens.get(room_id)
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Notify, RwLock};
+
+use super::store::Store;
+use super::{RelayConfig, RelayError};
+
+/// All the rooms of relay server
+pub struct Rooms {
+    rooms: RwLock<HashMap<String, Arc<Room>>>,
+    limits: RoomLimits,
+    store: Option<Store>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RoomLimits {
+    ttl: Duration,
+    max_participants: u16,
+    history_limit: usize,
+}
+
+pub struct Room {
+    id: String,
+    history: RwLock<History>,
+    message_appeared: Notify,
+    subscribers: AtomicU16,
+    next_idx: AtomicU16,
+    last_activity: Mutex<Instant>,
+    limits: RoomLimits,
+    store: Option<Store>,
+}
+
+struct History {
+    /// Id of the first message in `messages`
+    first_id: u64,
+    messages: VecDeque<String>,
+    /// Idempotency keys of `messages`
+    keys: VecDeque<Option<String>>,
+    /// Maps idempotency key to id of the message published with it
+    published: HashMap<String, u64>,
+}
+
+impl Rooms {
+    pub fn new(config: &RelayConfig, store: Option<Store>) -> Self {
+        Self {
+            rooms: RwLock::new(HashMap::new()),
+            limits: RoomLimits {
+                ttl: config.room_ttl,
+                max_participants: config.max_participants,
+                history_limit: config.history_limit.max(1),
+            },
+            store,
+        }
+    }
+
+    /// Returns the room, creating (or restoring from the store) it if it doesn't exist
+    ///
+    /// Expired room is replaced with an empty one. Room is kept along with its history while
+    /// parties are reconnecting, even if all of them are disconnected for a moment.
+    pub async fn get_room_or_create_empty(&self, room_id: &str) -> Result<Arc<Room>, RelayError> {
+        let rooms = self.rooms.read().await;
+        if let Some(room) = rooms.get(room_id) {
+            if !room.is_expired(Instant::now()) {
+                return Ok(room.clone());
+            }
+        }
+        drop(rooms);
+
+        let mut rooms = self.rooms.write().await;
+        match rooms.entry(room_id.to_owned()) {
+            Entry::Occupied(entry) if !entry.get().is_expired(Instant::now()) => {
+                Ok(entry.get().clone())
+            }
+            Entry::Occupied(entry) => {
+                if let Some(store) = &self.store {
+                    store.drop_room(room_id)?;
+                }
+                let room = Arc::new(Room::empty(room_id, self.limits, self.store.clone()));
+                *entry.into_mut() = room.clone();
+                Ok(room)
+            }
+            Entry::Vacant(entry) => {
+                let room = match &self.store {
+                    Some(store) => Room::restore(room_id, self.limits, store.clone())?,
+                    None => Room::empty(room_id, self.limits, None),
+                };
+                Ok(entry.insert(Arc::new(room)).clone())
+            }
+        }
+    }
+
+    /// Removes expired rooms, returns number of removed rooms
+    pub async fn remove_expired(&self) -> Result<usize, RelayError> {
+        let now = Instant::now();
+        let mut rooms = self.rooms.write().await;
+        let expired: Vec<String> = rooms
+            .iter()
+            .filter(|(_, room)| room.is_expired(now))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &expired {
+            rooms.remove(id);
+            if let Some(store) = &self.store {
+                store.drop_room(id)?;
+            }
+        }
+        Ok(expired.len())
+    }
+
+    /// Number of rooms kept in memory
+    pub async fn len(&self) -> usize {
+        self.rooms.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+impl Room {
+    fn empty(id: &str, limits: RoomLimits, store: Option<Store>) -> Self {
+        Self {
+            id: id.to_owned(),
+            history: RwLock::new(History {
+                first_id: 0,
+                messages: VecDeque::new(),
+                keys: VecDeque::new(),
+                published: HashMap::new(),
+            }),
+            message_appeared: Notify::new(),
+            subscribers: AtomicU16::new(0),
+            next_idx: AtomicU16::new(1),
+            last_activity: Mutex::new(Instant::now()),
+            limits,
+            store,
+        }
+    }
+
+    fn restore(id: &str, limits: RoomLimits, store: Store) -> Result<Self, RelayError> {
+        let stored = match store.load(id)? {
+            Some(stored) => stored,
+            None => return Ok(Self::empty(id, limits, Some(store))),
+        };
+        let room = Self::empty(id, limits, Some(store));
+        room.next_idx.store(stored.next_idx, Ordering::SeqCst);
+        let mut history = room.history.try_write().expect("room isn't shared yet");
+        history.first_id = stored.first_id;
+        history.messages.extend(stored.messages);
+        let first_id = stored.first_id;
+        for (id, key) in (first_id..).zip(&stored.keys) {
+            if let Some(key) = key {
+                history.published.insert(key.clone(), id);
+            }
+        }
+        history.keys.extend(stored.keys);
+        drop(history);
+        Ok(room)
+    }
+
+    /// Publishes a message, returns its id
+    ///
+    /// Message with `idempotency_key` is published once: if the room history already has a
+    /// message with the same key (e.g. client retries a request whose response was lost), id of
+    /// that message is returned instead.
+    pub async fn publish(
+        &self,
+        message: String,
+        idempotency_key: Option<String>,
+    ) -> Result<u64, RelayError> {
+        self.touch();
+        let mut history = self.history.write().await;
+        if let Some(&id) = idempotency_key
+            .as_ref()
+            .and_then(|key| history.published.get(key))
+        {
+            return Ok(id);
+        }
+        let id = history.first_id + history.messages.len() as u64;
+        if let Some(store) = &self.store {
+            store.append(&self.id, id, &message, idempotency_key.as_deref())?;
+        }
+        history.messages.push_back(message);
+        if let Some(key) = &idempotency_key {
+            history.published.insert(key.clone(), id);
+        }
+        history.keys.push_back(idempotency_key);
+        while history.messages.len() > self.limits.history_limit {
+            history.messages.pop_front();
+            if let Some(Some(key)) = history.keys.pop_front() {
+                history.published.remove(&key);
+            }
+            if let Some(store) = &self.store {
+                store.remove(&self.id, history.first_id)?;
+            }
+            history.first_id += 1;
+        }
+        self.message_appeared.notify_waiters();
+        Ok(id)
+    }
+
+    /// Subscribes to messages published after `last_seen_msg`, or to all messages if it's `None`
+    pub fn subscribe(self: Arc<Self>, last_seen_msg: Option<u64>) -> Result<Subscription, RelayError> {
+        self.touch();
+        let max = self.limits.max_participants;
+        self.subscribers
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                if n < max {
+                    Some(n + 1)
+                } else {
+                    None
+                }
+            })
+            .map_err(|_| RelayError::RoomIsFull)?;
+        Ok(Subscription {
+            room: self,
+            next_event: last_seen_msg.map(|i| i + 1).unwrap_or(0),
+        })
+    }
+
+    /// Issues a unique index in range `[1; max_participants]`
+    pub fn issue_unique_idx(&self) -> Result<u16, RelayError> {
+        self.touch();
+        let max = self.limits.max_participants;
+        let idx = self
+            .next_idx
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |i| {
+                if i <= max {
+                    i.checked_add(1)
+                } else {
+                    None
+                }
+            })
+            .map_err(|_| RelayError::RoomIsFull)?;
+        if let Some(store) = &self.store {
+            store.set_next_idx(&self.id, idx.saturating_add(1))?;
+        }
+        Ok(idx)
+    }
+
+    /// Room is expired when nobody is subscribed to it, and there was no activity for
+    /// [room_ttl](RelayConfig::room_ttl)
+    pub fn is_expired(&self, now: Instant) -> bool {
+        let last_activity = *self.last_activity.lock().unwrap_or_else(|e| e.into_inner());
+        self.subscribers.load(Ordering::SeqCst) == 0
+            && now.saturating_duration_since(last_activity) > self.limits.ttl
+    }
+
+    fn touch(&self) {
+        *self.last_activity.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+}
+
+/// Subscription to messages of the room
+pub struct Subscription {
+    room: Arc<Room>,
+    next_event: u64,
+}
+
+/// Subscriber can't receive the next message, as it isn't in the room history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryTruncated {
+    /// Message was evicted from the history, the oldest available one has id `first_available`
+    Evicted { first_available: u64 },
+    /// Subscriber has seen messages the room doesn't have (e.g. the room expired, or the relay
+    /// was restarted without persistence). Next published message will have id `head`.
+    BeyondHead { head: u64 },
+}
+
+impl HistoryTruncated {
+    /// Id of the oldest message the subscriber can still receive
+    pub fn first_available(&self) -> u64 {
+        match *self {
+            HistoryTruncated::Evicted { first_available } => first_available,
+            HistoryTruncated::BeyondHead { head } => head,
+        }
+    }
+}
+
+impl Subscription {
+    /// Waits for the next message, returns it with its id
+    ///
+    /// Returns error if the next message was evicted from history, or if the subscriber asked
+    /// for messages following the ones the room has never had.
+    pub async fn next(&mut self) -> Result<(u64, String), HistoryTruncated> {
+        loop {
+            let history = self.room.history.read().await;
+            if self.next_event < history.first_id {
+                return Err(HistoryTruncated::Evicted {
+                    first_available: history.first_id,
+                });
+            }
+            let head = history.first_id + history.messages.len() as u64;
+            if self.next_event > head {
+                return Err(HistoryTruncated::BeyondHead { head });
+            }
+            let offset = self.next_event - history.first_id;
+            if let Some(msg) = history.messages.get(offset as usize) {
+                let event_id = self.next_event;
+                self.next_event = event_id + 1;
+                return Ok((event_id, msg.clone()));
+            }
+            let notification = self.room.message_appeared.notified();
+            drop(history);
+            notification.await;
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.room.touch();
+        self.room.subscribers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use futures::Stream;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::stream::{stream, Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::State;
+use serde::{Deserialize, Serialize};
+
+use super::auth::{authorize, BearerToken};
+use super::{RelayConfig, RelayError, Rooms};
+
+#[rocket::get("/rooms/<room_id>/subscribe")]
+pub async fn subscribe(
+    rooms: &State<Arc<Rooms>>,
+    config: &State<RelayConfig>,
+    token: BearerToken,
+    mut shutdown: rocket::Shutdown,
+    last_seen_msg: LastEventId,
+    room_id: &str,
+) -> Result<EventStream<impl Stream<Item = Event>>, Status> {
+    authorize(config, room_id, &token)?;
+    let room = rooms
+        .get_room_or_create_empty(room_id)
+        .await
+        .map_err(into_status)?;
+    let mut subscription = room.subscribe(last_seen_msg.0).map_err(into_status)?;
+    Ok(EventStream::from(stream! {
+        loop {
+            let next = tokio::select! {
+                message = subscription.next() => message,
+                _ = &mut shutdown => return,
+            };
+            match next {
+                Ok((id, msg)) => {
+                    yield Event::data(msg)
+                        .event("new-message")
+                        .id(id.to_string())
+                }
+                Err(truncated) => {
+                    yield Event::data(truncated.first_available().to_string())
+                        .event("history-truncated");
+                    return;
+                }
+            }
+        }
+    }))
+}
+
+#[rocket::post("/rooms/<room_id>/issue_unique_idx")]
+pub async fn issue_idx(
+    rooms: &State<Arc<Rooms>>,
+    config: &State<RelayConfig>,
+    token: BearerToken,
+    room_id: &str,
+) -> Result<Json<IssuedUniqueIdx>, Status> {
+    authorize(config, room_id, &token)?;
+    let room = rooms
+        .get_room_or_create_empty(room_id)
+        .await
+        .map_err(into_status)?;
+    let idx = room.issue_unique_idx().map_err(into_status)?;
+    Ok(Json::from(IssuedUniqueIdx { unique_idx: idx }))
+}
+
+#[rocket::post("/rooms/<room_id>/broadcast", data = "<message>")]
+pub async fn broadcast(
+    rooms: &State<Arc<Rooms>>,
+    config: &State<RelayConfig>,
+    token: BearerToken,
+    idempotency_key: IdempotencyKey,
+    room_id: &str,
+    message: String,
+) -> Status {
+    if let Err(status) = authorize(config, room_id, &token) {
+        return status;
+    }
+    let room = match rooms.get_room_or_create_empty(room_id).await {
+        Ok(room) => room,
+        Err(e) => return into_status(e),
+    };
+    match room.publish(message, idempotency_key.0).await {
+        Ok(_) => Status::Ok,
+        Err(e) => into_status(e),
+    }
+}
+
+fn into_status(err: RelayError) -> Status {
+    match err {
+        RelayError::RoomIsFull => Status::Forbidden,
+        err => {
+            log::error!("relay: {}", err);
+            Status::InternalServerError
+        }
+    }
+}
+
+/// Represents a header Last-Event-ID
+pub struct LastEventId(Option<u64>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LastEventId {
+    type Error = &'static str;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let header = request
+            .headers()
+            .get_one("Last-Event-ID")
+            .map(|id| id.parse::<u64>());
+        match header {
+            Some(Ok(last_seen_msg)) => Outcome::Success(LastEventId(Some(last_seen_msg))),
+            Some(Err(_parse_err)) => {
+                Outcome::Failure((Status::BadRequest, "last seen msg id is not valid"))
+            }
+            None => Outcome::Success(LastEventId(None)),
+        }
+    }
+}
+
+/// Max length of Idempotency-Key header
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 64;
+
+/// Represents a header Idempotency-Key
+pub struct IdempotencyKey(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IdempotencyKey {
+    type Error = &'static str;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one("Idempotency-Key") {
+            Some(key) if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN => {
+                Outcome::Failure((Status::BadRequest, "idempotency key is not valid"))
+            }
+            key => Outcome::Success(IdempotencyKey(key.map(|key| key.to_owned()))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IssuedUniqueIdx {
+    pub unique_idx: u16,
+}
@@ -0,0 +1,148 @@
+use std::convert::TryInto;
+use std::path::Path;
+
+use super::RelayError;
+
+const TREE_PREFIX: &[u8] = b"room/";
+const MESSAGE_PREFIX: u8 = b'm';
+const IDEMPOTENCY_KEY_PREFIX: u8 = b'k';
+const NEXT_IDX_KEY: &[u8] = b"next_idx";
+
+/// Embedded store persisting rooms between restarts of relay server
+///
+/// Every room is kept in a separate tree containing its messages and their idempotency keys
+/// (keyed by event id), and the next index to be issued.
+#[derive(Clone)]
+pub struct Store {
+    db: sled::Db,
+}
+
+/// Room restored from the store
+pub struct StoredRoom {
+    /// Id of the first message in `messages`
+    pub first_id: u64,
+    pub messages: Vec<String>,
+    /// Idempotency keys of `messages`
+    pub keys: Vec<Option<String>>,
+    pub next_idx: u16,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self, RelayError> {
+        let db = sled::open(path).map_err(RelayError::OpenStore)?;
+        Ok(Self { db })
+    }
+
+    pub fn append(
+        &self,
+        room_id: &str,
+        id: u64,
+        message: &str,
+        idempotency_key: Option<&str>,
+    ) -> Result<(), RelayError> {
+        let mut batch = sled::Batch::default();
+        batch.insert(&message_key(MESSAGE_PREFIX, id)[..], message.as_bytes());
+        if let Some(key) = idempotency_key {
+            batch.insert(&message_key(IDEMPOTENCY_KEY_PREFIX, id)[..], key.as_bytes());
+        }
+        self.tree(room_id)?
+            .apply_batch(batch)
+            .map_err(RelayError::Store)?;
+        Ok(())
+    }
+
+    pub fn remove(&self, room_id: &str, id: u64) -> Result<(), RelayError> {
+        let mut batch = sled::Batch::default();
+        batch.remove(&message_key(MESSAGE_PREFIX, id)[..]);
+        batch.remove(&message_key(IDEMPOTENCY_KEY_PREFIX, id)[..]);
+        self.tree(room_id)?
+            .apply_batch(batch)
+            .map_err(RelayError::Store)?;
+        Ok(())
+    }
+
+    pub fn set_next_idx(&self, room_id: &str, next_idx: u16) -> Result<(), RelayError> {
+        self.tree(room_id)?
+            .insert(NEXT_IDX_KEY, &next_idx.to_be_bytes()[..])
+            .map_err(RelayError::Store)?;
+        Ok(())
+    }
+
+    /// Loads the room, returns `None` if it's not in the store
+    pub fn load(&self, room_id: &str) -> Result<Option<StoredRoom>, RelayError> {
+        let name = tree_name(room_id);
+        let exists = self
+            .db
+            .tree_names()
+            .iter()
+            .any(|existing| existing.as_ref() == name.as_slice());
+        if !exists {
+            return Ok(None);
+        }
+
+        let malformed = || RelayError::MalformedStore {
+            room: room_id.to_owned(),
+        };
+        let tree = self.tree(room_id)?;
+        let next_idx = match tree.get(NEXT_IDX_KEY).map_err(RelayError::Store)? {
+            Some(bytes) => u16::from_be_bytes(bytes.as_ref().try_into().map_err(|_| malformed())?),
+            None => 1,
+        };
+
+        let mut first_id = None;
+        let mut messages = vec![];
+        for entry in tree.scan_prefix([MESSAGE_PREFIX]) {
+            let (key, value) = entry.map_err(RelayError::Store)?;
+            let id = u64::from_be_bytes(key[1..].try_into().map_err(|_| malformed())?);
+            let expected_id = first_id.unwrap_or(id) + messages.len() as u64;
+            if id != expected_id {
+                return Err(malformed());
+            }
+            first_id.get_or_insert(id);
+            messages.push(String::from_utf8(value.to_vec()).map_err(|_| malformed())?);
+        }
+        let first_id = first_id.unwrap_or(0);
+
+        let mut keys = vec![None; messages.len()];
+        for entry in tree.scan_prefix([IDEMPOTENCY_KEY_PREFIX]) {
+            let (key, value) = entry.map_err(RelayError::Store)?;
+            let id = u64::from_be_bytes(key[1..].try_into().map_err(|_| malformed())?);
+            let slot = id
+                .checked_sub(first_id)
+                .and_then(|offset| keys.get_mut(offset as usize))
+                .ok_or_else(malformed)?;
+            *slot = Some(String::from_utf8(value.to_vec()).map_err(|_| malformed())?);
+        }
+
+        Ok(Some(StoredRoom {
+            first_id,
+            messages,
+            keys,
+            next_idx,
+        }))
+    }
+
+    pub fn drop_room(&self, room_id: &str) -> Result<(), RelayError> {
+        self.db
+            .drop_tree(tree_name(room_id))
+            .map_err(RelayError::Store)?;
+        Ok(())
+    }
+
+    fn tree(&self, room_id: &str) -> Result<sled::Tree, RelayError> {
+        self.db
+            .open_tree(tree_name(room_id))
+            .map_err(RelayError::Store)
+    }
+}
+
+fn tree_name(room_id: &str) -> Vec<u8> {
+    [TREE_PREFIX, room_id.as_bytes()].concat()
+}
+
+fn message_key(prefix: u8, id: u64) -> [u8; 9] {
+    let mut key = [0u8; 9];
+    key[0] = prefix;
+    key[1..].copy_from_slice(&id.to_be_bytes());
+    key
+}
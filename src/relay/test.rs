@@ -0,0 +1,359 @@
+#[cfg(feature = "transport-sse")]
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "transport-sse")]
+use {
+    anyhow::Result,
+    futures::{SinkExt, StreamExt, TryStreamExt},
+    round_based::async_runtime::AsyncProtocol,
+    round_based::Msg,
+};
+
+#[cfg(feature = "transport-sse")]
+use crate::protocols::gg_2020::{
+    party_i::verify,
+    state_machine::keygen::Keygen,
+    state_machine::sign::stages::{offline_stage::OfflineStage, sign_manual::SignManual},
+};
+#[cfg(feature = "transport-sse")]
+use crate::relay;
+use crate::relay::{HistoryTruncated, RelayConfig, RelayError, Rooms, Store};
+#[cfg(feature = "transport-sse")]
+use crate::transport::sse::{join_computation, ClientConfig, PartyIndex, RelayClient, SseError};
+#[cfg(feature = "transport-sse")]
+use crate::utilities::{message_digest::MessageDigest, session_id::SessionId};
+
+fn temp_store_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("relay-store-{}", uuid::Uuid::new_v4()))
+}
+
+#[tokio::test]
+async fn event_ids_keep_growing_past_history_limit() {
+    let config = RelayConfig {
+        history_limit: 3,
+        ..Default::default()
+    };
+    let rooms = Rooms::new(&config, None);
+    let room = rooms.get_room_or_create_empty("room").await.unwrap();
+    let mut late = room.clone().subscribe(Some(0)).unwrap();
+    for i in 0..(u64::from(u16::MAX) + 10) {
+        assert_eq!(room.publish(i.to_string(), None).await.unwrap(), i);
+    }
+
+    // subscriber that hasn't read messages in time is told which ones it has lost
+    assert_eq!(
+        late.next().await,
+        Err(HistoryTruncated::Evicted {
+            first_available: u64::from(u16::MAX) + 7
+        })
+    );
+
+    let mut subscription = room.subscribe(Some(u64::from(u16::MAX) + 7)).unwrap();
+    let (id, msg) = subscription.next().await.unwrap();
+    assert_eq!(id, u64::from(u16::MAX) + 8);
+    assert_eq!(msg, (u64::from(u16::MAX) + 8).to_string());
+}
+
+#[tokio::test]
+async fn message_is_published_once_per_idempotency_key() {
+    let config = RelayConfig {
+        history_limit: 2,
+        ..Default::default()
+    };
+    let rooms = Rooms::new(&config, None);
+    let room = rooms.get_room_or_create_empty("room").await.unwrap();
+
+    let key = || Some("key".to_string());
+    assert_eq!(room.publish("a".into(), key()).await.unwrap(), 0);
+    assert_eq!(room.publish("a".into(), key()).await.unwrap(), 0);
+    assert_eq!(room.publish("b".into(), None).await.unwrap(), 1);
+    assert_eq!(room.publish("b".into(), None).await.unwrap(), 2);
+
+    // Key is forgotten once its message is evicted from history
+    assert_eq!(room.publish("a".into(), key()).await.unwrap(), 3);
+    let mut subscription = room.subscribe(Some(1)).unwrap();
+    assert_eq!(subscription.next().await, Ok((2, "b".to_string())));
+    assert_eq!(subscription.next().await, Ok((3, "a".to_string())));
+}
+
+#[tokio::test]
+async fn room_limits_participants() {
+    let config = RelayConfig {
+        max_participants: 2,
+        ..Default::default()
+    };
+    let rooms = Rooms::new(&config, None);
+    let room = rooms.get_room_or_create_empty("room").await.unwrap();
+
+    assert_eq!(room.issue_unique_idx().unwrap(), 1);
+    assert_eq!(room.issue_unique_idx().unwrap(), 2);
+    assert!(matches!(
+        room.issue_unique_idx(),
+        Err(RelayError::RoomIsFull)
+    ));
+
+    let _s1 = room.clone().subscribe(None).unwrap();
+    let s2 = room.clone().subscribe(None).unwrap();
+    assert!(matches!(
+        room.clone().subscribe(None),
+        Err(RelayError::RoomIsFull)
+    ));
+    drop(s2);
+    assert!(room.subscribe(None).is_ok());
+}
+
+#[tokio::test]
+async fn expired_rooms_are_removed() {
+    let config = RelayConfig {
+        room_ttl: Duration::from_millis(50),
+        ..Default::default()
+    };
+    let rooms = Rooms::new(&config, None);
+    let active = rooms.get_room_or_create_empty("active").await.unwrap();
+    let _subscription = active.subscribe(None).unwrap();
+    let idle = rooms.get_room_or_create_empty("idle").await.unwrap();
+    idle.publish("hello".into(), None).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(rooms.remove_expired().await.unwrap(), 1);
+    assert_eq!(rooms.len().await, 1);
+
+    let idle = rooms.get_room_or_create_empty("idle").await.unwrap();
+    let mut subscription = idle.subscribe(None).unwrap();
+    idle.publish("new".into(), None).await.unwrap();
+    assert_eq!(subscription.next().await, Ok((0, "new".to_string())));
+}
+
+#[tokio::test]
+async fn room_keeps_history_while_parties_reconnect() {
+    let rooms = Rooms::new(&RelayConfig::default(), None);
+    let room = rooms.get_room_or_create_empty("room").await.unwrap();
+    let subscription = room.clone().subscribe(None).unwrap();
+    assert_eq!(room.issue_unique_idx().unwrap(), 1);
+    room.publish("a".into(), None).await.unwrap();
+    room.publish("b".into(), None).await.unwrap();
+
+    // Everyone is disconnected for a moment
+    drop(subscription);
+    let reconnected = rooms.get_room_or_create_empty("room").await.unwrap();
+    assert!(Arc::ptr_eq(&room, &reconnected));
+    assert_eq!(reconnected.issue_unique_idx().unwrap(), 2);
+    let mut subscription = reconnected.subscribe(Some(0)).unwrap();
+    assert_eq!(subscription.next().await, Ok((1, "b".to_string())));
+}
+
+#[tokio::test]
+async fn subscription_beyond_head_is_rejected() {
+    let rooms = Rooms::new(&RelayConfig::default(), None);
+    let room = rooms.get_room_or_create_empty("room").await.unwrap();
+    room.publish("a".into(), None).await.unwrap();
+    room.publish("b".into(), None).await.unwrap();
+
+    // Subscriber which has seen everything waits for the next message
+    let mut up_to_date = room.clone().subscribe(Some(1)).unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(50), up_to_date.next())
+            .await
+            .is_err()
+    );
+
+    // Subscriber which has seen messages the room doesn't have is told so instead of waiting
+    let mut ahead = room.subscribe(Some(5)).unwrap();
+    assert_eq!(
+        ahead.next().await,
+        Err(HistoryTruncated::BeyondHead { head: 2 })
+    );
+}
+
+#[tokio::test]
+async fn rooms_survive_restart_with_persistence() {
+    let path = temp_store_path();
+    let config = RelayConfig {
+        history_limit: 2,
+        persistence: Some(path.clone()),
+        ..Default::default()
+    };
+
+    {
+        let rooms = Rooms::new(&config, Some(Store::open(&path).unwrap()));
+        let room = rooms.get_room_or_create_empty("room").await.unwrap();
+        assert_eq!(room.issue_unique_idx().unwrap(), 1);
+        for msg in ["a", "b", "c"].iter() {
+            room.publish(msg.to_string(), Some(msg.to_string()))
+                .await
+                .unwrap();
+        }
+    }
+
+    let rooms = Rooms::new(&config, Some(Store::open(&path).unwrap()));
+    let room = rooms.get_room_or_create_empty("room").await.unwrap();
+    assert_eq!(room.issue_unique_idx().unwrap(), 2);
+    let mut subscription = room.clone().subscribe(Some(1)).unwrap();
+    assert_eq!(subscription.next().await, Ok((2, "c".to_string())));
+    // Idempotency keys are restored along with the messages
+    assert_eq!(room.publish("c".into(), Some("c".into())).await.unwrap(), 2);
+    assert_eq!(room.publish("d".into(), None).await.unwrap(), 3);
+
+    drop(subscription);
+    drop(rooms);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[cfg(feature = "transport-sse")]
+pub(crate) fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+#[cfg(feature = "transport-sse")]
+pub(crate) async fn spawn_relay(config: RelayConfig) -> surf::Url {
+    spawn_relay_on_port(free_port(), config).await
+}
+
+#[cfg(feature = "transport-sse")]
+pub(crate) async fn spawn_relay_on_port(port: u16, config: RelayConfig) -> surf::Url {
+    let figment = rocket::Config::figment()
+        .merge(("address", "127.0.0.1"))
+        .merge(("port", port))
+        .merge(("log_level", "off"));
+    let server = relay::build_with_figment(figment, config).unwrap();
+    tokio::spawn(server.launch());
+
+    while tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    format!("http://127.0.0.1:{}/", port).parse().unwrap()
+}
+
+#[cfg(feature = "transport-sse")]
+fn client_config(token: Option<&str>) -> ClientConfig {
+    ClientConfig {
+        token: token.map(|t| t.to_owned()),
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "transport-sse")]
+#[tokio::test]
+async fn rejects_requests_without_room_token() {
+    let address = spawn_relay(RelayConfig::default().with_room_token("room", "token")).await;
+    let client = |token| RelayClient::new(&address, "room", client_config(token)).unwrap();
+
+    assert!(matches!(
+        client(None).issue_index().await,
+        Err(SseError::Rejected { status: 401 })
+    ));
+    assert!(matches!(
+        client(Some("wrong")).broadcast("hello").await,
+        Err(SseError::Rejected { status: 401 })
+    ));
+    assert_eq!(client(Some("token")).issue_index().await.unwrap(), 1);
+}
+
+#[cfg(feature = "transport-sse")]
+#[tokio::test(flavor = "multi_thread")]
+async fn keygen_and_signing_over_relay() {
+    let (t, n) = (1, 3);
+    let address = spawn_relay(
+        RelayConfig {
+            require_token: true,
+            ..Default::default()
+        }
+        .with_room_token("keygen", "keygen token")
+        .with_room_token("offline", "offline token")
+        .with_room_token("online", "online token"),
+    )
+    .await;
+
+    let keygen_session = SessionId::random();
+    let keygen_parties = (0..n).map(|_| {
+        let address = address.clone();
+        tokio::spawn(async move {
+            let (i, incoming, outgoing) = join_computation(
+                &address,
+                "keygen",
+                PartyIndex::IssuedByRelay,
+                client_config(Some("keygen token")),
+            )
+            .await?;
+            let incoming = incoming.fuse();
+            tokio::pin!(incoming);
+            tokio::pin!(outgoing);
+            let keygen = Keygen::new(i, t, n, keygen_session)?;
+            AsyncProtocol::new(keygen, incoming, outgoing)
+                .run()
+                .await
+                .map_err(|e| anyhow::anyhow!("keygen: {}", e))
+        })
+    });
+    let mut local_keys = futures::future::try_join_all(keygen_parties)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    local_keys.sort_by_key(|key| key.own_party_index);
+    let public_key = local_keys[0].public_key.clone();
+
+    let message = MessageDigest::sha256(b"relay");
+    let s_l = vec![1u16, 3];
+    let signing_session = SessionId::random();
+    let signing_parties = s_l.iter().map(|_| {
+        let address = address.clone();
+        let local_keys = local_keys.clone();
+        let s_l = s_l.clone();
+        tokio::spawn(async move {
+            let (i, incoming, outgoing) = join_computation(
+                &address,
+                "offline",
+                PartyIndex::IssuedByRelay,
+                client_config(Some("offline token")),
+            )
+            .await?;
+            let incoming = incoming.fuse();
+            tokio::pin!(incoming);
+            tokio::pin!(outgoing);
+            // index issued by relay defines which keygen party we are, just like in
+            // `gg20_signing` example
+            let local_key = local_keys[usize::from(s_l[usize::from(i - 1)] - 1)].clone();
+            let offline = OfflineStage::new(i, s_l.clone(), local_key, signing_session)?;
+            let completed = AsyncProtocol::new(offline, incoming, outgoing)
+                .run()
+                .await
+                .map_err(|e| anyhow::anyhow!("offline stage: {}", e))?;
+
+            let (i, incoming, outgoing) = join_computation(
+                &address,
+                "online",
+                PartyIndex::IssuedByRelay,
+                client_config(Some("online token")),
+            )
+            .await?;
+            tokio::pin!(incoming);
+            tokio::pin!(outgoing);
+            let (signing, partial_signature) = SignManual::new(message, completed)?;
+            outgoing
+                .send(Msg {
+                    sender: i,
+                    receiver: None,
+                    body: partial_signature,
+                })
+                .await?;
+            let partial_signatures: Vec<_> = incoming
+                .take(s_l.len() - 1)
+                .map_ok(|msg| msg.body)
+                .try_collect()
+                .await?;
+            Ok::<_, anyhow::Error>(signing.complete(&partial_signatures)?)
+        })
+    });
+    let signatures = futures::future::try_join_all(signing_parties).await.unwrap();
+    for signature in signatures {
+        verify(&signature.unwrap(), &public_key, &message).unwrap();
+    }
+}
@@ -0,0 +1,384 @@
+//! Encrypted-at-rest storage of key shares and presignatures
+//!
+//! [LocalKey] is sealed with AES-256-GCM under a key derived from a passphrase with scrypt.
+//! Public part of the key (public key, party index and key parameters) is kept in plaintext
+//! and authenticated as associated data, so it can be inspected without the passphrase but
+//! can't be altered without breaking decryption.
+//!
+//! [CompletedOfflineStage] is sealed the same way into [SealedPresignature], with the shared
+//! public key as its public part.
+
+use std::convert::TryFrom;
+
+use aes_gcm::aead::{Aead, NewAead, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    error::local_key_error::LocalKeyFileError, local_key::LocalKey,
+};
+use crate::protocols::gg_2020::state_machine::sign::CompletedOfflineStage;
+
+pub const SEALED_KEY_VERSION: u32 = 1;
+pub const SEALED_PRESIGNATURE_VERSION: u32 = 1;
+const KDF_SCRYPT: &str = "scrypt";
+const CIPHER_AES_256_GCM: &str = "aes-256-gcm";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Upper bounds on scrypt parameters accepted from a sealed key
+///
+/// Parameters are read from the file before it's authenticated, so they're capped to keep a
+/// tampered file from exhausting memory or CPU. Memory cost of scrypt is `128 · r · 2^log_n`
+/// bytes, which is 1 GiB at the maximums.
+pub const MAX_SCRYPT_LOG_N: u8 = 20;
+pub const MAX_SCRYPT_R: u32 = 8;
+pub const MAX_SCRYPT_P: u32 = 16;
+
+/// Public data of sealed key, readable without passphrase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SealedKeyMetadata {
+    pub version: u32,
+    /// Hex of compressed SEC1 encoding of shared public key
+    pub public_key: String,
+    pub own_party_index: u16,
+    pub threshold: u16,
+    pub share_count: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: String,
+}
+
+impl KdfParams {
+    /// Scrypt parameters recommended for interactive use (`log_n = 15, r = 8, p = 1`)
+    pub fn recommended() -> Self {
+        Self::scrypt(15, 8, 1)
+    }
+
+    pub fn scrypt(log_n: u8, r: u32, p: u32) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            algorithm: KDF_SCRYPT.to_string(),
+            log_n,
+            r,
+            p,
+            salt: hex::encode(salt),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &[u8]) -> Result<Zeroizing<[u8; KEY_LEN]>, StorageError> {
+        if self.algorithm != KDF_SCRYPT {
+            return Err(StorageError::UnsupportedKdf(self.algorithm.clone()));
+        }
+        if self.log_n > MAX_SCRYPT_LOG_N || self.r > MAX_SCRYPT_R || self.p > MAX_SCRYPT_P {
+            return Err(StorageError::KdfParamsTooLarge {
+                log_n: self.log_n,
+                r: self.r,
+                p: self.p,
+            });
+        }
+        let salt = hex::decode(&self.salt).map_err(|_| StorageError::InvalidField { field: "salt" })?;
+        let params = scrypt::Params::new(self.log_n, self.r, self.p)
+            .map_err(|_| StorageError::InvalidKdfParams)?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        scrypt::scrypt(passphrase, &salt, &params, &mut key[..])
+            .map_err(|_| StorageError::InvalidKdfParams)?;
+        Ok(key)
+    }
+}
+
+/// [LocalKey] encrypted under a passphrase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SealedLocalKey {
+    pub metadata: SealedKeyMetadata,
+    pub kdf: KdfParams,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Public data of sealed presignature, readable without passphrase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SealedPresignatureMetadata {
+    pub version: u32,
+    /// Hex of compressed SEC1 encoding of shared public key
+    pub public_key: String,
+}
+
+/// [CompletedOfflineStage] encrypted under a passphrase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SealedPresignature {
+    pub metadata: SealedPresignatureMetadata,
+    pub kdf: KdfParams,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Error of sealing or opening [SealedLocalKey] or [SealedPresignature]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum StorageError {
+    #[error("malformed sealed key: {0}")]
+    Malformed(#[source] serde_json::Error),
+    #[error("sealed key version {0} is not supported")]
+    UnsupportedVersion(u32),
+    #[error("key derivation function {0:?} is not supported")]
+    UnsupportedKdf(String),
+    #[error("cipher {0:?} is not supported")]
+    UnsupportedCipher(String),
+    #[error("invalid key derivation parameters")]
+    InvalidKdfParams,
+    /// Scrypt parameters exceed [MAX_SCRYPT_LOG_N], [MAX_SCRYPT_R] or [MAX_SCRYPT_P]
+    #[error("key derivation parameters are too large: log_n={log_n}, r={r}, p={p}")]
+    KdfParamsTooLarge { log_n: u8, r: u32, p: u32 },
+    #[error("field {field} is malformed")]
+    InvalidField { field: &'static str },
+    #[error("encode local key: {0}")]
+    EncodeKey(#[source] LocalKeyFileError),
+    #[error("decode local key: {0}")]
+    DecodeKey(#[source] LocalKeyFileError),
+    #[error("encode presignature: {0}")]
+    EncodePresignature(#[source] serde_json::Error),
+    #[error("decode presignature: {0}")]
+    DecodePresignature(#[source] serde_json::Error),
+    #[error("encryption failed")]
+    Encrypt,
+    /// Passphrase is wrong, or ciphertext or metadata were modified
+    #[error("wrong passphrase or sealed key is corrupted")]
+    Decrypt,
+    /// Decrypted key or presignature doesn't correspond to the metadata it was stored with
+    #[error("sealed key metadata doesn't match decrypted key")]
+    MetadataMismatch,
+}
+
+impl SealedLocalKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        serde_json::from_slice(bytes).map_err(StorageError::Malformed)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, StorageError> {
+        serde_json::to_vec_pretty(self).map_err(StorageError::Malformed)
+    }
+
+    /// Shared public key, available without passphrase
+    pub fn public_key(&self) -> Result<Point<Secp256k1>, StorageError> {
+        let bytes = hex::decode(&self.metadata.public_key)
+            .map_err(|_| StorageError::InvalidField { field: "public_key" })?;
+        Point::from_bytes(&bytes).map_err(|_| StorageError::InvalidField { field: "public_key" })
+    }
+
+    /// Decrypts the key
+    pub fn open(&self, passphrase: &[u8]) -> Result<LocalKey<Secp256k1>, StorageError> {
+        if self.metadata.version != SEALED_KEY_VERSION {
+            return Err(StorageError::UnsupportedVersion(self.metadata.version));
+        }
+        let aad = associated_data(&self.metadata, &self.kdf)?;
+        let plaintext = decrypt(
+            passphrase,
+            &self.kdf,
+            &aad,
+            &self.cipher,
+            &self.nonce,
+            &self.ciphertext,
+        )?;
+
+        let local_key = LocalKey::<Secp256k1>::from_versioned_bytes(&plaintext)
+            .map_err(StorageError::DecodeKey)?;
+        if metadata_of(&local_key)? != self.metadata {
+            return Err(StorageError::MetadataMismatch);
+        }
+        Ok(local_key)
+    }
+}
+
+impl LocalKey<Secp256k1> {
+    /// Encrypts the key under given passphrase using [recommended](KdfParams::recommended)
+    /// scrypt parameters
+    pub fn seal(&self, passphrase: &[u8]) -> Result<SealedLocalKey, StorageError> {
+        self.seal_with_kdf(passphrase, KdfParams::recommended())
+    }
+
+    pub fn seal_with_kdf(
+        &self,
+        passphrase: &[u8],
+        kdf: KdfParams,
+    ) -> Result<SealedLocalKey, StorageError> {
+        let metadata = metadata_of(self)?;
+        let aad = associated_data(&metadata, &kdf)?;
+        let plaintext = Zeroizing::new(self.to_versioned_bytes().map_err(StorageError::EncodeKey)?);
+        let (nonce, ciphertext) = encrypt(passphrase, &kdf, &aad, &plaintext)?;
+
+        Ok(SealedLocalKey {
+            metadata,
+            kdf,
+            cipher: CIPHER_AES_256_GCM.to_string(),
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Decrypts the key sealed by [seal](Self::seal)
+    pub fn open(sealed: &SealedLocalKey, passphrase: &[u8]) -> Result<Self, StorageError> {
+        sealed.open(passphrase)
+    }
+}
+
+fn metadata_of(local_key: &LocalKey<Secp256k1>) -> Result<SealedKeyMetadata, StorageError> {
+    let own_party_index = u16::try_from(local_key.own_party_index).map_err(|_| {
+        StorageError::InvalidField {
+            field: "own_party_index",
+        }
+    })?;
+    Ok(SealedKeyMetadata {
+        version: SEALED_KEY_VERSION,
+        public_key: hex::encode(local_key.public_key.to_bytes(true)),
+        own_party_index,
+        threshold: local_key.key_params.threshold,
+        share_count: local_key.key_params.share_count,
+    })
+}
+
+impl SealedPresignature {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        serde_json::from_slice(bytes).map_err(StorageError::Malformed)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, StorageError> {
+        serde_json::to_vec_pretty(self).map_err(StorageError::Malformed)
+    }
+
+    /// Decrypts the presignature
+    pub fn open(&self, passphrase: &[u8]) -> Result<CompletedOfflineStage, StorageError> {
+        if self.metadata.version != SEALED_PRESIGNATURE_VERSION {
+            return Err(StorageError::UnsupportedVersion(self.metadata.version));
+        }
+        let aad = associated_data(&self.metadata, &self.kdf)?;
+        let plaintext = decrypt(
+            passphrase,
+            &self.kdf,
+            &aad,
+            &self.cipher,
+            &self.nonce,
+            &self.ciphertext,
+        )?;
+
+        let presignature: CompletedOfflineStage =
+            serde_json::from_slice(&plaintext).map_err(StorageError::DecodePresignature)?;
+        if presignature_metadata_of(&presignature) != self.metadata {
+            return Err(StorageError::MetadataMismatch);
+        }
+        Ok(presignature)
+    }
+}
+
+impl CompletedOfflineStage {
+    /// Encrypts the presignature under given passphrase using
+    /// [recommended](KdfParams::recommended) scrypt parameters
+    pub fn seal(&self, passphrase: &[u8]) -> Result<SealedPresignature, StorageError> {
+        self.seal_with_kdf(passphrase, KdfParams::recommended())
+    }
+
+    pub fn seal_with_kdf(
+        &self,
+        passphrase: &[u8],
+        kdf: KdfParams,
+    ) -> Result<SealedPresignature, StorageError> {
+        let metadata = presignature_metadata_of(self);
+        let aad = associated_data(&metadata, &kdf)?;
+        let plaintext =
+            Zeroizing::new(serde_json::to_vec(self).map_err(StorageError::EncodePresignature)?);
+        let (nonce, ciphertext) = encrypt(passphrase, &kdf, &aad, &plaintext)?;
+
+        Ok(SealedPresignature {
+            metadata,
+            kdf,
+            cipher: CIPHER_AES_256_GCM.to_string(),
+            nonce,
+            ciphertext,
+        })
+    }
+}
+
+fn presignature_metadata_of(presignature: &CompletedOfflineStage) -> SealedPresignatureMetadata {
+    SealedPresignatureMetadata {
+        version: SEALED_PRESIGNATURE_VERSION,
+        public_key: hex::encode(presignature.public_key().to_bytes(true)),
+    }
+}
+
+fn associated_data<M: Serialize>(metadata: &M, kdf: &KdfParams) -> Result<Vec<u8>, StorageError> {
+    serde_json::to_vec(&(metadata, kdf)).map_err(StorageError::Malformed)
+}
+
+/// Encrypts `plaintext` under a key derived from passphrase, returns hex of nonce and ciphertext
+fn encrypt(
+    passphrase: &[u8],
+    kdf: &KdfParams,
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<(String, String), StorageError> {
+    let key = kdf.derive_key(passphrase)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let cipher = Aes256Gcm::new(Key::from_slice(&key[..]));
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| StorageError::Encrypt)?;
+    Ok((hex::encode(nonce), hex::encode(ciphertext)))
+}
+
+/// Decrypts hex-encoded `ciphertext` sealed by [encrypt]
+fn decrypt(
+    passphrase: &[u8],
+    kdf: &KdfParams,
+    aad: &[u8],
+    cipher: &str,
+    nonce: &str,
+    ciphertext: &str,
+) -> Result<Zeroizing<Vec<u8>>, StorageError> {
+    if cipher != CIPHER_AES_256_GCM {
+        return Err(StorageError::UnsupportedCipher(cipher.to_string()));
+    }
+    let nonce = hex::decode(nonce).map_err(|_| StorageError::InvalidField { field: "nonce" })?;
+    if nonce.len() != NONCE_LEN {
+        return Err(StorageError::InvalidField { field: "nonce" });
+    }
+    let ciphertext = hex::decode(ciphertext).map_err(|_| StorageError::InvalidField {
+        field: "ciphertext",
+    })?;
+
+    let key = kdf.derive_key(passphrase)?;
+    let cipher = Aes256Gcm::new(Key::from_slice(&key[..]));
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| StorageError::Decrypt)?;
+    Ok(Zeroizing::new(plaintext))
+}
+
+#[cfg(test)]
+mod test;
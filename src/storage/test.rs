@@ -0,0 +1,150 @@
+use curv::elliptic::curves::{Point, Secp256k1};
+
+use crate::protocols::gg_2020::state_machine::keygen::{
+    local_key::versioned::LocalKeyFile, local_key::LocalKey, test::simulate_keygen,
+};
+use crate::protocols::gg_2020::state_machine::sign::test::simulate_offline_stage;
+use crate::storage::{KdfParams, SealedLocalKey, SealedPresignature, StorageError};
+
+const GOLDEN_LOCAL_KEY_V1: &[u8] =
+    include_bytes!("../protocols/gg_2020/state_machine/keygen/testdata/local_key_v1.json");
+
+fn local_key() -> LocalKey<Secp256k1> {
+    LocalKeyFile::from_bytes(GOLDEN_LOCAL_KEY_V1)
+        .unwrap()
+        .to_local_key()
+        .unwrap()
+}
+
+/// Cheap scrypt parameters, so tests don't take long
+fn test_kdf() -> KdfParams {
+    KdfParams::scrypt(10, 8, 1)
+}
+
+#[test]
+fn seal_and_open() {
+    let key = local_key();
+    let sealed = key.seal_with_kdf(b"correct horse", test_kdf()).unwrap();
+    let sealed = SealedLocalKey::from_bytes(&sealed.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(sealed.public_key().unwrap(), Point::generator().to_point());
+    assert_eq!(sealed.metadata.own_party_index, 1);
+    assert_eq!(sealed.metadata.share_count, 2);
+
+    let opened = LocalKey::open(&sealed, b"correct horse").unwrap();
+    assert_eq!(
+        LocalKeyFile::new(&opened, 0).unwrap(),
+        LocalKeyFile::new(&key, 0).unwrap()
+    );
+}
+
+#[test]
+fn sealed_key_doesnt_contain_secret_share() {
+    let key = local_key();
+    let sealed = key.seal_with_kdf(b"correct horse", test_kdf()).unwrap();
+    let bytes = String::from_utf8(sealed.to_bytes().unwrap()).unwrap();
+    let file = LocalKeyFile::new(&key, 0).unwrap();
+    assert!(!bytes.contains(&file.key.x_i));
+}
+
+#[test]
+fn wrong_passphrase_is_rejected() {
+    let sealed = local_key()
+        .seal_with_kdf(b"correct horse", test_kdf())
+        .unwrap();
+    assert!(matches!(
+        sealed.open(b"battery staple"),
+        Err(StorageError::Decrypt)
+    ));
+}
+
+#[test]
+fn tampered_metadata_is_rejected() {
+    let sealed = local_key()
+        .seal_with_kdf(b"correct horse", test_kdf())
+        .unwrap();
+
+    let mut tampered = sealed.clone();
+    tampered.metadata.own_party_index = 2;
+    assert!(matches!(
+        tampered.open(b"correct horse"),
+        Err(StorageError::Decrypt)
+    ));
+
+    let mut tampered = sealed;
+    tampered.kdf.log_n = 11;
+    assert!(matches!(
+        tampered.open(b"correct horse"),
+        Err(StorageError::Decrypt)
+    ));
+}
+
+#[test]
+fn oversized_kdf_params_are_rejected_before_derivation() {
+    let sealed = local_key()
+        .seal_with_kdf(b"correct horse", test_kdf())
+        .unwrap();
+
+    let mut tampered = sealed.clone();
+    tampered.kdf.log_n = 40;
+    assert!(matches!(
+        tampered.open(b"correct horse"),
+        Err(StorageError::KdfParamsTooLarge { log_n: 40, .. })
+    ));
+
+    let mut tampered = sealed;
+    tampered.kdf.p = u32::MAX;
+    assert!(matches!(
+        tampered.open(b"correct horse"),
+        Err(StorageError::KdfParamsTooLarge { .. })
+    ));
+
+    assert!(matches!(
+        local_key().seal_with_kdf(b"correct horse", KdfParams::scrypt(21, 8, 1)),
+        Err(StorageError::KdfParamsTooLarge { .. })
+    ));
+}
+
+#[test]
+fn party_index_overflow_is_rejected() {
+    let mut key = local_key();
+    key.own_party_index = usize::from(u16::MAX) + 1;
+    assert!(matches!(
+        key.seal_with_kdf(b"correct horse", test_kdf()),
+        Err(StorageError::InvalidField {
+            field: "own_party_index"
+        })
+    ));
+}
+
+#[test]
+fn seal_and_open_presignature() {
+    let keys = simulate_keygen(1, 2);
+    let presignature = simulate_offline_stage(keys, &[1, 2]).remove(0);
+    let sealed = presignature
+        .seal_with_kdf(b"correct horse", test_kdf())
+        .unwrap();
+    let sealed = SealedPresignature::from_bytes(&sealed.to_bytes().unwrap()).unwrap();
+    assert_eq!(
+        sealed.metadata.public_key,
+        hex::encode(presignature.public_key().to_bytes(true))
+    );
+
+    let opened = sealed.open(b"correct horse").unwrap();
+    assert_eq!(
+        serde_json::to_value(&opened).unwrap(),
+        serde_json::to_value(&presignature).unwrap()
+    );
+
+    assert!(matches!(
+        sealed.open(b"battery staple"),
+        Err(StorageError::Decrypt)
+    ));
+    let mut tampered = sealed;
+    tampered.metadata.public_key =
+        hex::encode(Point::<Secp256k1>::generator().to_point().to_bytes(true));
+    assert!(matches!(
+        tampered.open(b"correct horse"),
+        Err(StorageError::Decrypt)
+    ));
+}
@@ -0,0 +1,8 @@
+//! Transport-level utilities for running protocols over untrusted networks
+
+pub mod authenticated;
+pub mod secure_channel;
+#[cfg(feature = "transport-sse")]
+pub mod sse;
+#[cfg(feature = "transport-tcp")]
+pub mod tcp;
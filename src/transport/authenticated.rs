@@ -0,0 +1,343 @@
+//! Authentication of protocol messages by long-term party identities
+//!
+//! Transport (such as `gg20_sm_manager` example) lets any room participant send a message with
+//! arbitrary `sender` field. [MessageAuthenticator] makes every party sign its outgoing messages
+//! with a long-term [SigningKey], and rejects incoming messages whose signature doesn't verify
+//! under the public key of the claimed sender.
+//!
+//! Signature covers session id, round number, sender, receiver, and hash of the message body,
+//! so a signed message can't be replayed in another session or round, nor redirected to
+//! another party. Body is hashed in its canonical [wire](crate::utilities::wire) encoding, so
+//! the signature doesn't depend on how the transport serializes the message.
+//!
+//! Authenticator composes with [SecureChannel](super::secure_channel::SecureChannel): sign
+//! messages first, then encrypt signed messages.
+//!
+//! ```rust,ignore
+//! let auth = MessageAuthenticator::new(i, signing_key, peers, &session_id);
+//! let incoming = auth.wrap_incoming(incoming).fuse();
+//! let outgoing = auth.wrap_outgoing(outgoing);
+//! AsyncProtocol::new(keygen, incoming, outgoing).run().await?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use futures::{future, Sink, SinkExt, Stream, StreamExt};
+use round_based::Msg;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+use crate::utilities::fingerprint::Fingerprint;
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::{self, Wire};
+
+const SIGNATURE_DOMAIN: &[u8] = b"multi-party-ecdsa/authenticated-msg/v1";
+const CHALLENGE_DOMAIN: &[u8] = b"multi-party-ecdsa/schnorr-challenge/v1";
+
+/// Long-term signing key of a party
+#[derive(Clone)]
+pub struct SigningKey {
+    secret: Scalar<Secp256k1>,
+    public: Point<Secp256k1>,
+}
+
+impl SigningKey {
+    pub fn generate() -> Self {
+        Self::from_secret(Scalar::random())
+    }
+
+    pub fn from_secret(secret: Scalar<Secp256k1>) -> Self {
+        let public = Point::generator() * &secret;
+        Self { secret, public }
+    }
+
+    pub fn public_key(&self) -> &Point<Secp256k1> {
+        &self.public
+    }
+
+    /// Produces Schnorr signature of the message
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let k = Scalar::<Secp256k1>::random();
+        let r = Point::generator() * &k;
+        let e = challenge(&r, &self.public, message);
+        let s = &k + &(e * &self.secret);
+        Signature { r, s }
+    }
+}
+
+impl Zeroize for SigningKey {
+    fn zeroize(&mut self) {
+        self.secret = Scalar::zero();
+    }
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SigningKey {}
+
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("secret", &Fingerprint::of_scalar(&self.secret))
+            .field("public", &self.public)
+            .finish()
+    }
+}
+
+/// Schnorr signature over secp256k1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub r: Point<Secp256k1>,
+    pub s: Scalar<Secp256k1>,
+}
+
+impl Signature {
+    pub fn verify(&self, public_key: &Point<Secp256k1>, message: &[u8]) -> bool {
+        let e = challenge(&self.r, public_key, message);
+        Point::generator() * &self.s == &self.r + &(public_key * &e)
+    }
+}
+
+/// Message body as it's sent over the transport
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SignedBody<M> {
+    pub round: u16,
+    pub body: M,
+    pub signature: Signature,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AuthenticationError {
+    /// Claimed sender is not a participant of the session
+    #[error("message claims to be sent by unknown party {sender}")]
+    UnknownSender { sender: u16 },
+    /// Message is addressed to another party
+    #[error("message claimed to be sent by party {sender} is addressed to party {receiver}")]
+    MisAddressed { sender: u16, receiver: u16 },
+    /// Round number in signed envelope doesn't match the message body
+    #[error("message claimed to be sent by party {sender} belongs to round {body_round}, but signed for round {signed_round}")]
+    RoundMismatch {
+        sender: u16,
+        signed_round: u16,
+        body_round: u16,
+    },
+    /// Signature doesn't verify under public key of the claimed sender
+    #[error("message claimed to be sent by party {sender} has invalid signature")]
+    Forged { sender: u16 },
+}
+
+/// Error of stream returned by [MessageAuthenticator::wrap_incoming]
+#[derive(Debug, Error)]
+pub enum IncomingError<E> {
+    #[error("receive message")]
+    Transport(#[source] E),
+    #[error(transparent)]
+    Authentication(AuthenticationError),
+}
+
+/// Error of sink returned by [MessageAuthenticator::wrap_outgoing]
+#[derive(Debug, Error)]
+pub enum OutgoingError<E> {
+    #[error("send message")]
+    Transport(#[source] E),
+    #[error(transparent)]
+    Authentication(AuthenticationError),
+}
+
+impl<E> From<E> for OutgoingError<E> {
+    fn from(err: E) -> Self {
+        OutgoingError::Transport(err)
+    }
+}
+
+/// Signs outgoing and verifies incoming messages of a single party
+///
+/// Cheap to clone.
+#[derive(Clone)]
+pub struct MessageAuthenticator {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    party_index: u16,
+    signing_key: SigningKey,
+    peers: BTreeMap<u16, Point<Secp256k1>>,
+    session_id: SessionId,
+}
+
+impl MessageAuthenticator {
+    /// Constructs authenticator of party `party_index`
+    ///
+    /// `peers` maps index of every other party to its long-term public key. `session_id` must be
+    /// unique for every protocol execution and agreed by all the parties.
+    pub fn new(
+        party_index: u16,
+        signing_key: SigningKey,
+        peers: BTreeMap<u16, Point<Secp256k1>>,
+        session_id: &SessionId,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                party_index,
+                signing_key,
+                peers,
+                session_id: *session_id,
+            }),
+        }
+    }
+
+    pub fn party_index(&self) -> u16 {
+        self.inner.party_index
+    }
+
+    /// Signs outgoing message
+    pub fn sign<M>(&self, msg: Msg<M>) -> Result<Msg<SignedBody<M>>, AuthenticationError>
+    where
+        M: Wire + RoundMessage,
+    {
+        let round = msg.body.round();
+        let payload = self.signed_payload(round, msg.sender, msg.receiver, &msg.body);
+        let signature = self.inner.signing_key.sign(&payload);
+        Ok(Msg {
+            sender: msg.sender,
+            receiver: msg.receiver,
+            body: SignedBody {
+                round,
+                body: msg.body,
+                signature,
+            },
+        })
+    }
+
+    /// Verifies incoming message
+    ///
+    /// Rejects messages from unknown parties, messages addressed to other parties, and messages
+    /// not signed by the claimed sender.
+    pub fn verify<M>(&self, msg: Msg<SignedBody<M>>) -> Result<Msg<M>, AuthenticationError>
+    where
+        M: Wire + RoundMessage,
+    {
+        let sender = msg.sender;
+        let public_key = self
+            .inner
+            .peers
+            .get(&sender)
+            .filter(|_| sender != self.inner.party_index)
+            .ok_or(AuthenticationError::UnknownSender { sender })?;
+        if let Some(receiver) = msg.receiver {
+            if receiver != self.inner.party_index {
+                return Err(AuthenticationError::MisAddressed { sender, receiver });
+            }
+        }
+        let body_round = msg.body.body.round();
+        if body_round != msg.body.round {
+            return Err(AuthenticationError::RoundMismatch {
+                sender,
+                signed_round: msg.body.round,
+                body_round,
+            });
+        }
+
+        let payload = self.signed_payload(msg.body.round, sender, msg.receiver, &msg.body.body);
+        if !msg.body.signature.verify(public_key, &payload) {
+            return Err(AuthenticationError::Forged { sender });
+        }
+        Ok(Msg {
+            sender,
+            receiver: msg.receiver,
+            body: msg.body.body,
+        })
+    }
+
+    /// Wraps a stream of signed messages received from transport into stream of verified protocol
+    /// messages, suitable for [AsyncProtocol](round_based::AsyncProtocol)
+    pub fn wrap_incoming<M, S, E>(
+        &self,
+        incoming: S,
+    ) -> impl Stream<Item = Result<Msg<M>, IncomingError<E>>>
+    where
+        S: Stream<Item = Result<Msg<SignedBody<M>>, E>>,
+        M: Wire + RoundMessage,
+    {
+        let auth = self.clone();
+        incoming.map(move |msg| match msg {
+            Ok(msg) => auth.verify(msg).map_err(IncomingError::Authentication),
+            Err(err) => Err(IncomingError::Transport(err)),
+        })
+    }
+
+    /// Wraps a sink of signed messages into sink of protocol messages, suitable for
+    /// [AsyncProtocol](round_based::AsyncProtocol)
+    pub fn wrap_outgoing<M, S>(
+        &self,
+        outgoing: S,
+    ) -> impl Sink<Msg<M>, Error = OutgoingError<S::Error>>
+    where
+        S: Sink<Msg<SignedBody<M>>>,
+        M: Wire + RoundMessage,
+    {
+        let auth = self.clone();
+        outgoing.with(move |msg: Msg<M>| {
+            future::ready(auth.sign(msg).map_err(OutgoingError::Authentication))
+        })
+    }
+
+    fn signed_payload<M: Wire>(
+        &self,
+        round: u16,
+        sender: u16,
+        receiver: Option<u16>,
+        body: &M,
+    ) -> Vec<u8> {
+        let body = wire::to_bytes(body);
+        let mut payload = SIGNATURE_DOMAIN.to_vec();
+        payload.extend_from_slice(self.inner.session_id.as_bytes());
+        payload.extend_from_slice(&round.to_be_bytes());
+        payload.extend_from_slice(&sender.to_be_bytes());
+        match receiver {
+            None => payload.push(0),
+            Some(receiver) => {
+                payload.push(1);
+                payload.extend_from_slice(&receiver.to_be_bytes());
+            }
+        }
+        payload.extend_from_slice(&Sha256::digest(&body));
+        payload
+    }
+}
+
+impl fmt::Debug for MessageAuthenticator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MessageAuthenticator")
+            .field("party_index", &self.inner.party_index)
+            .field("public_key", self.inner.signing_key.public_key())
+            .field("peers", &self.inner.peers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn challenge(r: &Point<Secp256k1>, public_key: &Point<Secp256k1>, message: &[u8]) -> Scalar<Secp256k1> {
+    let hash = Sha256::new()
+        .chain(CHALLENGE_DOMAIN)
+        .chain(r.to_bytes(true).as_ref())
+        .chain(public_key.to_bytes(true).as_ref())
+        .chain(message)
+        .finalize();
+    Scalar::from_bigint(&BigInt::from_bytes(&hash))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::{SinkExt, StreamExt};
+use round_based::Msg;
+use serde::{Deserialize, Serialize};
+
+use crate::protocols::gg_2020::state_machine::traits::RoundMessage;
+use crate::transport::authenticated::{
+    AuthenticationError, IncomingError, MessageAuthenticator, SigningKey,
+};
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct TestMsg {
+    round: u16,
+    payload: String,
+}
+
+impl RoundMessage for TestMsg {
+    fn round(&self) -> u16 {
+        self.round
+    }
+}
+
+wire_struct!(TestMsg { round, payload });
+
+fn authenticators(n: u16, session: &[u8]) -> Vec<MessageAuthenticator> {
+    let session_id = SessionId::new(session);
+    let keys: Vec<SigningKey> = (0..n).map(|_| SigningKey::generate()).collect();
+    let peers: BTreeMap<u16, _> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, k)| (i as u16 + 1, k.public_key().clone()))
+        .collect();
+    keys.into_iter()
+        .enumerate()
+        .map(|(i, k)| MessageAuthenticator::new(i as u16 + 1, k, peers.clone(), &session_id))
+        .collect()
+}
+
+fn msg(sender: u16, receiver: Option<u16>, round: u16, payload: &str) -> Msg<TestMsg> {
+    Msg {
+        sender,
+        receiver,
+        body: TestMsg {
+            round,
+            payload: payload.to_string(),
+        },
+    }
+}
+
+#[test]
+fn signed_message_is_accepted() {
+    let parties = authenticators(3, b"session");
+    for receiver in [None, Some(2)].iter().copied() {
+        let signed = parties[0].sign(msg(1, receiver, 1, "hello")).unwrap();
+        let verified = parties[1].verify(signed).unwrap();
+        assert_eq!(verified.sender, 1);
+        assert_eq!(verified.receiver, receiver);
+        assert_eq!(verified.body.payload, "hello");
+    }
+}
+
+#[test]
+fn forged_sender_is_rejected() {
+    let parties = authenticators(3, b"session");
+
+    // Party 3 claims to be party 1
+    let forged = parties[2].sign(msg(1, None, 1, "hello")).unwrap();
+    let err = parties[1].verify(forged).unwrap_err();
+    assert!(matches!(err, AuthenticationError::Forged { sender: 1 }));
+    assert!(err.to_string().contains("party 1"));
+
+    let mut tampered = parties[0].sign(msg(1, None, 1, "hello")).unwrap();
+    tampered.body.body.payload = "bye".to_string();
+    let err = parties[1].verify(tampered).unwrap_err();
+    assert!(matches!(err, AuthenticationError::Forged { sender: 1 }));
+
+    let unknown = parties[0].sign(msg(4, None, 1, "hello")).unwrap();
+    let err = parties[1].verify(unknown).unwrap_err();
+    assert!(matches!(err, AuthenticationError::UnknownSender { sender: 4 }));
+}
+
+#[test]
+fn misaddressed_message_is_rejected() {
+    let parties = authenticators(3, b"session");
+    let signed = parties[0].sign(msg(1, Some(2), 1, "share")).unwrap();
+
+    let err = parties[2].verify(signed.clone()).unwrap_err();
+    assert!(matches!(
+        err,
+        AuthenticationError::MisAddressed {
+            sender: 1,
+            receiver: 2
+        }
+    ));
+
+    // Relay redirects the message to party 3
+    let mut redirected = signed;
+    redirected.receiver = Some(3);
+    let err = parties[2].verify(redirected).unwrap_err();
+    assert!(matches!(err, AuthenticationError::Forged { sender: 1 }));
+}
+
+#[test]
+fn message_cannot_be_replayed_in_other_session_or_round() {
+    let session_a = authenticators(2, b"session a");
+    let signed = session_a[0].sign(msg(1, None, 1, "hello")).unwrap();
+
+    // Same keys, different session
+    let session_b = MessageAuthenticator::new(
+        2,
+        SigningKey::generate(),
+        [(1, session_a[0].inner.signing_key.public_key().clone())]
+            .iter()
+            .cloned()
+            .collect(),
+        &SessionId::new(b"session b"),
+    );
+    let err = session_b.verify(signed.clone()).unwrap_err();
+    assert!(matches!(err, AuthenticationError::Forged { sender: 1 }));
+
+    let mut other_round = signed.clone();
+    other_round.body.round = 2;
+    other_round.body.body.round = 2;
+    let err = session_a[1].verify(other_round).unwrap_err();
+    assert!(matches!(err, AuthenticationError::Forged { sender: 1 }));
+
+    let mut mismatched_round = signed;
+    mismatched_round.body.body.round = 2;
+    let err = session_a[1].verify(mismatched_round).unwrap_err();
+    assert!(matches!(
+        err,
+        AuthenticationError::RoundMismatch {
+            sender: 1,
+            signed_round: 1,
+            body_round: 2
+        }
+    ));
+}
+
+#[test]
+fn adapters_wrap_transport() {
+    let parties = authenticators(3, b"session");
+    let (tx, rx) = mpsc::unbounded();
+    let mut outgoing = Box::pin(parties[0].wrap_outgoing(tx.clone()));
+    let mut forger = Box::pin(parties[2].wrap_outgoing(tx));
+    let mut incoming = Box::pin(parties[1].wrap_incoming(rx.map(Ok::<_, Infallible>)));
+
+    block_on(async {
+        outgoing.send(msg(1, None, 1, "hello")).await.unwrap();
+        forger.send(msg(1, None, 1, "forged")).await.unwrap();
+
+        let received = incoming.next().await.unwrap().unwrap();
+        assert_eq!(received.body.payload, "hello");
+        let err = incoming.next().await.unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            IncomingError::Authentication(AuthenticationError::Forged { sender: 1 })
+        ));
+    });
+}
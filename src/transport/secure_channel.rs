@@ -0,0 +1,424 @@
+//! End-to-end encryption of P2P protocol messages
+//!
+//! Relay (such as `gg20_sm_manager` example) sees every message routed through it, including P2P
+//! messages which carry secret shares (e.g. `FeldmanVSS` in keygen round 3). [SecureChannel]
+//! encrypts P2P messages so only the intended receiver can read them.
+//!
+//! Every party holds a static [IdentityKey] and knows identity public keys of other parties.
+//! A message from party `i` to party `j` is encrypted with AES-256-GCM under the key derived
+//! by HKDF-SHA256 from ECDH shared secret of `i` and `j` identities. Key is bound to the session
+//! id and ordered pair `(i, j)`. Session id, `(i, j)` and a sequence number of the message are
+//! also authenticated as associated data, so the relay can neither read nor alter the message,
+//! redirect it to another party, forge its sender, nor replay it within the same session or
+//! into another session.
+//!
+//! Broadcast messages are passed as is: they are seen by every party anyway.
+//!
+//! Channel is plugged between [AsyncProtocol](round_based::AsyncProtocol) and the transport:
+//!
+//! ```rust,ignore
+//! let channel = SecureChannel::new(i, &identity, &peers, &session_id)?;
+//! let incoming = channel.wrap_incoming(incoming).fuse();
+//! let outgoing = channel.wrap_outgoing(outgoing);
+//! AsyncProtocol::new(keygen, incoming, outgoing).run().await?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, NewAead, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use futures::{future, Sink, SinkExt, Stream, StreamExt};
+use hkdf::Hkdf;
+use rand::{rngs::OsRng, RngCore};
+use round_based::Msg;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use crate::utilities::fingerprint::Fingerprint;
+use crate::utilities::session_id::SessionId;
+
+const HKDF_INFO_PREFIX: &[u8] = b"multi-party-ecdsa/secure-channel/v1";
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Long-term identity key of a party
+#[derive(Clone)]
+pub struct IdentityKey {
+    secret: Scalar<Secp256k1>,
+    public: Point<Secp256k1>,
+}
+
+impl IdentityKey {
+    pub fn generate() -> Self {
+        Self::from_secret(Scalar::random())
+    }
+
+    pub fn from_secret(secret: Scalar<Secp256k1>) -> Self {
+        let public = Point::generator() * &secret;
+        Self { secret, public }
+    }
+
+    pub fn public_key(&self) -> &Point<Secp256k1> {
+        &self.public
+    }
+}
+
+impl Zeroize for IdentityKey {
+    fn zeroize(&mut self) {
+        self.secret = Scalar::zero();
+    }
+}
+
+impl Drop for IdentityKey {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for IdentityKey {}
+
+impl fmt::Debug for IdentityKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IdentityKey")
+            .field("secret", &Fingerprint::of_scalar(&self.secret))
+            .field("public", &self.public)
+            .finish()
+    }
+}
+
+/// Message body as it's sent over the transport
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Envelope<M> {
+    /// Broadcast message, sent in plaintext
+    Plain(M),
+    /// P2P message encrypted under receiver's key
+    Encrypted(EncryptedBody),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EncryptedBody {
+    /// Number of messages sent before this one by the same sender to the same receiver
+    pub seq: u64,
+    /// Hex of 96 bits nonce
+    pub nonce: String,
+    /// Hex of AES-256-GCM ciphertext of serialized message body
+    pub ciphertext: String,
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SecureChannelError {
+    /// Identity public key of the party is not known
+    #[error("identity key of party {party} is unknown")]
+    UnknownParty { party: u16 },
+    /// ECDH with party's identity key resulted in point at infinity
+    #[error("identity key of party {party} is invalid")]
+    InvalidIdentityKey { party: u16 },
+    /// Message is addressed to another party
+    #[error("message from party {sender} is addressed to party {receiver}")]
+    WrongReceiver { sender: u16, receiver: u16 },
+    /// Outgoing message claims to be sent by a party other than owner of the channel
+    #[error("message claims to be sent by party {sender}, but channel belongs to party {party}")]
+    WrongSender { sender: u16, party: u16 },
+    /// Message with the same or greater sequence number was already received from the sender
+    #[error("message {seq} from party {sender} is replayed or out of order")]
+    Replayed { sender: u16, seq: u64 },
+    /// P2P message was received in plaintext, or broadcast message was encrypted
+    #[error("message from party {sender} is not properly enveloped")]
+    UnexpectedEnvelope { sender: u16 },
+    /// Message wasn't produced by the party it claims to be sent by, or was altered in transit
+    #[error("message from party {sender} failed authentication")]
+    Decrypt { sender: u16 },
+    #[error("encryption failed")]
+    Encrypt,
+    #[error("field {field} is malformed")]
+    InvalidField { field: &'static str },
+    #[error("serialize message body: {0}")]
+    Serialize(#[source] serde_json::Error),
+    #[error("deserialize message body: {0}")]
+    Deserialize(#[source] serde_json::Error),
+}
+
+/// Error of stream returned by [SecureChannel::wrap_incoming]
+#[derive(Debug, Error)]
+pub enum IncomingError<E> {
+    #[error("receive message")]
+    Transport(#[source] E),
+    #[error(transparent)]
+    Channel(SecureChannelError),
+}
+
+/// Error of sink returned by [SecureChannel::wrap_outgoing]
+#[derive(Debug, Error)]
+pub enum OutgoingError<E> {
+    #[error("send message")]
+    Transport(#[source] E),
+    #[error(transparent)]
+    Channel(SecureChannelError),
+}
+
+impl<E> From<E> for OutgoingError<E> {
+    fn from(err: E) -> Self {
+        OutgoingError::Transport(err)
+    }
+}
+
+/// Encrypts outgoing and decrypts incoming P2P messages of a single party
+///
+/// Cheap to clone: derived keys and sequence numbers are shared between clones.
+#[derive(Clone)]
+pub struct SecureChannel {
+    party_index: u16,
+    session_id: SessionId,
+    keys: Arc<BTreeMap<u16, PeerKeys>>,
+}
+
+struct PeerKeys {
+    /// Key for messages sent by us to the peer
+    send: Zeroizing<[u8; KEY_LEN]>,
+    /// Key for messages sent by the peer to us
+    recv: Zeroizing<[u8; KEY_LEN]>,
+    /// Sequence number of the next message sent to the peer
+    next_send: AtomicU64,
+    /// Smallest sequence number accepted in the next message from the peer
+    next_recv: AtomicU64,
+}
+
+impl SecureChannel {
+    /// Constructs a channel of party `party_index`
+    ///
+    /// `peers` maps index of every other party to its identity public key. Entry of
+    /// `party_index` itself, if present, is ignored. `session_id` must be unique for every
+    /// protocol execution and agreed by all the parties.
+    pub fn new(
+        party_index: u16,
+        identity: &IdentityKey,
+        peers: &BTreeMap<u16, Point<Secp256k1>>,
+        session_id: &SessionId,
+    ) -> Result<Self, SecureChannelError> {
+        let mut keys = BTreeMap::new();
+        for (&peer, peer_pk) in peers.iter().filter(|&(&peer, _)| peer != party_index) {
+            let shared_point = peer_pk * &identity.secret;
+            if shared_point.is_zero() {
+                return Err(SecureChannelError::InvalidIdentityKey { party: peer });
+            }
+            let shared_secret = Zeroizing::new(shared_point.to_bytes(true).to_vec());
+            keys.insert(
+                peer,
+                PeerKeys {
+                    send: derive_key(&shared_secret, session_id, party_index, peer)?,
+                    recv: derive_key(&shared_secret, session_id, peer, party_index)?,
+                    next_send: AtomicU64::new(0),
+                    next_recv: AtomicU64::new(0),
+                },
+            );
+        }
+        Ok(Self {
+            party_index,
+            session_id: *session_id,
+            keys: Arc::new(keys),
+        })
+    }
+
+    pub fn party_index(&self) -> u16 {
+        self.party_index
+    }
+
+    /// Encrypts P2P message, leaves broadcast message in plaintext
+    ///
+    /// Rejects messages whose sender is not the owner of the channel.
+    pub fn seal<M: Serialize>(&self, msg: Msg<M>) -> Result<Msg<Envelope<M>>, SecureChannelError> {
+        if msg.sender != self.party_index {
+            return Err(SecureChannelError::WrongSender {
+                sender: msg.sender,
+                party: self.party_index,
+            });
+        }
+        let receiver = match msg.receiver {
+            None => {
+                return Ok(Msg {
+                    sender: msg.sender,
+                    receiver: None,
+                    body: Envelope::Plain(msg.body),
+                })
+            }
+            Some(receiver) => receiver,
+        };
+        let peer = self.peer_keys(receiver)?;
+        let key = &peer.send;
+        let seq = peer.next_send.fetch_add(1, Ordering::SeqCst);
+        let plaintext =
+            Zeroizing::new(serde_json::to_vec(&msg.body).map_err(SecureChannelError::Serialize)?);
+
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let aad = self.associated_data(msg.sender, receiver, seq);
+        let ciphertext = Aes256Gcm::new(Key::from_slice(&key[..]))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| SecureChannelError::Encrypt)?;
+
+        Ok(Msg {
+            sender: msg.sender,
+            receiver: Some(receiver),
+            body: Envelope::Encrypted(EncryptedBody {
+                seq,
+                nonce: hex::encode(nonce),
+                ciphertext: hex::encode(ciphertext),
+            }),
+        })
+    }
+
+    /// Decrypts P2P message addressed to this party, and unwraps broadcast message
+    ///
+    /// Rejects P2P messages sent in plaintext, messages addressed to other parties, messages
+    /// which fail authentication, and messages whose sequence number isn't greater than the
+    /// one of the last message accepted from the same sender.
+    pub fn open<M: DeserializeOwned>(
+        &self,
+        msg: Msg<Envelope<M>>,
+    ) -> Result<Msg<M>, SecureChannelError> {
+        let sender = msg.sender;
+        match (msg.receiver, msg.body) {
+            (None, Envelope::Plain(body)) => Ok(Msg {
+                sender,
+                receiver: None,
+                body,
+            }),
+            (Some(receiver), _) if receiver != self.party_index => {
+                Err(SecureChannelError::WrongReceiver { sender, receiver })
+            }
+            (Some(receiver), Envelope::Encrypted(body)) => {
+                let peer = self.peer_keys(sender)?;
+                let key = &peer.recv;
+                let seq = body.seq;
+                if seq < peer.next_recv.load(Ordering::SeqCst) {
+                    return Err(SecureChannelError::Replayed { sender, seq });
+                }
+                let nonce = hex::decode(&body.nonce)
+                    .map_err(|_| SecureChannelError::InvalidField { field: "nonce" })?;
+                if nonce.len() != NONCE_LEN {
+                    return Err(SecureChannelError::InvalidField { field: "nonce" });
+                }
+                let ciphertext = hex::decode(&body.ciphertext)
+                    .map_err(|_| SecureChannelError::InvalidField { field: "ciphertext" })?;
+                let aad = self.associated_data(sender, receiver, seq);
+                let plaintext = Zeroizing::new(
+                    Aes256Gcm::new(Key::from_slice(&key[..]))
+                        .decrypt(
+                            Nonce::from_slice(&nonce),
+                            Payload {
+                                msg: &ciphertext,
+                                aad: &aad,
+                            },
+                        )
+                        .map_err(|_| SecureChannelError::Decrypt { sender })?,
+                );
+                // Counter is advanced only once the message is authenticated, and checked again
+                // in case a copy of the message was accepted concurrently
+                if peer.next_recv.fetch_max(seq + 1, Ordering::SeqCst) > seq {
+                    return Err(SecureChannelError::Replayed { sender, seq });
+                }
+                let body =
+                    serde_json::from_slice(&plaintext).map_err(SecureChannelError::Deserialize)?;
+                Ok(Msg {
+                    sender,
+                    receiver: Some(receiver),
+                    body,
+                })
+            }
+            _ => Err(SecureChannelError::UnexpectedEnvelope { sender }),
+        }
+    }
+
+    /// Wraps a stream of enveloped messages received from transport into stream of protocol
+    /// messages, suitable for [AsyncProtocol](round_based::AsyncProtocol)
+    pub fn wrap_incoming<M, S, E>(
+        &self,
+        incoming: S,
+    ) -> impl Stream<Item = Result<Msg<M>, IncomingError<E>>>
+    where
+        S: Stream<Item = Result<Msg<Envelope<M>>, E>>,
+        M: DeserializeOwned,
+    {
+        let channel = self.clone();
+        incoming.map(move |msg| match msg {
+            Ok(msg) => channel.open(msg).map_err(IncomingError::Channel),
+            Err(err) => Err(IncomingError::Transport(err)),
+        })
+    }
+
+    /// Wraps a sink of enveloped messages into sink of protocol messages, suitable for
+    /// [AsyncProtocol](round_based::AsyncProtocol)
+    pub fn wrap_outgoing<M, S>(
+        &self,
+        outgoing: S,
+    ) -> impl Sink<Msg<M>, Error = OutgoingError<S::Error>>
+    where
+        S: Sink<Msg<Envelope<M>>>,
+        M: Serialize,
+    {
+        let channel = self.clone();
+        outgoing.with(move |msg: Msg<M>| {
+            future::ready(channel.seal(msg).map_err(OutgoingError::Channel))
+        })
+    }
+
+    fn peer_keys(&self, party: u16) -> Result<&PeerKeys, SecureChannelError> {
+        self.keys
+            .get(&party)
+            .ok_or(SecureChannelError::UnknownParty { party })
+    }
+
+    fn associated_data(&self, sender: u16, receiver: u16, seq: u64) -> Vec<u8> {
+        let mut aad = session_context(&self.session_id, sender, receiver);
+        aad.extend_from_slice(&seq.to_be_bytes());
+        aad
+    }
+}
+
+impl fmt::Debug for SecureChannel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SecureChannel")
+            .field("party_index", &self.party_index)
+            .field("peers", &self.keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+fn derive_key(
+    shared_secret: &[u8],
+    session_id: &SessionId,
+    sender: u16,
+    receiver: u16,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SecureChannelError> {
+    let mut info = HKDF_INFO_PREFIX.to_vec();
+    info.extend_from_slice(&session_context(session_id, sender, receiver));
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    Hkdf::<Sha256>::new(None, shared_secret)
+        .expand(&info, &mut key[..])
+        .map_err(|_| SecureChannelError::Encrypt)?;
+    Ok(key)
+}
+
+/// Session id followed by sender and receiver
+fn session_context(session_id: &SessionId, sender: u16, receiver: u16) -> Vec<u8> {
+    let mut context = session_id.as_bytes().to_vec();
+    context.extend_from_slice(&sender.to_be_bytes());
+    context.extend_from_slice(&receiver.to_be_bytes());
+    context
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::{SinkExt, StreamExt};
+use round_based::Msg;
+
+use crate::transport::secure_channel::{
+    Envelope, IdentityKey, IncomingError, SecureChannel, SecureChannelError,
+};
+use crate::utilities::session_id::SessionId;
+
+fn channels(n: u16) -> Vec<SecureChannel> {
+    channels_in_session(n, &SessionId::new(b"session"))
+}
+
+fn channels_in_session(n: u16, session_id: &SessionId) -> Vec<SecureChannel> {
+    let identities: Vec<IdentityKey> = (0..n).map(|_| IdentityKey::generate()).collect();
+    let peers: BTreeMap<u16, _> = identities
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (i as u16 + 1, id.public_key().clone()))
+        .collect();
+    identities
+        .iter()
+        .enumerate()
+        .map(|(i, id)| SecureChannel::new(i as u16 + 1, id, &peers, session_id).unwrap())
+        .collect()
+}
+
+fn p2p(sender: u16, receiver: u16, body: &str) -> Msg<String> {
+    Msg {
+        sender,
+        receiver: Some(receiver),
+        body: body.to_string(),
+    }
+}
+
+fn broadcast(sender: u16, body: &str) -> Msg<String> {
+    Msg {
+        sender,
+        receiver: None,
+        body: body.to_string(),
+    }
+}
+
+#[test]
+fn p2p_message_is_encrypted() {
+    let channels = channels(3);
+    let sealed = channels[0].seal(p2p(1, 2, "secret share")).unwrap();
+    assert!(matches!(sealed.body, Envelope::Encrypted(_)));
+    let serialized = serde_json::to_string(&sealed).unwrap();
+    assert!(!serialized.contains("secret share"));
+
+    let opened = channels[1].open(sealed).unwrap();
+    assert_eq!(opened.sender, 1);
+    assert_eq!(opened.receiver, Some(2));
+    assert_eq!(opened.body, "secret share");
+}
+
+#[test]
+fn broadcast_message_is_not_encrypted() {
+    let channels = channels(3);
+    let sealed = channels[0].seal(broadcast(1, "commitment")).unwrap();
+    assert_eq!(sealed.body, Envelope::Plain("commitment".to_string()));
+    assert_eq!(channels[2].open(sealed).unwrap().body, "commitment");
+}
+
+#[test]
+fn message_cannot_be_read_by_other_party() {
+    let channels = channels(3);
+    let sealed = channels[0].seal(p2p(1, 2, "secret share")).unwrap();
+
+    let err = channels[2].open(sealed.clone()).unwrap_err();
+    assert!(matches!(
+        err,
+        SecureChannelError::WrongReceiver {
+            sender: 1,
+            receiver: 2
+        }
+    ));
+
+    // Relay redirects the message to party 3
+    let mut redirected = sealed;
+    redirected.receiver = Some(3);
+    let err = channels[2].open(redirected).unwrap_err();
+    assert!(matches!(err, SecureChannelError::Decrypt { sender: 1 }));
+}
+
+#[test]
+fn forged_or_altered_message_is_rejected() {
+    let channels = channels(3);
+
+    // Party 3 can't seal a message on behalf of party 1
+    let err = channels[2].seal(p2p(1, 2, "forged share")).unwrap_err();
+    assert!(matches!(
+        err,
+        SecureChannelError::WrongSender {
+            sender: 1,
+            party: 3
+        }
+    ));
+
+    // Party 3 seals a message on its own and claims it's sent by party 1
+    let mut forged = channels[2].seal(p2p(3, 2, "forged share")).unwrap();
+    forged.sender = 1;
+    let err = channels[1].open(forged).unwrap_err();
+    assert!(matches!(err, SecureChannelError::Decrypt { sender: 1 }));
+
+    let mut altered = channels[0].seal(p2p(1, 2, "secret share")).unwrap();
+    if let Envelope::Encrypted(body) = &mut altered.body {
+        let mut ciphertext = hex::decode(&body.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        body.ciphertext = hex::encode(ciphertext);
+    }
+    let err = channels[1].open(altered).unwrap_err();
+    assert!(matches!(err, SecureChannelError::Decrypt { sender: 1 }));
+
+    let plaintext = Msg {
+        sender: 1,
+        receiver: Some(2),
+        body: Envelope::Plain("secret share".to_string()),
+    };
+    let err = channels[1].open(plaintext).unwrap_err();
+    assert!(matches!(
+        err,
+        SecureChannelError::UnexpectedEnvelope { sender: 1 }
+    ));
+}
+
+#[test]
+fn replayed_message_is_rejected() {
+    let channels = channels(2);
+    let first = channels[0].seal(p2p(1, 2, "round 2 share")).unwrap();
+    let second = channels[0].seal(p2p(1, 2, "round 3 share")).unwrap();
+
+    assert_eq!(
+        channels[1].open(first.clone()).unwrap().body,
+        "round 2 share"
+    );
+    let err = channels[1].open(first).unwrap_err();
+    assert!(matches!(
+        err,
+        SecureChannelError::Replayed { sender: 1, seq: 0 }
+    ));
+
+    // Sequence number is authenticated, so it can't be bumped to get past the check
+    let mut bumped = second.clone();
+    if let Envelope::Encrypted(body) = &mut bumped.body {
+        body.seq = 5;
+    }
+    let err = channels[1].open(bumped).unwrap_err();
+    assert!(matches!(err, SecureChannelError::Decrypt { sender: 1 }));
+
+    assert_eq!(channels[1].open(second).unwrap().body, "round 3 share");
+}
+
+#[test]
+fn message_cannot_be_replayed_into_another_session() {
+    let identities = [IdentityKey::generate(), IdentityKey::generate()];
+    let peers: BTreeMap<u16, _> = (1..)
+        .zip(&identities)
+        .map(|(i, id)| (i, id.public_key().clone()))
+        .collect();
+    let channel = |i: u16, session_id: &[u8]| {
+        let session_id = SessionId::new(session_id);
+        SecureChannel::new(i, &identities[usize::from(i) - 1], &peers, &session_id).unwrap()
+    };
+
+    let sealed = channel(1, b"session a")
+        .seal(p2p(1, 2, "secret share"))
+        .unwrap();
+    let err = channel(2, b"session b").open(sealed.clone()).unwrap_err();
+    assert!(matches!(err, SecureChannelError::Decrypt { sender: 1 }));
+    assert_eq!(
+        channel(2, b"session a").open(sealed).unwrap().body,
+        "secret share"
+    );
+}
+
+#[test]
+fn adapters_wrap_transport() {
+    let channels = channels(2);
+    let (tx, rx) = mpsc::unbounded::<Msg<Envelope<String>>>();
+    let mut outgoing = Box::pin(channels[0].wrap_outgoing(tx));
+    let mut incoming = Box::pin(channels[1].wrap_incoming(rx.map(Ok::<_, Infallible>)));
+
+    block_on(async {
+        outgoing.send(p2p(1, 2, "secret share")).await.unwrap();
+        outgoing.send(broadcast(1, "commitment")).await.unwrap();
+        assert!(outgoing.send(p2p(1, 3, "unknown party")).await.is_err());
+
+        let received = incoming.next().await.unwrap().unwrap();
+        assert_eq!(received.body, "secret share");
+        let received = incoming.next().await.unwrap().unwrap();
+        assert_eq!(received.body, "commitment");
+    });
+
+    let (mut tx, rx) = mpsc::unbounded::<Msg<Envelope<String>>>();
+    let mut incoming = Box::pin(channels[1].wrap_incoming(rx.map(Ok::<_, Infallible>)));
+    block_on(async {
+        tx.send(Msg {
+            sender: 1,
+            receiver: Some(2),
+            body: Envelope::Plain("secret share".to_string()),
+        })
+        .await
+        .unwrap();
+        let err = incoming.next().await.unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            IncomingError::Channel(SecureChannelError::UnexpectedEnvelope { sender: 1 })
+        ));
+    });
+}
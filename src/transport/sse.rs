@@ -0,0 +1,366 @@
+//! Client of [relay](crate::relay) server (and `gg20_sm_manager` example)
+//!
+//! [join_computation] connects a state machine to a room of relay server: it returns a stream
+//! of incoming messages and a sink of outgoing messages that can be given straight to
+//! `AsyncProtocol`.
+//!
+//! Compared to `gg20_sm_client` example it replaces, client:
+//! * lets party use an index known in advance (see [PartyIndex]) instead of the one issued by
+//!   relay in order of joining the room,
+//! * reconnects when subscription is lost, and resumes from the last received message via
+//!   `Last-Event-ID` header,
+//! * retries failed requests with exponential [Backoff] (broadcast messages are deduplicated by
+//!   relay, so a retried message is delivered once),
+//! * reports failures as typed [SseError].
+//!
+//! ```rust,ignore
+//! let (i, incoming, outgoing) = join_computation::<ProtocolMessage>(
+//!     &address,
+//!     "keygen-42",
+//!     PartyIndex::Explicit(i),
+//!     ClientConfig::default(),
+//! )
+//! .await?;
+//! let incoming = incoming.fuse();
+//! tokio::pin!(incoming);
+//! tokio::pin!(outgoing);
+//! AsyncProtocol::new(keygen, incoming, outgoing).run().await?;
+//! ```
+
+use std::convert::TryInto;
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures::{future, Sink, Stream, StreamExt, TryStreamExt};
+use round_based::Msg;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+pub use surf::Url;
+
+/// Joins a computation in the room
+///
+/// Returns index of the party, stream of messages addressed to this party (broadcast messages
+/// and p2p messages with `receiver` equal to the index), and sink of outgoing messages.
+pub async fn join_computation<M>(
+    address: &Url,
+    room_id: &str,
+    index: PartyIndex,
+    config: ClientConfig,
+) -> Result<
+    (
+        u16,
+        impl Stream<Item = Result<Msg<M>, SseError>>,
+        impl Sink<Msg<M>, Error = SseError>,
+    ),
+    SseError,
+>
+where
+    M: Serialize + DeserializeOwned,
+{
+    let client = RelayClient::new(address, room_id, config)?;
+
+    // Subscribe before obtaining an index, so the room doesn't expire meanwhile
+    let incoming = client
+        .subscribe(None)
+        .await?
+        .and_then(|(_id, msg)| async move {
+            serde_json::from_str::<Msg<M>>(&msg).map_err(SseError::Deserialize)
+        });
+
+    let index = match index {
+        PartyIndex::Explicit(0) => return Err(SseError::InvalidPartyIndex),
+        PartyIndex::Explicit(i) => i,
+        PartyIndex::IssuedByRelay => client.issue_index().await?,
+    };
+
+    // Ignore incoming messages addressed to someone else
+    let incoming = incoming.try_filter(move |msg| {
+        future::ready(msg.sender != index && (msg.receiver.is_none() || msg.receiver == Some(index)))
+    });
+
+    let outgoing = futures::sink::unfold(client, |client, message: Msg<M>| async move {
+        let serialized = serde_json::to_string(&message).map_err(SseError::Serialize)?;
+        client.broadcast(&serialized).await?;
+        Ok::<_, SseError>(client)
+    });
+
+    Ok((index, incoming, outgoing))
+}
+
+/// Determines index of the party in the computation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartyIndex {
+    /// Index known in advance, e.g. `own_party_index` of the local key. Must be non-zero.
+    Explicit(u16),
+    /// Index issued by relay: parties are numbered in the order they join the room
+    IssuedByRelay,
+}
+
+/// Exponential backoff between retries
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound of the delay
+    pub max_delay: Duration,
+    /// Number of consecutive failed attempts after which client gives up
+    pub max_attempts: u32,
+}
+
+impl Backoff {
+    /// Delay before retrying after `attempt` consecutive failures (starting from 1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.initial_delay
+            .checked_mul(factor)
+            .map(|delay| delay.min(self.max_delay))
+            .unwrap_or(self.max_delay)
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            max_attempts: 10,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ClientConfig {
+    /// Bearer token of the room, see [RelayConfig::with_room_token](crate::relay::RelayConfig::with_room_token)
+    pub token: Option<String>,
+    pub backoff: Backoff,
+}
+
+/// Client of a single room of relay server
+#[derive(Clone)]
+pub struct RelayClient {
+    http_client: surf::Client,
+    token: Option<String>,
+    backoff: Backoff,
+}
+
+impl RelayClient {
+    pub fn new(address: &Url, room_id: &str, config: ClientConfig) -> Result<Self, SseError> {
+        let base_url = address
+            .join(&format!("rooms/{}/", room_id))
+            .map_err(|e| SseError::InvalidAddress(e.to_string()))?;
+        let http_client = surf::Config::new()
+            .set_base_url(base_url)
+            .set_timeout(None)
+            .try_into()
+            .map_err(|e: <surf::Config as TryInto<surf::Client>>::Error| {
+                SseError::InvalidAddress(e.to_string())
+            })?;
+        Ok(Self {
+            http_client,
+            token: config.token,
+            backoff: config.backoff,
+        })
+    }
+
+    /// Obtains a unique index in the room
+    pub async fn issue_index(&self) -> Result<u16, SseError> {
+        let mut response = self
+            .send(|| self.http_client.post("issue_unique_idx"))
+            .await?;
+        let issued: IssuedUniqueIdx = response
+            .body_json()
+            .await
+            .map_err(|e| SseError::MalformedResponse(e.to_string()))?;
+        Ok(issued.unique_idx)
+    }
+
+    /// Publishes a message in the room
+    ///
+    /// Request carries a random idempotency key which stays the same across retries, so the
+    /// message is published once even if relay accepted it but the response was lost.
+    pub async fn broadcast(&self, message: &str) -> Result<(), SseError> {
+        let idempotency_key = hex::encode(rand::random::<[u8; 16]>());
+        self.send(|| {
+            self.http_client
+                .post("broadcast")
+                .header("Idempotency-Key", idempotency_key.as_str())
+                .body(message)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Subscribes to messages published in the room after `last_event_id` (or to all messages
+    /// if it's `None`)
+    ///
+    /// Stream yields messages along with their ids. Lost connection is re-established
+    /// transparently, stream resumes from the last received message. Stream ends with an
+    /// error if relay can't be reached in [max_attempts](Backoff::max_attempts), or if some of
+    /// the messages are no longer in the room history (see [SseError::HistoryTruncated]).
+    pub async fn subscribe(
+        &self,
+        last_event_id: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<(u64, String), SseError>>, SseError> {
+        let events = self.connect(last_event_id).await?;
+        let subscription = Subscription {
+            client: self.clone(),
+            events: Some(events),
+            last_event_id,
+            failed_attempts: 0,
+        };
+        Ok(futures::stream::unfold(
+            Some(subscription),
+            |subscription| async move {
+                let mut subscription = subscription?;
+                match subscription.next_message().await {
+                    Ok(msg) => Some((Ok(msg), Some(subscription))),
+                    Err(err) => Some((Err(err), None)),
+                }
+            },
+        ))
+    }
+
+    async fn connect(&self, last_event_id: Option<u64>) -> Result<Events, SseError> {
+        let response = self
+            .send(|| {
+                let request = self.http_client.get("subscribe");
+                match last_event_id {
+                    Some(id) => request.header("Last-Event-ID", id.to_string()),
+                    None => request,
+                }
+            })
+            .await?;
+        Ok(Box::pin(async_sse::decode(response)))
+    }
+
+    /// Sends a request, retrying it if relay is unreachable or responds with server error
+    async fn send(
+        &self,
+        request: impl Fn() -> surf::RequestBuilder,
+    ) -> Result<surf::Response, SseError> {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let request = match &self.token {
+                Some(token) => request().header("Authorization", format!("Bearer {}", token)),
+                None => request(),
+            };
+            let err: BoxedError = match request.await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) if !response.status().is_server_error() => {
+                    return Err(SseError::Rejected {
+                        status: response.status().into(),
+                    })
+                }
+                Ok(response) => format!("relay responded with {}", response.status()).into(),
+                Err(err) => err.into_inner().into(),
+            };
+            if attempt >= self.backoff.max_attempts {
+                return Err(SseError::Unreachable {
+                    attempts: attempt,
+                    source: err,
+                });
+            }
+            tokio::time::sleep(self.backoff.delay(attempt)).await;
+        }
+    }
+}
+
+type Events = Pin<Box<async_sse::Decoder<surf::Response>>>;
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+struct Subscription {
+    client: RelayClient,
+    events: Option<Events>,
+    last_event_id: Option<u64>,
+    failed_attempts: u32,
+}
+
+impl Subscription {
+    async fn next_message(&mut self) -> Result<(u64, String), SseError> {
+        loop {
+            let events = match &mut self.events {
+                Some(events) => events,
+                None => {
+                    let events = self.client.connect(self.last_event_id).await?;
+                    self.events.insert(events)
+                }
+            };
+            let err: BoxedError = match events.next().await {
+                Some(Ok(async_sse::Event::Message(msg))) if msg.name() == "new-message" => {
+                    let id = msg
+                        .id()
+                        .as_deref()
+                        .and_then(|id| id.parse::<u64>().ok())
+                        .ok_or_else(|| SseError::MalformedResponse("event id is missing".into()))?;
+                    let msg = String::from_utf8(msg.into_bytes()).map_err(|_| {
+                        SseError::MalformedResponse("message is not valid UTF-8".into())
+                    })?;
+                    self.last_event_id = Some(id);
+                    self.failed_attempts = 0;
+                    return Ok((id, msg));
+                }
+                Some(Ok(async_sse::Event::Message(msg))) if msg.name() == "history-truncated" => {
+                    let first_available = std::str::from_utf8(msg.data())
+                        .ok()
+                        .and_then(|id| id.parse().ok())
+                        .ok_or_else(|| {
+                            SseError::MalformedResponse(
+                                "history-truncated event is malformed".into(),
+                            )
+                        })?;
+                    return Err(SseError::HistoryTruncated { first_available });
+                }
+                // ignore other types of events
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => err.into_inner().into(),
+                None => "relay closed the connection".into(),
+            };
+
+            self.events = None;
+            self.failed_attempts += 1;
+            if self.failed_attempts >= self.client.backoff.max_attempts {
+                return Err(SseError::Unreachable {
+                    attempts: self.failed_attempts,
+                    source: err,
+                });
+            }
+            tokio::time::sleep(self.client.backoff.delay(self.failed_attempts)).await;
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct IssuedUniqueIdx {
+    unique_idx: u16,
+}
+
+/// Error of relay client
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SseError {
+    #[error("invalid relay address: {0}")]
+    InvalidAddress(String),
+    #[error("explicit party index must be non-zero")]
+    InvalidPartyIndex,
+    #[error("relay is unreachable after {attempts} attempts")]
+    Unreachable {
+        attempts: u32,
+        #[source]
+        source: BoxedError,
+    },
+    #[error("relay rejected request with status {status}")]
+    Rejected { status: u16 },
+    #[error("relay sent malformed response: {0}")]
+    MalformedResponse(String),
+    #[error("room history is truncated, the oldest available message is {first_available}")]
+    HistoryTruncated { first_available: u64 },
+    #[error("serialize message")]
+    Serialize(#[source] serde_json::Error),
+    #[error("deserialize message")]
+    Deserialize(#[source] serde_json::Error),
+}
+
+#[cfg(all(test, feature = "relay"))]
+mod test;
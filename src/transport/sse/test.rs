@@ -0,0 +1,359 @@
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::{StreamExt, TryStreamExt};
+use round_based::async_runtime::AsyncProtocol;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use super::{join_computation, Backoff, ClientConfig, PartyIndex, RelayClient, SseError};
+use crate::protocols::gg_2020::state_machine::keygen::Keygen;
+use crate::relay::test::{free_port, spawn_relay, spawn_relay_on_port};
+use crate::relay::RelayConfig;
+use crate::utilities::session_id::SessionId;
+
+fn fast_backoff(max_attempts: u32) -> ClientConfig {
+    ClientConfig {
+        token: None,
+        backoff: Backoff {
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(100),
+            max_attempts,
+        },
+    }
+}
+
+/// TCP proxy in front of relay that lets tests break connections between clients and relay
+struct Proxy {
+    relay: SocketAddr,
+    connections: Mutex<Vec<JoinHandle<()>>>,
+    drop_next_response: AtomicBool,
+    last_event_ids: Mutex<Vec<u64>>,
+}
+
+async fn spawn_proxy(relay: &super::Url) -> (super::Url, Arc<Proxy>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}/", listener.local_addr().unwrap())
+        .parse()
+        .unwrap();
+    let proxy = Arc::new(Proxy {
+        relay: relay.socket_addrs(|| None).unwrap()[0],
+        connections: Mutex::new(vec![]),
+        drop_next_response: AtomicBool::new(false),
+        last_event_ids: Mutex::new(vec![]),
+    });
+    let accepting = proxy.clone();
+    tokio::spawn(async move {
+        while let Ok((client, _)) = listener.accept().await {
+            let connection = tokio::spawn(accepting.clone().forward(client));
+            accepting.connections.lock().unwrap().push(connection);
+        }
+    });
+    (address, proxy)
+}
+
+impl Proxy {
+    /// Closes all currently open connections
+    fn cut_connections(&self) {
+        for connection in self.connections.lock().unwrap().drain(..) {
+            connection.abort();
+        }
+    }
+
+    /// Closes connection instead of delivering the next response of relay
+    fn drop_next_response(&self) {
+        self.drop_next_response.store(true, Ordering::SeqCst);
+    }
+
+    fn last_event_ids(&self) -> Vec<u64> {
+        self.last_event_ids.lock().unwrap().clone()
+    }
+
+    async fn forward(self: Arc<Self>, client: TcpStream) {
+        let upstream = match TcpStream::connect(self.relay).await {
+            Ok(upstream) => upstream,
+            Err(_) => return,
+        };
+        let (mut client_rx, mut client_tx) = client.into_split();
+        let (mut upstream_rx, mut upstream_tx) = upstream.into_split();
+        let requests = async {
+            let mut buf = vec![0; 4096];
+            loop {
+                let n = match client_rx.read(&mut buf).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => n,
+                };
+                self.record_last_event_id(&buf[..n]);
+                if upstream_tx.write_all(&buf[..n]).await.is_err() {
+                    return;
+                }
+            }
+        };
+        let responses = async {
+            let mut buf = vec![0; 4096];
+            loop {
+                let n = match upstream_rx.read(&mut buf).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => n,
+                };
+                if self.drop_next_response.swap(false, Ordering::SeqCst) {
+                    return;
+                }
+                if client_tx.write_all(&buf[..n]).await.is_err() {
+                    return;
+                }
+            }
+        };
+        tokio::select! {
+            _ = requests => (),
+            _ = responses => (),
+        }
+    }
+
+    fn record_last_event_id(&self, request: &[u8]) {
+        let request = String::from_utf8_lossy(request);
+        let ids = request
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(name, _)| name.trim().eq_ignore_ascii_case("last-event-id"))
+            .filter_map(|(_, value)| value.trim().parse::<u64>().ok());
+        self.last_event_ids.lock().unwrap().extend(ids);
+    }
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_max_delay() {
+    let backoff = Backoff {
+        initial_delay: Duration::from_millis(100),
+        max_delay: Duration::from_secs(1),
+        max_attempts: 100,
+    };
+    assert_eq!(backoff.delay(1), Duration::from_millis(100));
+    assert_eq!(backoff.delay(2), Duration::from_millis(200));
+    assert_eq!(backoff.delay(4), Duration::from_millis(800));
+    assert_eq!(backoff.delay(5), Duration::from_secs(1));
+    assert_eq!(backoff.delay(100), Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn resumes_from_last_event_id() {
+    let address = spawn_relay(RelayConfig::default()).await;
+    let client = RelayClient::new(&address, "room", ClientConfig::default()).unwrap();
+    for msg in ["a", "b", "c"].iter() {
+        client.broadcast(msg).await.unwrap();
+    }
+
+    let messages: Vec<_> = client
+        .subscribe(Some(0))
+        .await
+        .unwrap()
+        .take(2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(messages, vec![(1, "b".to_string()), (2, "c".to_string())]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retried_broadcast_is_published_once() {
+    let address = spawn_relay(RelayConfig::default()).await;
+    let (proxy_address, proxy) = spawn_proxy(&address).await;
+
+    // relay publishes "a", but the client never learns it and sends the request again
+    proxy.drop_next_response();
+    let client = RelayClient::new(&proxy_address, "room", fast_backoff(3)).unwrap();
+    client.broadcast("a").await.unwrap();
+    client.broadcast("b").await.unwrap();
+
+    let client = RelayClient::new(&address, "room", ClientConfig::default()).unwrap();
+    let messages: Vec<_> = client
+        .subscribe(None)
+        .await
+        .unwrap()
+        .take(2)
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(messages, vec![(0, "a".to_string()), (1, "b".to_string())]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keygen_resumes_after_connections_are_cut() {
+    let address = spawn_relay(RelayConfig::default()).await;
+    let (proxy_address, proxy) = spawn_proxy(&address).await;
+    let (t, n) = (1, 3);
+    let session_id = SessionId::random();
+
+    // observer is connected to relay directly and cuts parties off in the middle of keygen
+    let observer = RelayClient::new(&address, "keygen", ClientConfig::default()).unwrap();
+    let observed = observer.subscribe(None).await.unwrap();
+    let cutter = {
+        let proxy = proxy.clone();
+        tokio::spawn(async move {
+            let _: Vec<_> = observed.take(4).try_collect().await.unwrap();
+            proxy.cut_connections();
+        })
+    };
+
+    let mut parties = vec![];
+    for i in 1..=n {
+        let address = proxy_address.clone();
+        parties.push(tokio::spawn(async move {
+            let (_, incoming, outgoing) = join_computation(
+                &address,
+                "keygen",
+                PartyIndex::Explicit(i),
+                fast_backoff(20),
+            )
+            .await
+            .unwrap();
+            let incoming = incoming.fuse();
+            tokio::pin!(incoming);
+            tokio::pin!(outgoing);
+            let keygen = Keygen::new(i, t, n, session_id).unwrap();
+            AsyncProtocol::new(keygen, incoming, outgoing)
+                .run()
+                .await
+                .map_err(|e| e.to_string())
+        }));
+    }
+
+    for party in parties {
+        party.await.unwrap().unwrap();
+    }
+    cutter.await.unwrap();
+    assert!(proxy.last_event_ids().iter().any(|&id| id > 0));
+}
+
+#[tokio::test]
+async fn reports_truncated_history() {
+    let address = spawn_relay(RelayConfig {
+        history_limit: 2,
+        ..Default::default()
+    })
+    .await;
+    let client = RelayClient::new(&address, "room", ClientConfig::default()).unwrap();
+    for msg in ["a", "b", "c", "d"].iter() {
+        client.broadcast(msg).await.unwrap();
+    }
+
+    let messages = client.subscribe(Some(0)).await.unwrap();
+    tokio::pin!(messages);
+    assert!(matches!(
+        messages.next().await,
+        Some(Err(SseError::HistoryTruncated { first_available: 2 }))
+    ));
+    assert!(messages.next().await.is_none());
+}
+
+#[tokio::test]
+async fn reports_history_lost_by_relay() {
+    let address = spawn_relay(RelayConfig::default()).await;
+    let client = RelayClient::new(&address, "room", ClientConfig::default()).unwrap();
+    client.broadcast("a").await.unwrap();
+
+    // e.g. relay was restarted without persistence after client had received 5 messages
+    let messages = client.subscribe(Some(4)).await.unwrap();
+    tokio::pin!(messages);
+    assert!(matches!(
+        messages.next().await,
+        Some(Err(SseError::HistoryTruncated { first_available: 1 }))
+    ));
+    assert!(messages.next().await.is_none());
+}
+
+#[tokio::test]
+async fn rejected_requests_are_not_retried() {
+    let address = spawn_relay(RelayConfig::default().with_room_token("room", "token")).await;
+    let client = RelayClient::new(&address, "room", fast_backoff(3)).unwrap();
+    assert!(matches!(
+        client.broadcast("hello").await,
+        Err(SseError::Rejected { status: 401 })
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn retries_until_relay_is_up() {
+    let port = free_port();
+    let address: super::Url = format!("http://127.0.0.1:{}/", port).parse().unwrap();
+    let client = RelayClient::new(&address, "room", fast_backoff(50)).unwrap();
+
+    let relay = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        spawn_relay_on_port(port, RelayConfig::default()).await
+    });
+    client.broadcast("hello").await.unwrap();
+    relay.await.unwrap();
+
+    let mut messages = Box::pin(client.subscribe(None).await.unwrap());
+    assert_eq!(
+        messages.next().await.unwrap().unwrap(),
+        (0, "hello".to_string())
+    );
+}
+
+#[tokio::test]
+async fn gives_up_when_relay_is_unreachable() {
+    let address: super::Url = format!("http://127.0.0.1:{}/", free_port())
+        .parse()
+        .unwrap();
+    let client = RelayClient::new(&address, "room", fast_backoff(3)).unwrap();
+    assert!(matches!(
+        client.issue_index().await,
+        Err(SseError::Unreachable { attempts: 3, .. })
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keygen_with_explicit_indexes() {
+    let address = spawn_relay(RelayConfig::default()).await;
+    let (t, n) = (1, 3);
+    let session_id = SessionId::random();
+
+    // parties join the room in reverse order, yet keep their indexes
+    let mut parties = vec![];
+    for i in (1..=n).rev() {
+        let address = address.clone();
+        parties.push(tokio::spawn(async move {
+            let (index, incoming, outgoing) = join_computation(
+                &address,
+                "keygen",
+                PartyIndex::Explicit(i),
+                ClientConfig::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(index, i);
+            let incoming = incoming.fuse();
+            tokio::pin!(incoming);
+            tokio::pin!(outgoing);
+            let keygen = Keygen::new(i, t, n, session_id).unwrap();
+            AsyncProtocol::new(keygen, incoming, outgoing)
+                .run()
+                .await
+                .map_err(|e| e.to_string())
+        }));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    for (party, i) in parties.into_iter().zip((1..=n).rev()) {
+        let local_key = party.await.unwrap().unwrap();
+        assert_eq!(local_key.own_party_index, usize::from(i));
+    }
+}
+
+#[tokio::test]
+async fn zero_explicit_index_is_rejected() {
+    let address = spawn_relay(RelayConfig::default()).await;
+    let result = join_computation::<()>(
+        &address,
+        "room",
+        PartyIndex::Explicit(0),
+        ClientConfig::default(),
+    )
+    .await;
+    assert!(matches!(result, Err(SseError::InvalidPartyIndex)));
+}
@@ -0,0 +1,576 @@
+//! Direct peer-to-peer transport over TCP secured by Noise protocol
+//!
+//! Parties talk to each other directly, without a relay. Every party has a static
+//! [NoiseKeypair] and knows a static list of [Peer]s: address and static public key of every
+//! other party. [connect] establishes a `Noise_XX_25519_ChaChaPoly_BLAKE2s` channel with every
+//! peer: party dials all parties with lower indexes and accepts connections from parties with
+//! higher indexes. Handshake mutually authenticates parties by their static keys, so a message
+//! received over a channel is known to come from the party on the other end, and its `sender`
+//! field is checked against it.
+//!
+//! ```rust,ignore
+//! let listener = TcpListener::bind(own_address).await?;
+//! let (incoming, outgoing) = tcp::connect(i, &keypair, listener, &peers, TcpConfig::default()).await?;
+//! let incoming = incoming.fuse();
+//! tokio::pin!(incoming);
+//! tokio::pin!(outgoing);
+//! AsyncProtocol::new(keygen, incoming, outgoing).run().await?;
+//! ```
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::FuturesUnordered;
+use futures::{future, stream, Sink, Stream, StreamExt};
+use round_based::Msg;
+use serde::{de::DeserializeOwned, Serialize};
+use snow::params::{DHChoice, NoiseParams};
+use snow::resolvers::{CryptoResolver, DefaultResolver};
+use snow::{HandshakeState, StatelessTransportState};
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use zeroize::Zeroizing;
+
+use crate::utilities::fingerprint::Fingerprint;
+
+const NOISE_PARAMS: &str = "Noise_XX_25519_ChaChaPoly_BLAKE2s";
+const PROLOGUE: &[u8] = b"multi-party-ecdsa/tcp/v1";
+/// Maximum length of Noise message
+const MAX_NOISE_MESSAGE: usize = 65535;
+const TAG_LEN: usize = 16;
+/// Maximum length of plaintext that fits into a single Noise message
+const MAX_CHUNK: usize = MAX_NOISE_MESSAGE - TAG_LEN;
+const KEY_LEN: usize = 32;
+
+/// Static Curve25519 keypair of a party
+pub struct NoiseKeypair {
+    secret: Zeroizing<Vec<u8>>,
+    public: [u8; KEY_LEN],
+}
+
+impl NoiseKeypair {
+    pub fn generate() -> Self {
+        let keypair = snow::Builder::new(noise_params())
+            .generate_keypair()
+            .expect("default resolver supports Curve25519");
+        let mut public = [0u8; KEY_LEN];
+        public.copy_from_slice(&keypair.public);
+        Self {
+            secret: Zeroizing::new(keypair.private),
+            public,
+        }
+    }
+
+    pub fn from_secret(secret: [u8; KEY_LEN]) -> Self {
+        let secret = Zeroizing::new(secret.to_vec());
+        let mut dh = DefaultResolver
+            .resolve_dh(&DHChoice::Curve25519)
+            .expect("default resolver supports Curve25519");
+        dh.set(&secret);
+        let mut public = [0u8; KEY_LEN];
+        public.copy_from_slice(dh.pubkey());
+        Self { secret, public }
+    }
+
+    pub fn public_key(&self) -> &[u8; KEY_LEN] {
+        &self.public
+    }
+}
+
+impl fmt::Debug for NoiseKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("NoiseKeypair")
+            .field("secret", &Fingerprint::of_bytes(&self.secret))
+            .field("public", &hex::encode(self.public))
+            .finish()
+    }
+}
+
+/// Another party of the computation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Peer {
+    pub address: SocketAddr,
+    /// Static public key of the party, see [NoiseKeypair::public_key]
+    pub static_public_key: [u8; KEY_LEN],
+}
+
+#[derive(Clone, Debug)]
+pub struct TcpConfig {
+    /// Time given to establish channels with all the peers
+    pub connect_timeout: Duration,
+    /// Delay between attempts to dial a peer which isn't listening yet
+    pub redial_interval: Duration,
+    /// Time given to a single inbound connection to complete the handshake
+    ///
+    /// Inbound handshakes run concurrently, and a connection that stalls is dropped once the
+    /// timeout expires, so it can't hold up channels with other peers.
+    pub handshake_timeout: Duration,
+    /// Maximum size of a serialized message received from a peer
+    pub max_message_size: usize,
+}
+
+impl Default for TcpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(60),
+            redial_interval: Duration::from_millis(100),
+            handshake_timeout: Duration::from_secs(10),
+            max_message_size: 100 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum TcpError {
+    #[error("i/o error")]
+    Io(#[source] io::Error),
+    #[error("noise protocol error")]
+    Noise(#[source] snow::Error),
+    #[error("timed out establishing channels with peers")]
+    ConnectTimeout,
+    #[error("handshake timed out")]
+    HandshakeTimeout,
+    /// Peer presented static key that doesn't match the one in the peers list
+    #[error("party {party} failed authentication")]
+    PeerAuthentication { party: u16 },
+    #[error("handshake message is malformed")]
+    MalformedHandshake,
+    #[error("party {party} is not in the peers list")]
+    UnknownParty { party: u16 },
+    #[error("message from party {party} exceeds size limit")]
+    MessageTooLarge { party: u16 },
+    /// Message received from the party claims to be sent by another party
+    #[error("message received from party {party} claims to be sent by party {sender}")]
+    WrongSender { party: u16, sender: u16 },
+    #[error("message from party {sender} is addressed to party {receiver}")]
+    WrongReceiver { sender: u16, receiver: u16 },
+    #[error("serialize message: {0}")]
+    Serialize(#[source] serde_json::Error),
+    #[error("deserialize message from party {party}: {source}")]
+    Deserialize {
+        party: u16,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Establishes channels with all the peers
+///
+/// `peers` maps index of every party to its address and static key. Entry of `party_index`
+/// itself, if present, is ignored: the party accepts connections on `listener`.
+///
+/// Returns stream of messages addressed to this party and sink of outgoing messages. Broadcast
+/// messages are sent to every peer over its channel.
+pub async fn connect<M>(
+    party_index: u16,
+    keypair: &NoiseKeypair,
+    listener: TcpListener,
+    peers: &BTreeMap<u16, Peer>,
+    config: TcpConfig,
+) -> Result<
+    (
+        impl Stream<Item = Result<Msg<M>, TcpError>>,
+        impl Sink<Msg<M>, Error = TcpError>,
+    ),
+    TcpError,
+>
+where
+    M: Serialize + DeserializeOwned + Send + 'static,
+{
+    let peers: BTreeMap<u16, Peer> = peers
+        .iter()
+        .filter(|(&j, _)| j != party_index)
+        .map(|(&j, peer)| (j, peer.clone()))
+        .collect();
+
+    let channels = tokio::time::timeout(
+        config.connect_timeout,
+        future::try_join(
+            accept_all(
+                party_index,
+                keypair,
+                &listener,
+                &peers,
+                config.handshake_timeout,
+            ),
+            dial_all(party_index, keypair, &peers, config.redial_interval),
+        ),
+    )
+    .await
+    .map_err(|_| TcpError::ConnectTimeout)??;
+
+    let mut readers = vec![];
+    let mut writers = BTreeMap::new();
+    for (party, channel) in channels.0.into_iter().chain(channels.1) {
+        let (read, write) = channel.stream.into_split();
+        readers.push(ChannelReader {
+            party,
+            read,
+            noise: channel.noise.clone(),
+            nonce: 0,
+            max_message_size: config.max_message_size,
+        });
+        writers.insert(
+            party,
+            ChannelWriter {
+                write,
+                noise: channel.noise,
+                nonce: 0,
+            },
+        );
+    }
+
+    let incoming = stream::select_all(readers.into_iter().map(|reader| {
+        Box::pin(stream::unfold(Some(reader), move |reader| async move {
+            let mut reader = reader?;
+            match reader.receive::<M>(party_index).await {
+                Ok(Some(msg)) => Some((Ok(msg), Some(reader))),
+                Ok(None) => None,
+                Err(err) => Some((Err(err), None)),
+            }
+        }))
+    }));
+
+    let outgoing = futures::sink::unfold(writers, |mut writers, msg: Msg<M>| async move {
+        let serialized = serde_json::to_vec(&msg).map_err(TcpError::Serialize)?;
+        match msg.receiver {
+            None => {
+                for writer in writers.values_mut() {
+                    writer.send(&serialized).await?;
+                }
+            }
+            Some(receiver) => {
+                writers
+                    .get_mut(&receiver)
+                    .ok_or(TcpError::UnknownParty { party: receiver })?
+                    .send(&serialized)
+                    .await?
+            }
+        }
+        Ok::<_, TcpError>(writers)
+    });
+
+    Ok((incoming, outgoing))
+}
+
+struct Channel {
+    stream: TcpStream,
+    noise: Arc<StatelessTransportState>,
+}
+
+/// Accepts connections from all parties with higher indexes
+///
+/// Handshakes of accepted connections run concurrently, each under `handshake_timeout`.
+async fn accept_all(
+    party_index: u16,
+    keypair: &NoiseKeypair,
+    listener: &TcpListener,
+    peers: &BTreeMap<u16, Peer>,
+    handshake_timeout: Duration,
+) -> Result<Vec<(u16, Channel)>, TcpError> {
+    let expected: BTreeMap<u16, &Peer> = peers
+        .iter()
+        .filter(|(&j, _)| j > party_index)
+        .map(|(&j, peer)| (j, peer))
+        .collect();
+    let expected = &expected;
+    let mut channels = BTreeMap::new();
+    let mut handshakes = FuturesUnordered::new();
+    while channels.len() < expected.len() {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (mut stream, address) = accepted.map_err(TcpError::Io)?;
+                handshakes.push(async move {
+                    let result = tokio::time::timeout(
+                        handshake_timeout,
+                        respond(keypair, &mut stream, expected),
+                    )
+                    .await
+                    .unwrap_or(Err(TcpError::HandshakeTimeout));
+                    (stream, address, result)
+                });
+            }
+            Some((stream, address, result)) = handshakes.next(), if !handshakes.is_empty() => {
+                // Connection that fails to authenticate as an expected peer doesn't abort the
+                // computation: it could be opened by anyone who can reach the listener
+                match result {
+                    Ok((party, _)) if channels.contains_key(&party) => log::warn!(
+                        "rejected second connection of party {} from {}",
+                        party,
+                        address
+                    ),
+                    Ok((party, noise)) => {
+                        channels.insert(
+                            party,
+                            Channel {
+                                stream,
+                                noise: Arc::new(noise),
+                            },
+                        );
+                    }
+                    Err(err) => log::warn!("rejected connection from {}: {}", address, err),
+                }
+            }
+        }
+    }
+    Ok(channels.into_iter().collect())
+}
+
+/// Dials all parties with lower indexes
+async fn dial_all(
+    party_index: u16,
+    keypair: &NoiseKeypair,
+    peers: &BTreeMap<u16, Peer>,
+    redial_interval: Duration,
+) -> Result<Vec<(u16, Channel)>, TcpError> {
+    let dials = peers
+        .iter()
+        .filter(|(&j, _)| j < party_index)
+        .map(|(&j, peer)| async move {
+            let mut stream = loop {
+                match TcpStream::connect(peer.address).await {
+                    Ok(stream) => break stream,
+                    Err(_) => tokio::time::sleep(redial_interval).await,
+                }
+            };
+            let noise = initiate(party_index, keypair, &mut stream, j, peer).await?;
+            Ok::<_, TcpError>((
+                j,
+                Channel {
+                    stream,
+                    noise: Arc::new(noise),
+                },
+            ))
+        });
+    future::try_join_all(dials).await
+}
+
+/// Performs handshake as initiator, sending our index in the last handshake message
+async fn initiate(
+    party_index: u16,
+    keypair: &NoiseKeypair,
+    stream: &mut TcpStream,
+    party: u16,
+    peer: &Peer,
+) -> Result<StatelessTransportState, TcpError> {
+    let mut noise = handshake_builder(keypair)
+        .build_initiator()
+        .map_err(TcpError::Noise)?;
+    let mut buf = vec![0u8; MAX_NOISE_MESSAGE];
+
+    // -> e
+    let len = noise.write_message(&[], &mut buf).map_err(TcpError::Noise)?;
+    write_frame(stream, &buf[..len]).await?;
+    // <- e, ee, s, es
+    let frame = read_frame(stream).await?;
+    noise.read_message(&frame, &mut buf).map_err(TcpError::Noise)?;
+    if noise.get_remote_static() != Some(&peer.static_public_key[..]) {
+        return Err(TcpError::PeerAuthentication { party });
+    }
+    // -> s, se
+    let len = noise
+        .write_message(&party_index.to_be_bytes(), &mut buf)
+        .map_err(TcpError::Noise)?;
+    write_frame(stream, &buf[..len]).await?;
+
+    into_transport_mode(noise)
+}
+
+/// Performs handshake as responder, returns index of authenticated initiator
+async fn respond(
+    keypair: &NoiseKeypair,
+    stream: &mut TcpStream,
+    expected: &BTreeMap<u16, &Peer>,
+) -> Result<(u16, StatelessTransportState), TcpError> {
+    let mut noise = handshake_builder(keypair)
+        .build_responder()
+        .map_err(TcpError::Noise)?;
+    let mut buf = vec![0u8; MAX_NOISE_MESSAGE];
+
+    // -> e
+    let frame = read_frame(stream).await?;
+    noise.read_message(&frame, &mut buf).map_err(TcpError::Noise)?;
+    // <- e, ee, s, es
+    let len = noise.write_message(&[], &mut buf).map_err(TcpError::Noise)?;
+    write_frame(stream, &buf[..len]).await?;
+    // -> s, se
+    let frame = read_frame(stream).await?;
+    let len = noise.read_message(&frame, &mut buf).map_err(TcpError::Noise)?;
+    let party = u16::from_be_bytes(
+        buf[..len]
+            .try_into()
+            .map_err(|_| TcpError::MalformedHandshake)?,
+    );
+    let peer = expected
+        .get(&party)
+        .ok_or(TcpError::UnknownParty { party })?;
+    if noise.get_remote_static() != Some(&peer.static_public_key[..]) {
+        return Err(TcpError::PeerAuthentication { party });
+    }
+
+    Ok((party, into_transport_mode(noise)?))
+}
+
+fn noise_params() -> NoiseParams {
+    NOISE_PARAMS.parse().expect("noise params are valid")
+}
+
+fn handshake_builder(keypair: &NoiseKeypair) -> snow::Builder {
+    snow::Builder::new(noise_params())
+        .local_private_key(&keypair.secret)
+        .prologue(PROLOGUE)
+}
+
+fn into_transport_mode(noise: HandshakeState) -> Result<StatelessTransportState, TcpError> {
+    noise
+        .into_stateless_transport_mode()
+        .map_err(TcpError::Noise)
+}
+
+struct ChannelWriter {
+    write: OwnedWriteHalf,
+    noise: Arc<StatelessTransportState>,
+    nonce: u64,
+}
+
+impl ChannelWriter {
+    /// Sends a message: length header followed by chunks of the message, each chunk in a
+    /// separate Noise message
+    async fn send(&mut self, message: &[u8]) -> Result<(), TcpError> {
+        let len: u32 = message
+            .len()
+            .try_into()
+            .map_err(|_| TcpError::Serialize(serde::ser::Error::custom("message is too large")))?;
+        self.send_chunk(&len.to_be_bytes()).await?;
+        for chunk in message.chunks(MAX_CHUNK) {
+            self.send_chunk(chunk).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_chunk(&mut self, chunk: &[u8]) -> Result<(), TcpError> {
+        let mut buf = vec![0u8; chunk.len() + TAG_LEN];
+        let len = self
+            .noise
+            .write_message(self.nonce, chunk, &mut buf)
+            .map_err(TcpError::Noise)?;
+        self.nonce += 1;
+        write_frame(&mut self.write, &buf[..len]).await
+    }
+}
+
+struct ChannelReader {
+    party: u16,
+    read: OwnedReadHalf,
+    noise: Arc<StatelessTransportState>,
+    nonce: u64,
+    max_message_size: usize,
+}
+
+impl ChannelReader {
+    /// Receives next message, returns `None` if peer has closed the connection
+    ///
+    /// Peer closes the connection once it has completed the protocol, which doesn't mean that
+    /// we have, so it's not an error as long as it happens between messages.
+    async fn receive<M: DeserializeOwned>(
+        &mut self,
+        party_index: u16,
+    ) -> Result<Option<Msg<M>>, TcpError> {
+        let header = match self.receive_chunk().await {
+            Ok(header) => header,
+            Err(TcpError::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        };
+        let len: [u8; 4] = header[..]
+            .try_into()
+            .map_err(|_| TcpError::MessageTooLarge { party: self.party })?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > self.max_message_size {
+            return Err(TcpError::MessageTooLarge { party: self.party });
+        }
+
+        // Memory is allocated as chunks arrive rather than up front, so a peer can't make us
+        // allocate `max_message_size` by merely announcing it. Chunks are joined once the whole
+        // message is received, so no partially filled buffer is reallocated without wiping.
+        let mut chunks = vec![];
+        let mut received = 0;
+        while received < len {
+            let chunk = self.receive_chunk().await?;
+            received += chunk.len();
+            if received > len {
+                return Err(TcpError::MessageTooLarge { party: self.party });
+            }
+            if !chunk.is_empty() {
+                chunks.push(chunk);
+            }
+        }
+        let mut message = Zeroizing::new(Vec::with_capacity(len));
+        for chunk in &chunks {
+            message.extend_from_slice(chunk);
+        }
+
+        let msg: Msg<M> =
+            serde_json::from_slice(&message).map_err(|source| TcpError::Deserialize {
+                party: self.party,
+                source,
+            })?;
+        if msg.sender != self.party {
+            return Err(TcpError::WrongSender {
+                party: self.party,
+                sender: msg.sender,
+            });
+        }
+        if let Some(receiver) = msg.receiver {
+            if receiver != party_index {
+                return Err(TcpError::WrongReceiver {
+                    sender: msg.sender,
+                    receiver,
+                });
+            }
+        }
+        Ok(Some(msg))
+    }
+
+    async fn receive_chunk(&mut self) -> Result<Zeroizing<Vec<u8>>, TcpError> {
+        let frame = read_frame(&mut self.read).await?;
+        let mut chunk = Zeroizing::new(vec![0u8; frame.len()]);
+        let len = self
+            .noise
+            .read_message(self.nonce, &frame, &mut chunk)
+            .map_err(TcpError::Noise)?;
+        self.nonce += 1;
+        chunk.truncate(len);
+        Ok(chunk)
+    }
+}
+
+/// Writes a frame prefixed with its length
+async fn write_frame<W: AsyncWrite + Unpin>(write: &mut W, frame: &[u8]) -> Result<(), TcpError> {
+    let len: u16 = frame
+        .len()
+        .try_into()
+        .expect("frame never exceeds max Noise message length");
+    write.write_u16(len).await.map_err(TcpError::Io)?;
+    write.write_all(frame).await.map_err(TcpError::Io)?;
+    Ok(())
+}
+
+async fn read_frame<R: AsyncRead + Unpin>(read: &mut R) -> Result<Vec<u8>, TcpError> {
+    let len = read.read_u16().await.map_err(TcpError::Io)?;
+    let mut frame = vec![0u8; usize::from(len)];
+    read.read_exact(&mut frame).await.map_err(TcpError::Io)?;
+    Ok(frame)
+}
+
+#[cfg(test)]
+mod test;
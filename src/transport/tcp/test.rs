@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt, TryStreamExt};
+use round_based::async_runtime::AsyncProtocol;
+use round_based::Msg;
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{connect, NoiseKeypair, Peer, TcpConfig, TcpError};
+use crate::protocols::gg_2020::state_machine::keygen::Keygen;
+use crate::protocols::gg_2020::state_machine::sign::stages::offline_stage::OfflineStage;
+use crate::utilities::session_id::SessionId;
+
+struct Network {
+    keypairs: Vec<NoiseKeypair>,
+    listeners: Vec<TcpListener>,
+    peers: BTreeMap<u16, Peer>,
+}
+
+async fn network(n: u16) -> Network {
+    let mut keypairs = vec![];
+    let mut listeners = vec![];
+    let mut peers = BTreeMap::new();
+    for i in 1..=n {
+        let keypair = NoiseKeypair::generate();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        peers.insert(
+            i,
+            Peer {
+                address: listener.local_addr().unwrap(),
+                static_public_key: *keypair.public_key(),
+            },
+        );
+        keypairs.push(keypair);
+        listeners.push(listener);
+    }
+    Network {
+        keypairs,
+        listeners,
+        peers,
+    }
+}
+
+fn config() -> TcpConfig {
+    TcpConfig {
+        connect_timeout: Duration::from_secs(10),
+        redial_interval: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn keypair_can_be_restored_from_secret() {
+    let secret = [7u8; 32];
+    let keypair = NoiseKeypair::from_secret(secret);
+    assert_eq!(
+        NoiseKeypair::from_secret(secret).public_key(),
+        keypair.public_key()
+    );
+    assert!(!format!("{:?}", keypair).contains(&hex::encode(secret)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn keygen_and_offline_stage_over_loopback() {
+    let (t, n) = (1, 3);
+    let Network {
+        keypairs,
+        listeners,
+        peers,
+    } = network(n).await;
+    let keygen_session = SessionId::random();
+    let keygen_parties = (1..).zip(keypairs.into_iter().zip(listeners)).map(
+        |(i, (keypair, listener))| {
+            let peers = peers.clone();
+            tokio::spawn(async move {
+                let (incoming, outgoing) = connect(i, &keypair, listener, &peers, config())
+                    .await
+                    .unwrap();
+                let incoming = incoming.fuse();
+                tokio::pin!(incoming);
+                tokio::pin!(outgoing);
+                let keygen = Keygen::new(i, t, n, keygen_session).unwrap();
+                AsyncProtocol::new(keygen, incoming, outgoing)
+                    .run()
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        },
+    );
+    let local_keys: Vec<_> = futures::future::try_join_all(keygen_parties)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    // Parties 1 and 3 sign, they are parties 1 and 2 of the offline stage
+    let s_l = vec![1u16, 3];
+    let Network {
+        keypairs,
+        listeners,
+        peers,
+    } = network(2).await;
+    let signing_session = SessionId::random();
+    let signing_parties = (1..).zip(keypairs.into_iter().zip(listeners)).map(
+        |(i, (keypair, listener)): (u16, _)| {
+            let peers = peers.clone();
+            let s_l = s_l.clone();
+            let local_key = local_keys[usize::from(s_l[usize::from(i - 1)] - 1)].clone();
+            tokio::spawn(async move {
+                let (incoming, outgoing) = connect(i, &keypair, listener, &peers, config())
+                    .await
+                    .unwrap();
+                let incoming = incoming.fuse();
+                tokio::pin!(incoming);
+                tokio::pin!(outgoing);
+                let offline = OfflineStage::new(i, s_l, local_key, signing_session).unwrap();
+                AsyncProtocol::new(offline, incoming, outgoing)
+                    .run()
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        },
+    );
+    let completed = futures::future::try_join_all(signing_parties)
+        .await
+        .unwrap();
+    for stage in completed {
+        assert_eq!(stage.unwrap().public_key(), &local_keys[0].public_key);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn large_messages_are_split_into_chunks() {
+    let Network {
+        mut keypairs,
+        mut listeners,
+        peers,
+    } = network(2).await;
+    let body = "x".repeat(200_000);
+
+    let peers2 = peers.clone();
+    let (keypair2, listener2) = (keypairs.pop().unwrap(), listeners.pop().unwrap());
+    let party2 = tokio::spawn(async move {
+        let (incoming, _outgoing) = connect::<String>(2, &keypair2, listener2, &peers2, config())
+            .await
+            .unwrap();
+        let messages: Vec<_> = incoming.take(2).try_collect().await.unwrap();
+        messages
+    });
+
+    let (keypair1, listener1) = (keypairs.pop().unwrap(), listeners.pop().unwrap());
+    let (_incoming, outgoing) = connect::<String>(1, &keypair1, listener1, &peers, config())
+        .await
+        .unwrap();
+    tokio::pin!(outgoing);
+    for receiver in [None, Some(2)].iter() {
+        outgoing
+            .send(Msg {
+                sender: 1,
+                receiver: *receiver,
+                body: body.clone(),
+            })
+            .await
+            .unwrap();
+    }
+
+    let received = party2.await.unwrap();
+    assert_eq!(received.len(), 2);
+    assert!(received.iter().all(|msg| msg.sender == 1 && msg.body == body));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn party_with_unexpected_static_key_is_rejected() {
+    let Network {
+        mut keypairs,
+        mut listeners,
+        peers,
+    } = network(2).await;
+
+    // Party 1 listens on its address, but doesn't own the static key from the peers list
+    let listener1 = listeners.remove(0);
+    let peers1 = peers.clone();
+    tokio::spawn(async move {
+        let impostor = NoiseKeypair::generate();
+        let _ = connect::<String>(1, &impostor, listener1, &peers1, config()).await;
+    });
+
+    let (keypair2, listener2) = (keypairs.pop().unwrap(), listeners.pop().unwrap());
+    let result = connect::<String>(2, &keypair2, listener2, &peers, config()).await;
+    assert!(matches!(
+        result,
+        Err(TcpError::PeerAuthentication { party: 1 })
+    ));
+}
+
+#[tokio::test]
+async fn connect_times_out_if_peer_is_offline() {
+    let Network {
+        mut keypairs,
+        mut listeners,
+        peers,
+    } = network(2).await;
+    let (keypair1, listener1) = (keypairs.remove(0), listeners.remove(0));
+    let config = TcpConfig {
+        connect_timeout: Duration::from_millis(200),
+        ..config()
+    };
+    let result = connect::<String>(1, &keypair1, listener1, &peers, config).await;
+    assert!(matches!(result, Err(TcpError::ConnectTimeout)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn stalled_inbound_connection_doesnt_block_peers() {
+    let Network {
+        mut keypairs,
+        mut listeners,
+        peers,
+    } = network(3).await;
+    let config = TcpConfig {
+        connect_timeout: Duration::from_secs(5),
+        handshake_timeout: Duration::from_millis(200),
+        ..config()
+    };
+
+    // Stranger connects to party 1 and never sends anything. It connects before real peers, and
+    // keeps the connection open for longer than it takes the peers to connect
+    let stalled = TcpStream::connect(peers[&1].address).await.unwrap();
+
+    let mut parties = vec![];
+    for i in (1..=3u16).rev() {
+        let (keypair, listener) = (keypairs.pop().unwrap(), listeners.pop().unwrap());
+        let peers = peers.clone();
+        let config = config.clone();
+        parties.push(tokio::spawn(async move {
+            connect::<String>(i, &keypair, listener, &peers, config)
+                .await
+                .map(|_| ())
+        }));
+    }
+    let started = std::time::Instant::now();
+    for party in parties {
+        party.await.unwrap().unwrap();
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+    drop(stalled);
+}
@@ -0,0 +1,40 @@
+//! Fingerprints of secret values, used by `Debug` implementations of secret-bearing types
+//!
+//! A fingerprint is a truncated SHA-256 hash of the secret. It allows telling apart two keys
+//! in logs without revealing them.
+
+use std::fmt;
+
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Curve, Scalar};
+use curv::BigInt;
+use sha2::{Digest, Sha256};
+
+const FINGERPRINT_LEN: usize = 4;
+
+/// Redacted secret value, displayed as `<redacted sha256:…>`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Fingerprint([u8; FINGERPRINT_LEN]);
+
+impl Fingerprint {
+    pub fn of_bytes(bytes: &[u8]) -> Self {
+        let hash = Sha256::digest(bytes);
+        let mut fingerprint = [0u8; FINGERPRINT_LEN];
+        fingerprint.copy_from_slice(&hash[..FINGERPRINT_LEN]);
+        Self(fingerprint)
+    }
+
+    pub fn of_bigint(n: &BigInt) -> Self {
+        Self::of_bytes(&n.to_bytes())
+    }
+
+    pub fn of_scalar<E: Curve>(s: &Scalar<E>) -> Self {
+        Self::of_bigint(&s.to_bigint())
+    }
+}
+
+impl fmt::Debug for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<redacted sha256:{}>", hex::encode(self.0))
+    }
+}
@@ -0,0 +1,93 @@
+//! Digest of a message to be signed
+//!
+//! ECDSA signs a 32-byte digest of the message rather than the message itself. [MessageDigest]
+//! can only be obtained by hashing the message or from exactly 32 bytes produced by the caller,
+//! so a raw message can't be signed by mistake.
+
+use std::fmt;
+
+use curv::arithmetic::traits::*;
+use curv::BigInt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use thiserror::Error;
+
+/// 32-byte digest of a message to be signed
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MessageDigest([u8; 32]);
+
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MessageDigestError {
+    #[error("digest must be 32 bytes long, got {0} bytes")]
+    InvalidLength(usize),
+    #[error("message is {bits} bits long, it must be hashed down to 256 bits before signing")]
+    MessageTooLong { bits: usize },
+}
+
+impl MessageDigest {
+    /// SHA-256 of the message
+    pub fn sha256(message: &[u8]) -> Self {
+        Self(Sha256::digest(message).into())
+    }
+
+    /// SHA-256 of SHA-256 of the message, as used by Bitcoin
+    pub fn double_sha256(message: &[u8]) -> Self {
+        Self(Sha256::digest(&Sha256::digest(message)).into())
+    }
+
+    /// Keccak-256 of the message, as used by Ethereum
+    pub fn keccak256(message: &[u8]) -> Self {
+        Self(Keccak256::digest(message).into())
+    }
+
+    /// Digest computed by the caller
+    pub fn prehashed(digest: [u8; 32]) -> Self {
+        Self(digest)
+    }
+
+    /// Digest computed by the caller, fails if it's not exactly 32 bytes long
+    pub fn from_slice(digest: &[u8]) -> Result<Self, MessageDigestError> {
+        if digest.len() != 32 {
+            return Err(MessageDigestError::InvalidLength(digest.len()));
+        }
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(digest);
+        Ok(Self(bytes))
+    }
+
+    /// Digest given as a big-endian integer, fails if it doesn't fit into 256 bits
+    ///
+    /// This is how messages were passed to signing before, but longer messages used to be
+    /// silently reduced modulo group order, producing non-standard signatures.
+    pub fn from_bigint(n: &BigInt) -> Result<Self, MessageDigestError> {
+        let bits = n.bit_length();
+        if bits > 256 || n < &BigInt::zero() {
+            return Err(MessageDigestError::MessageTooLong { bits });
+        }
+        let bytes = n.to_bytes();
+        let bytes = &bytes[bytes.iter().take_while(|b| **b == 0).count()..];
+        let mut digest = [0u8; 32];
+        digest[32 - bytes.len()..].copy_from_slice(bytes);
+        Ok(Self(digest))
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Digest as a big-endian integer
+    pub fn to_bigint(&self) -> BigInt {
+        BigInt::from_bytes(&self.0)
+    }
+}
+
+impl fmt::Debug for MessageDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MessageDigest({})", hex::encode(self.0))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,50 @@
+use curv::arithmetic::traits::*;
+use curv::BigInt;
+
+use crate::utilities::message_digest::{MessageDigest, MessageDigestError};
+
+#[test]
+fn digests_match_known_values() {
+    assert_eq!(
+        hex::encode(MessageDigest::sha256(b"abc").as_bytes()),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        hex::encode(MessageDigest::double_sha256(b"abc").as_bytes()),
+        "4f8b42c22dd3729b519ba6f68d2da7cc5b2d606d05daed5ad5128cc03e6c6358"
+    );
+    assert_eq!(
+        hex::encode(MessageDigest::keccak256(b"").as_bytes()),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+}
+
+#[test]
+fn raw_digests_must_be_32_bytes() {
+    let digest = MessageDigest::from_slice(&[1u8; 32]).unwrap();
+    assert_eq!(digest, MessageDigest::prehashed([1u8; 32]));
+    assert_eq!(
+        MessageDigest::from_slice(&[1u8; 33]),
+        Err(MessageDigestError::InvalidLength(33))
+    );
+    assert_eq!(
+        MessageDigest::from_slice(b"hello"),
+        Err(MessageDigestError::InvalidLength(5))
+    );
+}
+
+#[test]
+fn over_length_messages_are_rejected() {
+    let digest = MessageDigest::from_bigint(&BigInt::from(42)).unwrap();
+    assert_eq!(digest.to_bigint(), BigInt::from(42));
+    assert_eq!(digest.as_bytes()[31], 42);
+
+    let long_message = BigInt::from_bytes(b"this message is longer than thirty two bytes");
+    assert!(matches!(
+        MessageDigest::from_bigint(&long_message),
+        Err(MessageDigestError::MessageTooLong { .. })
+    ));
+
+    let max = (BigInt::one() << 256) - BigInt::one();
+    assert!(MessageDigest::from_bigint(&max).is_ok());
+}
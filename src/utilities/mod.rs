@@ -1,3 +1,9 @@
+pub mod fingerprint;
+pub mod message_digest;
 pub mod mta;
+pub mod secret_scalar;
+pub mod session_id;
+pub mod wire;
 pub mod zk_pdl;
 pub mod zk_pdl_with_slack;
+pub mod zk_sigma;
@@ -16,7 +16,6 @@
 
 /// MtA is described in https://eprint.iacr.org/2019/114.pdf section 3
 use curv::arithmetic::traits::Samplable;
-use curv::cryptographic_primitives::proofs::sigma_dlog::DLogProof;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
 use curv::BigInt;
 use paillier::traits::EncryptWithChosenRandomness;
@@ -25,9 +24,11 @@ use paillier::{DecryptionKey, EncryptionKey, Paillier, Randomness, RawCiphertext
 use zk_paillier::zkproofs::DLogStatement;
 
 use serde::{Deserialize, Serialize};
-use sha2::Sha256;
 
 use crate::utilities::mta::range_proofs::AliceProof;
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+use crate::utilities::zk_sigma::DLogProof;
 use crate::Error::{self, InvalidKey};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -36,25 +37,39 @@ pub struct MessageA {
     pub range_proofs: Vec<AliceProof>, // proofs (using other parties' h1,h2,N_tilde) that the plaintext is small
 }
 
+wire_struct!(MessageA { c, range_proofs });
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MessageB {
     pub c: BigInt, // paillier encryption
-    pub b_proof: DLogProof<Secp256k1, Sha256>,
-    pub beta_tag_proof: DLogProof<Secp256k1, Sha256>,
+    pub b_proof: DLogProof,
+    pub beta_tag_proof: DLogProof,
 }
 
+wire_struct!(MessageB {
+    c,
+    b_proof,
+    beta_tag_proof,
+});
+
 impl MessageA {
     /// Creates a new `messageA` using Alice's Paillier encryption key and `dlog_statements`
     /// - other parties' `h1,h2,N_tilde`s for range proofs.
-    /// If range proofs are not needed (one example is identification of aborts where we
-    /// only want to reconstruct a ciphertext), `dlog_statements` can be an empty slice.
+    /// Range proofs are bound to the session `sid`.
     pub fn a(
         a: &Scalar<Secp256k1>,
         alice_ek: &EncryptionKey,
         dlog_statements: &[DLogStatement],
+        sid: &SessionId,
     ) -> (Self, BigInt) {
         let randomness = BigInt::sample_below(&alice_ek.n);
-        let m_a = MessageA::a_with_predefined_randomness(a, alice_ek, &randomness, dlog_statements);
+        let m_a = MessageA::a_with_predefined_randomness(
+            a,
+            alice_ek,
+            &randomness,
+            dlog_statements,
+            sid,
+        );
         (m_a, randomness)
     }
 
@@ -63,19 +78,20 @@ impl MessageA {
         alice_ek: &EncryptionKey,
         randomness: &BigInt,
         dlog_statements: &[DLogStatement],
+        sid: &SessionId,
     ) -> Self {
-        let c_a = Paillier::encrypt_with_chosen_randomness(
-            alice_ek,
-            RawPlaintext::from(a.to_bigint()),
-            &Randomness::from(randomness.clone()),
-        )
-        .0
-        .clone()
-        .into_owned();
+        let c_a = MessageA::ciphertext_with_predefined_randomness(a, alice_ek, randomness);
         let alice_range_proofs = dlog_statements
             .iter()
             .map(|dlog_statement| {
-                AliceProof::generate(&a.to_bigint(), &c_a, alice_ek, dlog_statement, randomness)
+                AliceProof::generate(
+                    &a.to_bigint(),
+                    &c_a,
+                    alice_ek,
+                    dlog_statement,
+                    randomness,
+                    sid,
+                )
             })
             .collect::<Vec<AliceProof>>();
 
@@ -84,6 +100,22 @@ impl MessageA {
             range_proofs: alice_range_proofs,
         }
     }
+
+    /// Reconstructs only the ciphertext `c` of a `messageA`, without any proofs. Used in
+    /// identification of aborts.
+    pub fn ciphertext_with_predefined_randomness(
+        a: &Scalar<Secp256k1>,
+        alice_ek: &EncryptionKey,
+        randomness: &BigInt,
+    ) -> BigInt {
+        Paillier::encrypt_with_chosen_randomness(
+            alice_ek,
+            RawPlaintext::from(a.to_bigint()),
+            &Randomness::from(randomness.clone()),
+        )
+        .0
+        .into_owned()
+    }
 }
 
 impl MessageB {
@@ -92,6 +124,7 @@ impl MessageB {
         alice_ek: &EncryptionKey,
         m_a: MessageA,
         dlog_statements: &[DLogStatement],
+        sid: &SessionId,
     ) -> Result<(Self, Scalar<Secp256k1>, BigInt, BigInt), Error> {
         let beta_tag = BigInt::sample_below(&alice_ek.n);
         let randomness = BigInt::sample_below(&alice_ek.n);
@@ -102,6 +135,7 @@ impl MessageB {
             &randomness,
             &beta_tag,
             dlog_statements,
+            sid,
         )?;
 
         Ok((m_b, beta, randomness, beta_tag))
@@ -114,6 +148,7 @@ impl MessageB {
         randomness: &BigInt,
         beta_tag: &BigInt,
         dlog_statements: &[DLogStatement],
+        sid: &SessionId,
     ) -> Result<(Self, Scalar<Secp256k1>), Error> {
         if m_a.range_proofs.len() != dlog_statements.len() {
             return Err(InvalidKey);
@@ -123,12 +158,37 @@ impl MessageB {
             .range_proofs
             .iter()
             .zip(dlog_statements)
-            .map(|(proof, dlog_statement)| proof.verify(&m_a.c, alice_ek, dlog_statement))
+            .map(|(proof, dlog_statement)| proof.verify(&m_a.c, alice_ek, dlog_statement, sid))
             .all(|x| x)
         {
             return Err(InvalidKey);
         };
         let beta_tag_fe = Scalar::<Secp256k1>::from(beta_tag);
+        let (c_b, beta) = MessageB::ciphertext_with_predefined_randomness(
+            b, alice_ek, &m_a.c, randomness, beta_tag,
+        );
+        let dlog_proof_b = DLogProof::prove(b, sid);
+        let dlog_proof_beta_tag = DLogProof::prove(&beta_tag_fe, sid);
+
+        Ok((
+            Self {
+                c: c_b,
+                b_proof: dlog_proof_b,
+                beta_tag_proof: dlog_proof_beta_tag,
+            },
+            beta,
+        ))
+    }
+
+    /// Reconstructs only the ciphertext `c` of a `messageB` and Bob's share `beta`, without
+    /// verifying range proofs or producing dlog proofs. Used in identification of aborts.
+    pub fn ciphertext_with_predefined_randomness(
+        b: &Scalar<Secp256k1>,
+        alice_ek: &EncryptionKey,
+        c_a: &BigInt,
+        randomness: &BigInt,
+        beta_tag: &BigInt,
+    ) -> (BigInt, Scalar<Secp256k1>) {
         let c_beta_tag = Paillier::encrypt_with_chosen_randomness(
             alice_ek,
             RawPlaintext::from(beta_tag),
@@ -138,36 +198,27 @@ impl MessageB {
         let b_bn = b.to_bigint();
         let b_c_a = Paillier::mul(
             alice_ek,
-            RawCiphertext::from(m_a.c),
+            RawCiphertext::from(c_a.clone()),
             RawPlaintext::from(b_bn),
         );
         let c_b = Paillier::add(alice_ek, b_c_a, c_beta_tag);
-        let beta = Scalar::<Secp256k1>::zero() - &beta_tag_fe;
-        let dlog_proof_b = DLogProof::prove(b);
-        let dlog_proof_beta_tag = DLogProof::prove(&beta_tag_fe);
-
-        Ok((
-            Self {
-                c: c_b.0.clone().into_owned(),
-                b_proof: dlog_proof_b,
-                beta_tag_proof: dlog_proof_beta_tag,
-            },
-            beta,
-        ))
+        let beta = Scalar::<Secp256k1>::zero() - Scalar::<Secp256k1>::from(beta_tag);
+        (c_b.0.into_owned(), beta)
     }
 
     pub fn verify_proofs_get_alpha(
         &self,
         dk: &DecryptionKey,
         a: &Scalar<Secp256k1>,
+        sid: &SessionId,
     ) -> Result<(Scalar<Secp256k1>, BigInt), Error> {
         let alice_share = Paillier::decrypt(dk, &RawCiphertext::from(self.c.clone()));
         let g = Point::generator();
         let alpha = Scalar::<Secp256k1>::from(alice_share.0.as_ref());
         let g_alpha = g * &alpha;
         let ba_btag = &self.b_proof.pk * a + &self.beta_tag_proof.pk;
-        if DLogProof::verify(&self.b_proof).is_ok()
-            && DLogProof::verify(&self.beta_tag_proof).is_ok()
+        if self.b_proof.verify(sid).is_ok()
+            && self.beta_tag_proof.verify(sid).is_ok()
             // we prove the correctness of the ciphertext using this check and the proof of knowledge of dlog of beta_tag
             && ba_btag == g_alpha
         {
@@ -8,6 +8,7 @@
 //! There are some deviations from the original specification:
 //! 1) In Bob's proofs `gamma` is sampled from `[0;q^2 * N]` and `tau` from `[0;q^3 * N_tilde]`.
 //! 2) A non-interactive version is implemented, with challenge `e` computed via Fiat-Shamir.
+//!    The challenge is bound to the [SessionId], so a proof can't be replayed in another session.
 
 use curv::arithmetic::traits::*;
 use curv::cryptographic_primitives::hashing::{Digest, DigestExt};
@@ -22,6 +23,9 @@ use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
 use zeroize::Zeroize;
 
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+
 /// Represents the first round of the interactive version of the proof
 #[derive(Zeroize)]
 #[zeroize(drop)]
@@ -100,6 +104,8 @@ pub struct AliceProof {
     s2: BigInt,
 }
 
+wire_struct!(AliceProof { z, e, s, s1, s2 });
+
 impl AliceProof {
     /// verify Alice's proof using the proof and public keys
     pub fn verify(
@@ -107,6 +113,7 @@ impl AliceProof {
         cipher: &BigInt,
         alice_ek: &EncryptionKey,
         dlog_statement: &DLogStatement,
+        sid: &SessionId,
     ) -> bool {
         let N = &alice_ek.n;
         let NN = &alice_ek.nn;
@@ -141,6 +148,7 @@ impl AliceProof {
         let u = (gs1 * BigInt::mod_pow(&self.s, N, NN) * cipher_e_inv) % NN;
 
         let e = Sha256::new()
+            .chain(sid.as_bytes())
             .chain_bigint(N)
             .chain_bigint(&Gen)
             .chain_bigint(cipher)
@@ -163,6 +171,7 @@ impl AliceProof {
         alice_ek: &EncryptionKey,
         dlog_statement: &DLogStatement,
         r: &BigInt,
+        sid: &SessionId,
     ) -> Self {
         let round1 = AliceZkpRound1::from(
             alice_ek,
@@ -173,6 +182,7 @@ impl AliceProof {
 
         let Gen = alice_ek.n.borrow() + 1;
         let e = Sha256::new()
+            .chain(sid.as_bytes())
             .chain_bigint(&alice_ek.n)
             .chain_bigint(&Gen)
             .chain_bigint(cipher)
@@ -325,6 +335,7 @@ impl BobProof {
         alice_ek: &EncryptionKey,
         dlog_statement: &DLogStatement,
         check: Option<&BobCheck>,
+        sid: &SessionId,
     ) -> bool {
         let N = &alice_ek.n;
         let NN = &alice_ek.nn;
@@ -395,12 +406,12 @@ impl BobProof {
                 values_to_hash.push(&u_y_coor);
                 values_to_hash
                     .into_iter()
-                    .fold(Sha256::new(), |acc, b| acc.chain_bigint(b))
+                    .fold(Sha256::new().chain(sid.as_bytes()), |acc, b| acc.chain_bigint(b))
                     .result_bigint()
             }
             None => values_to_hash
                 .into_iter()
-                .fold(Sha256::new(), |acc, b| acc.chain_bigint(b))
+                .fold(Sha256::new().chain(sid.as_bytes()), |acc, b| acc.chain_bigint(b))
                 .result_bigint(),
         };
 
@@ -420,6 +431,7 @@ impl BobProof {
         dlog_statement: &DLogStatement,
         r: &Randomness,
         check: bool,
+        sid: &SessionId,
     ) -> (BobProof, Option<Point<Secp256k1>>) {
         let round1 = BobZkpRound1::from(
             alice_ek,
@@ -460,12 +472,12 @@ impl BobProof {
             values_to_hash.push(&u_y_coor);
             values_to_hash
                 .into_iter()
-                .fold(Sha256::new(), |acc, b| acc.chain_bigint(b))
+                .fold(Sha256::new().chain(sid.as_bytes()), |acc, b| acc.chain_bigint(b))
                 .result_bigint()
         } else {
             values_to_hash
                 .into_iter()
-                .fold(Sha256::new(), |acc, b| acc.chain_bigint(b))
+                .fold(Sha256::new().chain(sid.as_bytes()), |acc, b| acc.chain_bigint(b))
                 .result_bigint()
         };
 
@@ -503,6 +515,7 @@ impl BobProofExt {
         alice_ek: &EncryptionKey,
         dlog_statement: &DLogStatement,
         X: &Point<Secp256k1>,
+        sid: &SessionId,
     ) -> bool {
         // check basic proof first
         if !self.proof.verify(
@@ -514,6 +527,7 @@ impl BobProofExt {
                 u: self.u.clone(),
                 X: X.clone(),
             }),
+            sid,
         ) {
             return false;
         }
@@ -570,6 +584,7 @@ pub(crate) mod tests {
         alice_ek: &EncryptionKey,
         dlog_statement: &DLogStatement,
         r: &Randomness,
+        sid: &SessionId,
     ) -> BobProofExt {
         // proving a basic proof (with modified hash)
         let (bob_proof, u) = BobProof::generate(
@@ -581,6 +596,7 @@ pub(crate) mod tests {
             dlog_statement,
             r,
             true,
+            sid,
         );
 
         BobProofExt {
@@ -628,14 +644,18 @@ pub(crate) mod tests {
         .clone()
         .into_owned();
 
-        let alice_proof = AliceProof::generate(&a, &cipher, &ek, &dlog_statement, &r);
+        let sid = SessionId::new(b"session a");
+        let alice_proof = AliceProof::generate(&a, &cipher, &ek, &dlog_statement, &r, &sid);
 
-        assert!(alice_proof.verify(&cipher, &ek, &dlog_statement));
+        assert!(alice_proof.verify(&cipher, &ek, &dlog_statement, &sid));
+        assert!(!alice_proof.verify(&cipher, &ek, &dlog_statement, &SessionId::new(b"session b")));
     }
 
     #[test]
     fn bob_zkp() {
         let (dlog_statement, ek, _) = generate_init();
+        let sid = SessionId::new(b"session a");
+        let other_sid = SessionId::new(b"session b");
 
         (0..5).for_each(|_| {
             let alice_public_key = &ek;
@@ -676,13 +696,23 @@ pub(crate) mod tests {
                     &dlog_statement,
                     &r,
                     false,
+                    &sid,
                 );
                 assert!(bob_proof.verify(
                     &encrypted_a,
                     &mta_out.0.clone(),
                     alice_public_key,
                     &dlog_statement,
-                    None
+                    None,
+                    &sid,
+                ));
+                assert!(!bob_proof.verify(
+                    &encrypted_a,
+                    &mta_out.0.clone(),
+                    alice_public_key,
+                    &dlog_statement,
+                    None,
+                    &other_sid,
                 ));
 
                 // Bob follows MtAwc
@@ -696,13 +726,23 @@ pub(crate) mod tests {
                     alice_public_key,
                     &dlog_statement,
                     &r,
+                    &sid,
                 );
                 assert!(bob_proof.verify(
                     &encrypted_a,
                     &mta_out.0.clone(),
                     alice_public_key,
                     &dlog_statement,
-                    &X
+                    &X,
+                    &sid,
+                ));
+                assert!(!bob_proof.verify(
+                    &encrypted_a,
+                    &mta_out.0.clone(),
+                    alice_public_key,
+                    &dlog_statement,
+                    &X,
+                    &other_sid,
                 ));
             });
         });
@@ -1,5 +1,6 @@
 use crate::utilities::mta::range_proofs::tests::generate_init;
 use crate::utilities::mta::{MessageA, MessageB};
+use crate::utilities::session_id::SessionId;
 use curv::elliptic::curves::{secp256_k1::Secp256k1, Scalar};
 
 #[test]
@@ -7,13 +8,39 @@ fn test_mta() {
     let alice_input = Scalar::<Secp256k1>::random();
     let (dlog_statement, ek_alice, dk_alice) = generate_init();
     let bob_input = Scalar::<Secp256k1>::random();
-    let (m_a, _) = MessageA::a(&alice_input, &ek_alice, &[dlog_statement.clone()]);
-    let (m_b, beta, _, _) = MessageB::b(&bob_input, &ek_alice, m_a, &[dlog_statement]).unwrap();
+    let sid = SessionId::new(b"mta test");
+    let (m_a, _) = MessageA::a(&alice_input, &ek_alice, &[dlog_statement.clone()], &sid);
+    let (m_b, beta, _, _) =
+        MessageB::b(&bob_input, &ek_alice, m_a, &[dlog_statement], &sid).unwrap();
     let alpha = m_b
-        .verify_proofs_get_alpha(&dk_alice, &alice_input)
+        .verify_proofs_get_alpha(&dk_alice, &alice_input, &sid)
         .expect("wrong dlog or m_b");
 
     let left = alpha.0 + beta;
     let right = alice_input * bob_input;
     assert_eq!(left, right);
 }
+
+#[test]
+fn test_mta_rejects_replayed_messages() {
+    let alice_input = Scalar::<Secp256k1>::random();
+    let (dlog_statement, ek_alice, dk_alice) = generate_init();
+    let bob_input = Scalar::<Secp256k1>::random();
+    let sid = SessionId::new(b"mta session a");
+    let other_sid = SessionId::new(b"mta session b");
+
+    let (m_a, _) = MessageA::a(&alice_input, &ek_alice, &[dlog_statement.clone()], &sid);
+    assert!(MessageB::b(
+        &bob_input,
+        &ek_alice,
+        m_a.clone(),
+        &[dlog_statement.clone()],
+        &other_sid
+    )
+    .is_err());
+
+    let (m_b, _, _, _) = MessageB::b(&bob_input, &ek_alice, m_a, &[dlog_statement], &sid).unwrap();
+    assert!(m_b
+        .verify_proofs_get_alpha(&dk_alice, &alice_input, &other_sid)
+        .is_err());
+}
@@ -0,0 +1,44 @@
+//! Secret scalar which is wiped when dropped
+//!
+//! `Scalar` doesn't implement `Zeroize`, so it can't be held in `Zeroizing`. [SecretScalar]
+//! plays the same role for intermediate secrets (e.g. key share being recovered) that don't
+//! live in a struct with its own `Zeroize` implementation.
+
+use std::ops::Deref;
+
+use curv::elliptic::curves::{Scalar, Secp256k1};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Clone)]
+pub struct SecretScalar(Scalar<Secp256k1>);
+
+impl SecretScalar {
+    pub fn new(scalar: Scalar<Secp256k1>) -> Self {
+        Self(scalar)
+    }
+}
+
+impl Deref for SecretScalar {
+    type Target = Scalar<Secp256k1>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0 = Scalar::zero();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for SecretScalar {}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,13 @@
+use curv::elliptic::curves::Scalar;
+use zeroize::Zeroize;
+
+use super::SecretScalar;
+
+#[test]
+fn zeroize_wipes_the_scalar() {
+    let scalar = Scalar::random();
+    let mut secret = SecretScalar::new(scalar.clone());
+    assert_eq!(*secret, scalar);
+    secret.zeroize();
+    assert!(secret.is_zero());
+}
@@ -0,0 +1,87 @@
+//! Session identifier
+//!
+//! Every Fiat–Shamir challenge and hash commitment produced during keygen or signing is bound to
+//! a [SessionId], so proofs and commitments produced in one protocol execution can't be replayed
+//! into another one.
+
+use std::fmt;
+
+use curv::arithmetic::traits::*;
+use curv::cryptographic_primitives::hashing::{Digest, DigestExt};
+use curv::BigInt;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+const SESSION_ID_DOMAIN: &[u8] = b"multi-party-ecdsa/session-id/v1";
+const COMMITMENT_DOMAIN: &[u8] = b"multi-party-ecdsa/hash-commitment/v1";
+
+/// Identifier of a single protocol execution
+///
+/// All the parties must use the same session id, and it must never be reused for another
+/// execution (of any protocol).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionId([u8; 32]);
+
+impl SessionId {
+    /// Derives session id from arbitrary context the parties agreed on (e.g. a unique room id
+    /// issued by coordinator)
+    pub fn new(context: &[u8]) -> Self {
+        let hash = Sha256::new()
+            .chain(SESSION_ID_DOMAIN)
+            .chain((context.len() as u64).to_be_bytes())
+            .chain(context)
+            .finalize();
+        Self(hash.into())
+    }
+
+    /// Samples random session id
+    ///
+    /// One of the parties (or a coordinator) may sample session id and distribute it to others.
+    pub fn random() -> Self {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Hash commitment to `message` with randomness `blind_factor` bound to the session
+    pub fn commit(&self, message: &BigInt, blind_factor: &BigInt) -> BigInt {
+        Sha256::new()
+            .chain(COMMITMENT_DOMAIN)
+            .chain(self.0)
+            .chain_bigint(message)
+            .chain_bigint(blind_factor)
+            .result_bigint()
+    }
+
+    /// Salt for `NiCorrectKeyProof`
+    ///
+    /// The proof takes salt as a `BigInt` when proving, and as bytes when verifying, so salt
+    /// starts with a non-zero byte to survive the conversion.
+    pub fn correct_key_proof_salt(&self) -> Vec<u8> {
+        let mut salt = zk_paillier::zkproofs::SALT_STRING.to_vec();
+        salt.extend_from_slice(&self.0);
+        salt
+    }
+
+    pub(crate) fn correct_key_proof_salt_bigint(&self) -> BigInt {
+        BigInt::from_bytes(&self.correct_key_proof_salt())
+    }
+}
+
+impl fmt::Debug for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SessionId({})", hex::encode(self.0))
+    }
+}
+
+#[cfg(test)]
+mod test;
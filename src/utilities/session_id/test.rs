@@ -0,0 +1,29 @@
+use curv::arithmetic::traits::*;
+use curv::BigInt;
+
+use crate::utilities::session_id::SessionId;
+
+#[test]
+fn session_id_is_derived_deterministically() {
+    assert_eq!(SessionId::new(b"room 1"), SessionId::new(b"room 1"));
+    assert_ne!(SessionId::new(b"room 1"), SessionId::new(b"room 2"));
+    assert_ne!(SessionId::random(), SessionId::random());
+}
+
+#[test]
+fn commitment_is_bound_to_session() {
+    let (message, blind_factor) = (BigInt::from(42), BigInt::from(7));
+    let a = SessionId::new(b"session a");
+    let b = SessionId::new(b"session b");
+    assert_eq!(a.commit(&message, &blind_factor), a.commit(&message, &blind_factor));
+    assert_ne!(a.commit(&message, &blind_factor), b.commit(&message, &blind_factor));
+}
+
+#[test]
+fn correct_key_proof_salt_survives_bigint_conversion() {
+    let sid = SessionId::from_bytes([0u8; 32]);
+    assert_eq!(
+        sid.correct_key_proof_salt_bigint().to_bytes(),
+        sid.correct_key_proof_salt()
+    );
+}
@@ -0,0 +1,400 @@
+//! Compact binary encoding of protocol messages
+//!
+//! JSON representation of protocol messages spells every big integer as a hex string, which
+//! makes messages carrying Paillier ciphertexts and range proofs several times larger than they
+//! need to be. This module defines a canonical binary encoding instead:
+//!
+//! * integers (`u8`, `u16`, `u32`, `u64`) are fixed-width big-endian, `usize` is encoded as `u64`
+//! * big integers are a sign byte (`0` or `1`), `u32` length and big-endian magnitude without
+//!   leading zeroes (zero has empty magnitude and positive sign)
+//! * scalars are 32 big-endian bytes, reduced modulo group order
+//! * points are 33 bytes of compressed SEC1 encoding, point at infinity is 33 zero bytes
+//! * sequences and strings are prefixed with `u32` number of elements / bytes
+//! * enums are prefixed with `u8` tag, structs are concatenation of their fields
+//!
+//! Every value has exactly one encoding: decoding rejects non-minimal big integers, unreduced
+//! scalars, invalid points, unknown tags, and trailing bytes.
+//!
+//! Big integers are deliberately variable-length rather than fixed-width. Paillier moduli,
+//! `N_tilde` and the values modulo them have no width the protocol enforces (keys of any
+//! size are accepted, and a residue may have leading zero bytes), so a fixed width would
+//! either have to be chosen per field from the key in use or reject valid messages. Minimal
+//! magnitude with explicit length is just as canonical and costs 5 bytes per integer.
+
+use std::convert::TryFrom;
+
+use curv::arithmetic::traits::*;
+use curv::cryptographic_primitives::secret_sharing::feldman_vss::{
+    ShamirSecretSharing, VerifiableSS,
+};
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use paillier::EncryptionKey;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zk_paillier::zkproofs::{DLogStatement, NiCorrectKeyProof};
+
+/// Version of the encoding, prepended by [to_bytes]
+pub const WIRE_FORMAT_VERSION: u8 = 1;
+
+const SCALAR_BYTES: usize = 32;
+const POINT_BYTES: usize = 33;
+/// Maximum number of elements reserved up front when decoding a sequence
+const MAX_PREALLOCATED_ITEMS: usize = 1024;
+
+/// Value that has canonical binary encoding
+pub trait Wire: Sized {
+    /// Appends encoding of the value to `out`
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Reads the value from `reader`
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError>;
+}
+
+/// Encodes the value prefixed with [WIRE_FORMAT_VERSION]
+pub fn to_bytes<T: Wire>(value: &T) -> Vec<u8> {
+    let mut out = vec![WIRE_FORMAT_VERSION];
+    value.encode(&mut out);
+    out
+}
+
+/// Decodes the value produced by [to_bytes]
+///
+/// Input must contain exactly one encoded value, trailing bytes are rejected.
+pub fn from_bytes<T: Wire>(bytes: &[u8]) -> Result<T, WireError> {
+    let mut reader = Reader::new(bytes);
+    let version = u8::decode(&mut reader)?;
+    if version != WIRE_FORMAT_VERSION {
+        return Err(WireError::UnsupportedVersion(version));
+    }
+    let value = T::decode(&mut reader)?;
+    match reader.remaining() {
+        0 => Ok(value),
+        len => Err(WireError::TrailingBytes(len)),
+    }
+}
+
+/// Cursor over encoded bytes
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Takes next `len` bytes
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], WireError> {
+        if len > self.bytes.len() {
+            return Err(WireError::UnexpectedEnd);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    /// Reads `u32` length prefix, making sure that it doesn't exceed remaining input
+    pub fn take_len(&mut self) -> Result<usize, WireError> {
+        let len = u32::decode(self)?;
+        let len = usize::try_from(len).map_err(|_| WireError::UnexpectedEnd)?;
+        if len > self.remaining() {
+            return Err(WireError::UnexpectedEnd);
+        }
+        Ok(len)
+    }
+
+    /// Reads `u8` tag of an enum
+    pub fn take_tag(&mut self) -> Result<u8, WireError> {
+        u8::decode(self)
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WireError {
+    #[error("unexpected end of input")]
+    UnexpectedEnd,
+    #[error("{0} trailing bytes after encoded value")]
+    TrailingBytes(usize),
+    #[error("unsupported wire format version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unknown {ty} tag {tag}")]
+    UnknownTag { ty: &'static str, tag: u8 },
+    #[error("big integer is not canonically encoded")]
+    NonCanonicalBigInt,
+    #[error("scalar is not reduced modulo group order")]
+    NonCanonicalScalar,
+    #[error("invalid point encoding")]
+    InvalidPoint,
+    #[error("string is not valid utf-8")]
+    InvalidUtf8,
+    #[error("index {0} doesn't fit into usize")]
+    IndexOverflow(u64),
+    #[error("invalid {0}")]
+    InvalidValue(&'static str),
+}
+
+/// Implements [Wire] for a struct as concatenation of listed fields
+///
+/// Fields must be listed in declaration order, and every field of the struct must be listed.
+/// Newtypes are written as `wire_struct!(Newtype(_))`.
+macro_rules! wire_struct {
+    ($ty:ident ( _ )) => {
+        impl $crate::utilities::wire::Wire for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $crate::utilities::wire::Wire::encode(&self.0, out)
+            }
+
+            fn decode(
+                reader: &mut $crate::utilities::wire::Reader<'_>,
+            ) -> Result<Self, $crate::utilities::wire::WireError> {
+                Ok(Self($crate::utilities::wire::Wire::decode(reader)?))
+            }
+        }
+    };
+    ($ty:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::utilities::wire::Wire for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                $( $crate::utilities::wire::Wire::encode(&self.$field, out); )*
+            }
+
+            fn decode(
+                reader: &mut $crate::utilities::wire::Reader<'_>,
+            ) -> Result<Self, $crate::utilities::wire::WireError> {
+                Ok(Self {
+                    $( $field: $crate::utilities::wire::Wire::decode(reader)?, )*
+                })
+            }
+        }
+    };
+}
+pub(crate) use wire_struct;
+
+macro_rules! wire_uint {
+    ($($t:ty),*) => {
+        $(
+        impl Wire for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_be_bytes())
+            }
+
+            fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+                let mut bytes = [0u8; std::mem::size_of::<$t>()];
+                bytes.copy_from_slice(reader.take(bytes.len())?);
+                Ok(<$t>::from_be_bytes(bytes))
+            }
+        }
+        )*
+    };
+}
+
+wire_uint!(u8, u16, u32, u64);
+
+impl Wire for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out)
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let n = u64::decode(reader)?;
+        usize::try_from(n).map_err(|_| WireError::IndexOverflow(n))
+    }
+}
+
+fn encode_len(len: usize, out: &mut Vec<u8>) {
+    u32::try_from(len)
+        .expect("length of encoded sequence doesn't fit into u32")
+        .encode(out)
+}
+
+impl Wire for BigInt {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let negative = self < &BigInt::zero();
+        let bytes = self.abs().to_bytes();
+        let leading_zeroes = bytes.iter().take_while(|b| **b == 0).count();
+        let magnitude = &bytes[leading_zeroes..];
+
+        out.push(negative as u8);
+        encode_len(magnitude.len(), out);
+        out.extend_from_slice(magnitude);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let negative = match u8::decode(reader)? {
+            0 => false,
+            1 => true,
+            _ => return Err(WireError::NonCanonicalBigInt),
+        };
+        let len = reader.take_len()?;
+        let magnitude = reader.take(len)?;
+        match magnitude.first() {
+            Some(0) => Err(WireError::NonCanonicalBigInt),
+            None if negative => Err(WireError::NonCanonicalBigInt),
+            None => Ok(BigInt::zero()),
+            Some(_) if negative => Ok(BigInt::zero() - BigInt::from_bytes(magnitude)),
+            Some(_) => Ok(BigInt::from_bytes(magnitude)),
+        }
+    }
+}
+
+impl Wire for Scalar<Secp256k1> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let bytes = self.to_bigint().to_bytes();
+        let leading_zeroes = bytes.iter().take_while(|b| **b == 0).count();
+        let bytes = &bytes[leading_zeroes..];
+        out.resize(out.len() + SCALAR_BYTES - bytes.len(), 0);
+        out.extend_from_slice(bytes);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let n = BigInt::from_bytes(reader.take(SCALAR_BYTES)?);
+        if &n >= Scalar::<Secp256k1>::group_order() {
+            return Err(WireError::NonCanonicalScalar);
+        }
+        Ok(Scalar::from_bigint(&n))
+    }
+}
+
+impl Wire for Point<Secp256k1> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        if self.is_zero() {
+            out.extend_from_slice(&[0u8; POINT_BYTES])
+        } else {
+            out.extend_from_slice(&self.to_bytes(true))
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let bytes = reader.take(POINT_BYTES)?;
+        match bytes[0] {
+            0 if bytes.iter().all(|b| *b == 0) => Ok(Point::zero()),
+            2 | 3 => Point::from_bytes(bytes).map_err(|_| WireError::InvalidPoint),
+            _ => Err(WireError::InvalidPoint),
+        }
+    }
+}
+
+impl Wire for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let len = reader.take_len()?;
+        let bytes = reader.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| WireError::InvalidUtf8)
+    }
+}
+
+impl<T: Wire> Wire for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_len(self.len(), out);
+        for item in self {
+            item.encode(out)
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        // `take_len` only bounds the number of elements by remaining input, not the memory they
+        // take once decoded, so preallocation is capped
+        let len = reader.take_len()?;
+        let mut items = Vec::with_capacity(len.min(MAX_PREALLOCATED_ITEMS));
+        for _ in 0..len {
+            items.push(T::decode(reader)?)
+        }
+        Ok(items)
+    }
+}
+
+impl<A: Wire, B: Wire> Wire for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok((A::decode(reader)?, B::decode(reader)?))
+    }
+}
+
+impl<A: Wire, B: Wire, C: Wire> Wire for (A, B, C) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+        self.2.encode(out);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok((A::decode(reader)?, B::decode(reader)?, C::decode(reader)?))
+    }
+}
+
+/// Only `n` is encoded, `nn` is derived from it
+impl Wire for EncryptionKey {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.n.encode(out)
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let n = BigInt::decode(reader)?;
+        let nn = &n * &n;
+        Ok(EncryptionKey { n, nn })
+    }
+}
+
+wire_struct!(DLogStatement { N, g, ni });
+
+impl Wire for VerifiableSS<Secp256k1> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.parameters.threshold.encode(out);
+        self.parameters.share_count.encode(out);
+        self.commitments.encode(out);
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        Ok(VerifiableSS {
+            parameters: ShamirSecretSharing {
+                threshold: u16::decode(reader)?,
+                share_count: u16::decode(reader)?,
+            },
+            commitments: Vec::decode(reader)?,
+        })
+    }
+}
+
+/// Mirror of `NiCorrectKeyProof`, which keeps `sigma_vec` private
+///
+/// Both structs derive serde the same way, so a proof is converted to and from the mirror
+/// through its serialized form.
+#[derive(Serialize, Deserialize)]
+struct NiCorrectKeyProofFields {
+    sigma_vec: Vec<BigInt>,
+}
+
+impl Wire for NiCorrectKeyProof {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let fields: NiCorrectKeyProofFields = serde_json::to_vec(self)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .expect("NiCorrectKeyProof is serialized as {sigma_vec: [...]}");
+        fields.sigma_vec.encode(out)
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, WireError> {
+        let fields = NiCorrectKeyProofFields {
+            sigma_vec: Vec::decode(reader)?,
+        };
+        serde_json::to_vec(&fields)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or(WireError::InvalidValue("correct key proof"))
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,165 @@
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use curv::BigInt;
+use paillier::{EncryptionKey, KeyGeneration, Paillier};
+use zk_paillier::zkproofs::NiCorrectKeyProof;
+
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::{from_bytes, to_bytes, Wire, WireError, WIRE_FORMAT_VERSION};
+
+fn round_trip<T: Wire>(value: &T) -> T {
+    from_bytes(&to_bytes(value)).unwrap()
+}
+
+fn encoded<T: Wire>(value: &T) -> Vec<u8> {
+    let mut out = vec![];
+    value.encode(&mut out);
+    out
+}
+
+fn with_version(bytes: &[u8]) -> Vec<u8> {
+    let mut out = vec![WIRE_FORMAT_VERSION];
+    out.extend_from_slice(bytes);
+    out
+}
+
+#[test]
+fn bigints_are_encoded_minimally() {
+    for n in [
+        BigInt::zero(),
+        BigInt::one(),
+        BigInt::from(-255),
+        BigInt::from(256),
+        BigInt::sample(2048),
+    ]
+    .iter()
+    {
+        assert_eq!(&round_trip(n), n);
+    }
+
+    assert_eq!(encoded(&BigInt::zero()), vec![0, 0, 0, 0, 0]);
+    assert_eq!(encoded(&BigInt::from(256)), vec![0, 0, 0, 0, 2, 1, 0]);
+    assert_eq!(encoded(&BigInt::from(-1)), vec![1, 0, 0, 0, 1, 1]);
+}
+
+#[test]
+fn non_canonical_bigints_are_rejected() {
+    let cases: &[&[u8]] = &[
+        // leading zero
+        &[0, 0, 0, 0, 2, 0, 1],
+        // negative zero
+        &[1, 0, 0, 0, 0],
+        // sign byte other than 0 or 1
+        &[2, 0, 0, 0, 1, 1],
+    ];
+    for bytes in cases {
+        assert_eq!(
+            from_bytes::<BigInt>(&with_version(bytes)),
+            Err(WireError::NonCanonicalBigInt)
+        );
+    }
+    assert_eq!(
+        from_bytes::<BigInt>(&with_version(&[0, 0, 0, 0, 3, 1])),
+        Err(WireError::UnexpectedEnd)
+    );
+}
+
+#[test]
+fn scalars_are_fixed_width() {
+    let scalar = Scalar::<Secp256k1>::from_bigint(&BigInt::one());
+    let bytes = encoded(&scalar);
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(bytes[31], 1);
+    assert_eq!(round_trip(&scalar), scalar);
+    assert_eq!(round_trip(&Scalar::<Secp256k1>::zero()), Scalar::zero());
+
+    let random = Scalar::<Secp256k1>::random();
+    assert_eq!(round_trip(&random), random);
+
+    let mut order = Scalar::<Secp256k1>::group_order().to_bytes();
+    order.insert(0, WIRE_FORMAT_VERSION);
+    assert_eq!(
+        from_bytes::<Scalar<Secp256k1>>(&order),
+        Err(WireError::NonCanonicalScalar)
+    );
+}
+
+#[test]
+fn points_are_compressed() {
+    let point = Point::<Secp256k1>::generator() * Scalar::random();
+    let bytes = encoded(&point);
+    assert_eq!(bytes.len(), 33);
+    assert_eq!(round_trip(&point), point);
+    assert_eq!(round_trip(&Point::<Secp256k1>::zero()), Point::zero());
+
+    // Uncompressed prefix within compressed length
+    let mut uncompressed = bytes.clone();
+    uncompressed[0] = 4;
+    assert_eq!(
+        from_bytes::<Point<Secp256k1>>(&with_version(&uncompressed)),
+        Err(WireError::InvalidPoint)
+    );
+    // x coordinate exceeding field modulus
+    let mut off_curve = vec![2u8];
+    off_curve.extend_from_slice(&[0xff; 32]);
+    assert_eq!(
+        from_bytes::<Point<Secp256k1>>(&with_version(&off_curve)),
+        Err(WireError::InvalidPoint)
+    );
+}
+
+#[test]
+fn sequence_length_is_bounded_by_input() {
+    let bytes = with_version(&[0xff, 0xff, 0xff, 0xff, 0]);
+    assert_eq!(
+        from_bytes::<Vec<BigInt>>(&bytes),
+        Err(WireError::UnexpectedEnd)
+    );
+
+    // A declared length that fits into the input must not reserve memory for that many decoded
+    // elements: every big integer takes at least 5 bytes of input but far more once decoded
+    let mut bytes = with_version(&[0x00, 0x10, 0x00, 0x00]);
+    bytes.resize(bytes.len() + 0x0010_0000, 0xff);
+    assert_eq!(
+        from_bytes::<Vec<Vec<BigInt>>>(&bytes),
+        Err(WireError::UnexpectedEnd)
+    );
+
+    let items = vec![BigInt::from(1), BigInt::from(2)];
+    assert_eq!(round_trip(&items), items);
+    let strings = vec!["a".to_owned(), "".to_owned()];
+    assert_eq!(round_trip(&strings), strings);
+}
+
+#[test]
+fn trailing_bytes_and_unknown_versions_are_rejected() {
+    let mut bytes = to_bytes(&42u16);
+    bytes.push(0);
+    assert_eq!(from_bytes::<u16>(&bytes), Err(WireError::TrailingBytes(1)));
+
+    let mut bytes = to_bytes(&42u16);
+    bytes[0] = WIRE_FORMAT_VERSION + 1;
+    assert_eq!(
+        from_bytes::<u16>(&bytes),
+        Err(WireError::UnsupportedVersion(WIRE_FORMAT_VERSION + 1))
+    );
+}
+
+#[test]
+fn encryption_key_is_encoded_by_modulus() {
+    let (ek, _dk) = Paillier::keypair().keys();
+    assert_eq!(encoded(&ek), encoded(&ek.n));
+    let decoded: EncryptionKey = round_trip(&ek);
+    assert_eq!(decoded.n, ek.n);
+    assert_eq!(decoded.nn, ek.nn);
+}
+
+#[test]
+fn correct_key_proof_round_trips() {
+    let (ek, dk) = Paillier::keypair().keys();
+    let sid = SessionId::random();
+    let proof = NiCorrectKeyProof::proof(&dk, Some(sid.correct_key_proof_salt_bigint()));
+    let decoded: NiCorrectKeyProof = round_trip(&proof);
+    assert_eq!(encoded(&decoded), encoded(&proof));
+    assert!(decoded.verify(&ek, &sid.correct_key_proof_salt()).is_ok());
+}
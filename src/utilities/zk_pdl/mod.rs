@@ -35,6 +35,9 @@ use sha2::Sha256;
 use thiserror::Error;
 use zk_paillier::zkproofs::IncorrectProof;
 use zk_paillier::zkproofs::RangeProofNi;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::utilities::fingerprint::Fingerprint;
 
 #[derive(Error, Debug)]
 pub enum ZkPdlError {
@@ -58,6 +61,33 @@ pub struct PDLWitness {
     pub dk: DecryptionKey,
 }
 
+impl Zeroize for PDLWitness {
+    fn zeroize(&mut self) {
+        self.x = Scalar::zero();
+        self.r.zeroize();
+        self.dk.p.zeroize();
+        self.dk.q.zeroize();
+    }
+}
+
+impl Drop for PDLWitness {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for PDLWitness {}
+
+impl std::fmt::Debug for PDLWitness {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PDLWitness")
+            .field("x", &Fingerprint::of_scalar(&self.x))
+            .field("r", &Fingerprint::of_bigint(&self.r))
+            .field("dk", &Fingerprint::of_bigint(&self.dk.p))
+            .finish()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PDLVerifierState {
     pub c_tag: BigInt,
@@ -21,6 +21,8 @@
 //! Statement: (c, pk, Q, G)
 //! witness (x, r) such that Q = xG, c = Enc(pk, x, r)
 //! note that because of the range proof, the proof has a slack in the range: x in [-q^3, q^3]
+//!
+//! The challenge is bound to a [SessionId].
 
 use curv::arithmetic::traits::*;
 use curv::cryptographic_primitives::hashing::{Digest, DigestExt};
@@ -30,6 +32,10 @@ use paillier::EncryptionKey;
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use thiserror::Error;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
 
 #[derive(Error, Debug)]
 pub enum ZkPdlWithSlackError {
@@ -53,6 +59,21 @@ pub struct PDLwSlackWitness {
     pub r: BigInt,
 }
 
+impl Zeroize for PDLwSlackWitness {
+    fn zeroize(&mut self) {
+        self.x = Scalar::zero();
+        self.r.zeroize();
+    }
+}
+
+impl Drop for PDLwSlackWitness {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for PDLwSlackWitness {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PDLwSlackProof {
     z: BigInt,
@@ -64,8 +85,22 @@ pub struct PDLwSlackProof {
     s3: BigInt,
 }
 
+wire_struct!(PDLwSlackProof {
+    z,
+    u1,
+    u2,
+    u3,
+    s1,
+    s2,
+    s3,
+});
+
 impl PDLwSlackProof {
-    pub fn prove(witness: &PDLwSlackWitness, statement: &PDLwSlackStatement) -> Self {
+    pub fn prove(
+        witness: &PDLwSlackWitness,
+        statement: &PDLwSlackStatement,
+        sid: &SessionId,
+    ) -> Self {
         let q3 = Scalar::<Secp256k1>::group_order().pow(3);
         let q_N_tilde = Scalar::<Secp256k1>::group_order() * &statement.N_tilde;
         let q3_N_tilde = &q3 * &statement.N_tilde;
@@ -100,6 +135,7 @@ impl PDLwSlackProof {
         );
 
         let e = Sha256::new()
+            .chain(sid.as_bytes())
             .chain_bigint(&BigInt::from_bytes(statement.G.to_bytes(true).as_ref()))
             .chain_bigint(&BigInt::from_bytes(statement.Q.to_bytes(true).as_ref()))
             .chain_bigint(&statement.ciphertext)
@@ -124,8 +160,13 @@ impl PDLwSlackProof {
         }
     }
 
-    pub fn verify(&self, statement: &PDLwSlackStatement) -> Result<(), ZkPdlWithSlackError> {
+    pub fn verify(
+        &self,
+        statement: &PDLwSlackStatement,
+        sid: &SessionId,
+    ) -> Result<(), ZkPdlWithSlackError> {
         let e = Sha256::new()
+            .chain(sid.as_bytes())
             .chain_bigint(&BigInt::from_bytes(statement.G.to_bytes(true).as_ref()))
             .chain_bigint(&BigInt::from_bytes(statement.Q.to_bytes(true).as_ref()))
             .chain_bigint(&statement.ciphertext)
@@ -6,7 +6,10 @@ use paillier::core::Randomness;
 use paillier::traits::{EncryptWithChosenRandomness, KeyGeneration};
 use paillier::Paillier;
 use paillier::RawPlaintext;
-use zk_paillier::zkproofs::{CompositeDLogProof, DLogStatement};
+use zk_paillier::zkproofs::DLogStatement;
+
+use crate::utilities::session_id::SessionId;
+use crate::utilities::zk_sigma::CompositeDLogProof;
 
 #[test]
 fn test_zk_pdl_with_slack() {
@@ -27,7 +30,8 @@ fn test_zk_pdl_with_slack() {
         ni: h2.clone(),
     };
 
-    let composite_dlog_proof = CompositeDLogProof::prove(&statement, &xhi);
+    let sid = SessionId::new(b"pdl with slack test");
+    let composite_dlog_proof = CompositeDLogProof::prove(&statement, &xhi, &sid);
 
     // generate the scalar secret and Paillier encrypt it
     let (ek, _dk) = Paillier::keypair().keys();
@@ -59,11 +63,11 @@ fn test_zk_pdl_with_slack() {
 
     let pdl_w_slack_witness = PDLwSlackWitness { x, r: randomness.0 };
 
-    let proof = PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement);
+    let proof = PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement, &sid);
     // verify h1,h2, N_tilde
-    let setup_result = composite_dlog_proof.verify(&statement);
+    let setup_result = composite_dlog_proof.verify(&statement, &sid);
     assert!(setup_result.is_ok());
-    let result = proof.verify(&pdl_w_slack_statement);
+    let result = proof.verify(&pdl_w_slack_statement, &sid);
     assert!(result.is_ok());
 }
 
@@ -87,7 +91,8 @@ fn test_zk_pdl_with_slack_soundness() {
         ni: h2.clone(),
     };
 
-    let composite_dlog_proof = CompositeDLogProof::prove(&statement, &xhi);
+    let sid = SessionId::new(b"pdl with slack test");
+    let composite_dlog_proof = CompositeDLogProof::prove(&statement, &xhi, &sid);
 
     // generate the scalar secret and Paillier encrypt it
     let (ek, _dk) = Paillier::keypair().keys();
@@ -120,10 +125,50 @@ fn test_zk_pdl_with_slack_soundness() {
 
     let pdl_w_slack_witness = PDLwSlackWitness { x, r: randomness.0 };
 
-    let proof = PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement);
+    let proof = PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement, &sid);
     // verify h1,h2, N_tilde
-    let setup_result = composite_dlog_proof.verify(&statement);
+    let setup_result = composite_dlog_proof.verify(&statement, &sid);
     assert!(setup_result.is_ok());
-    let result = proof.verify(&pdl_w_slack_statement);
+    let result = proof.verify(&pdl_w_slack_statement, &sid);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_zk_pdl_with_slack_rejects_replay_from_other_session() {
+    let (ek_tilde, dk_tilde) = Paillier::keypair().keys();
+    let one = BigInt::one();
+    let phi = (&dk_tilde.p - &one) * (&dk_tilde.q - &one);
+    let h1 = BigInt::sample_below(&phi);
+    let xhi = BigInt::sample_below(&BigInt::from(2).pow(256_u32));
+    let h1_inv = BigInt::mod_inv(&h1, &ek_tilde.n).unwrap();
+    let h2 = BigInt::mod_pow(&h1_inv, &xhi, &ek_tilde.n);
+
+    let (ek, _dk) = Paillier::keypair().keys();
+    let randomness = Randomness::sample(&ek);
+    let x = Scalar::<Secp256k1>::random();
+    let c = Paillier::encrypt_with_chosen_randomness(
+        &ek,
+        RawPlaintext::from(x.to_bigint()),
+        &randomness,
+    )
+    .0
+    .into_owned();
+
+    let pdl_w_slack_statement = PDLwSlackStatement {
+        ciphertext: c,
+        ek,
+        Q: Point::generator() * &x,
+        G: Point::generator().to_point(),
+        h1,
+        h2,
+        N_tilde: ek_tilde.n,
+    };
+    let pdl_w_slack_witness = PDLwSlackWitness { x, r: randomness.0 };
+
+    let sid = SessionId::new(b"session a");
+    let proof = PDLwSlackProof::prove(&pdl_w_slack_witness, &pdl_w_slack_statement, &sid);
+    assert!(proof.verify(&pdl_w_slack_statement, &sid).is_ok());
+    assert!(proof
+        .verify(&pdl_w_slack_statement, &SessionId::new(b"session b"))
+        .is_err());
+}
@@ -0,0 +1,69 @@
+//! Proof of knowledge of discrete log in composite modulus group: given `(N, g, ni)`, prover
+//! knows `x` such that `ni = g^(-x) mod N`
+//!
+//! Used to prove that ring-Pedersen parameters `h1, h2, N_tilde` were generated correctly.
+
+use curv::arithmetic::traits::*;
+use curv::BigInt;
+use serde::{Deserialize, Serialize};
+use zk_paillier::zkproofs::DLogStatement;
+
+use super::{challenge_bigint, ProofError};
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+
+const DOMAIN: &[u8] = b"multi-party-ecdsa/zk-composite-dlog/v1";
+/// Bit length of the challenge
+const K: usize = 128;
+/// Statistical hiding parameter
+const K_PRIME: usize = 128;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompositeDLogProof {
+    pub x: BigInt,
+    pub y: BigInt,
+}
+
+wire_struct!(CompositeDLogProof { x, y });
+
+impl CompositeDLogProof {
+    pub fn prove(statement: &DLogStatement, secret: &BigInt, sid: &SessionId) -> Self {
+        let r_bits = statement.N.bit_length() + K + K_PRIME;
+        let r = BigInt::sample(r_bits);
+        let x = BigInt::mod_pow(&statement.g, &r, &statement.N);
+
+        let e = Self::challenge(&x, statement, sid);
+        let y = r + e * secret;
+        Self { x, y }
+    }
+
+    pub fn verify(&self, statement: &DLogStatement, sid: &SessionId) -> Result<(), ProofError> {
+        let in_group = |v: &BigInt| {
+            v > &BigInt::zero() && v < &statement.N && BigInt::mod_inv(v, &statement.N).is_some()
+        };
+        if !in_group(&statement.g) || !in_group(&statement.ni) || !in_group(&self.x) {
+            return Err(ProofError("composite dlog"));
+        }
+        if self.y < BigInt::zero() {
+            return Err(ProofError("composite dlog"));
+        }
+
+        let e = Self::challenge(&self.x, statement, sid);
+        let g_y = BigInt::mod_pow(&statement.g, &self.y, &statement.N);
+        let ni_e = BigInt::mod_pow(&statement.ni, &e, &statement.N);
+        if BigInt::mod_mul(&g_y, &ni_e, &statement.N) == self.x {
+            Ok(())
+        } else {
+            Err(ProofError("composite dlog"))
+        }
+    }
+
+    fn challenge(x: &BigInt, statement: &DLogStatement, sid: &SessionId) -> BigInt {
+        challenge_bigint(
+            sid,
+            DOMAIN,
+            &[x, &statement.g, &statement.N, &statement.ni],
+            K,
+        )
+    }
+}
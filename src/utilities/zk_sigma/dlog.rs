@@ -0,0 +1,50 @@
+//! Proof of knowledge of discrete log: given `pk`, prover knows `sk` such that `pk = sk·G`
+
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use serde::{Deserialize, Serialize};
+
+use super::{challenge, ProofError};
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+
+const DOMAIN: &[u8] = b"multi-party-ecdsa/zk-dlog/v1";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DLogProof {
+    pub pk: Point<Secp256k1>,
+    pub pk_t_rand_commitment: Point<Secp256k1>,
+    pub challenge_response: Scalar<Secp256k1>,
+}
+
+wire_struct!(DLogProof {
+    pk,
+    pk_t_rand_commitment,
+    challenge_response,
+});
+
+impl DLogProof {
+    pub fn prove(sk: &Scalar<Secp256k1>, sid: &SessionId) -> Self {
+        let generator = Point::<Secp256k1>::generator();
+        let sk_t_rand_commitment = Scalar::<Secp256k1>::random();
+        let pk_t_rand_commitment = generator * &sk_t_rand_commitment;
+        let pk = generator * sk;
+
+        let e = challenge(sid, DOMAIN, &[&pk_t_rand_commitment, &pk]);
+        let challenge_response = &sk_t_rand_commitment - &(e * sk);
+        Self {
+            pk,
+            pk_t_rand_commitment,
+            challenge_response,
+        }
+    }
+
+    pub fn verify(&self, sid: &SessionId) -> Result<(), ProofError> {
+        let e = challenge(sid, DOMAIN, &[&self.pk_t_rand_commitment, &self.pk]);
+        let pk_verifier = Point::generator() * &self.challenge_response + &self.pk * &e;
+        if pk_verifier == self.pk_t_rand_commitment {
+            Ok(())
+        } else {
+            Err(ProofError("dlog"))
+        }
+    }
+}
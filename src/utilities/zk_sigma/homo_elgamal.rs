@@ -0,0 +1,99 @@
+#![allow(non_snake_case)]
+//! Proof of correct homomorphic ElGamal encryption: given `(G, H, Y, D, E)`, prover knows
+//! `x, r` such that `D = x·H + r·Y` and `E = r·G`
+
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{challenge, ProofError};
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+
+const DOMAIN: &[u8] = b"multi-party-ecdsa/zk-homo-elgamal/v1";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HomoElGamalStatement {
+    pub G: Point<Secp256k1>,
+    pub H: Point<Secp256k1>,
+    pub Y: Point<Secp256k1>,
+    pub D: Point<Secp256k1>,
+    pub E: Point<Secp256k1>,
+}
+
+#[derive(Clone)]
+pub struct HomoElGamalWitness {
+    pub r: Scalar<Secp256k1>,
+    pub x: Scalar<Secp256k1>,
+}
+
+impl Zeroize for HomoElGamalWitness {
+    fn zeroize(&mut self) {
+        self.r = Scalar::zero();
+        self.x = Scalar::zero();
+    }
+}
+
+impl Drop for HomoElGamalWitness {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl ZeroizeOnDrop for HomoElGamalWitness {}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HomoELGamalProof {
+    pub T: Point<Secp256k1>,
+    pub A3: Point<Secp256k1>,
+    pub z1: Scalar<Secp256k1>,
+    pub z2: Scalar<Secp256k1>,
+}
+
+wire_struct!(HomoELGamalProof { T, A3, z1, z2 });
+
+impl HomoELGamalProof {
+    pub fn prove(
+        w: &HomoElGamalWitness,
+        delta: &HomoElGamalStatement,
+        sid: &SessionId,
+    ) -> Self {
+        let s1 = Scalar::<Secp256k1>::random();
+        let s2 = Scalar::<Secp256k1>::random();
+        let A1 = &delta.H * &s1;
+        let A2 = &delta.Y * &s2;
+        let A3 = &delta.G * &s2;
+        let T = A1 + A2;
+
+        let e = Self::challenge(&T, &A3, delta, sid);
+        let z1 = &s1 + &(&w.x * &e);
+        let z2 = &s2 + &(&w.r * &e);
+        Self { T, A3, z1, z2 }
+    }
+
+    pub fn verify(&self, delta: &HomoElGamalStatement, sid: &SessionId) -> Result<(), ProofError> {
+        let e = Self::challenge(&self.T, &self.A3, delta, sid);
+        let z1H_plus_z2Y = &delta.H * &self.z1 + &delta.Y * &self.z2;
+        let T_plus_eD = &self.T + &delta.D * &e;
+        let z2G = &delta.G * &self.z2;
+        let A3_plus_eE = &self.A3 + &delta.E * &e;
+        if z1H_plus_z2Y == T_plus_eD && z2G == A3_plus_eE {
+            Ok(())
+        } else {
+            Err(ProofError("homomorphic elgamal"))
+        }
+    }
+
+    fn challenge(
+        T: &Point<Secp256k1>,
+        A3: &Point<Secp256k1>,
+        delta: &HomoElGamalStatement,
+        sid: &SessionId,
+    ) -> Scalar<Secp256k1> {
+        challenge(
+            sid,
+            DOMAIN,
+            &[T, A3, &delta.G, &delta.H, &delta.Y, &delta.D, &delta.E],
+        )
+    }
+}
@@ -0,0 +1,69 @@
+/*
+    Multi-party ECDSA
+
+    Copyright 2018 by Kzen Networks
+
+    This file is part of Multi-party ECDSA library
+    (https://github.com/KZen-networks/multi-party-ecdsa)
+
+    Multi-party ECDSA is free software: you can redistribute
+    it and/or modify it under the terms of the GNU General Public
+    License as published by the Free Software Foundation, either
+    version 3 of the License, or (at your option) any later version.
+
+    @license GPL-3.0+ <https://github.com/KZen-networks/multi-party-ecdsa/blob/master/LICENSE>
+*/
+
+//! Sigma protocols made non-interactive with Fiat–Shamir transform, where every challenge is
+//! bound to a [SessionId].
+//!
+//! These are the same proofs as `curv`'s `DLogProof`, `PedersenProof`, `HomoELGamalProof` and
+//! `zk-paillier`'s `CompositeDLogProof`, which don't let the caller add anything to the challenge.
+
+pub mod composite_dlog;
+pub mod dlog;
+pub mod homo_elgamal;
+pub mod pedersen;
+
+pub use composite_dlog::CompositeDLogProof;
+pub use dlog::DLogProof;
+pub use homo_elgamal::{HomoELGamalProof, HomoElGamalStatement, HomoElGamalWitness};
+pub use pedersen::PedersenProof;
+
+use curv::arithmetic::traits::*;
+use curv::cryptographic_primitives::hashing::{Digest, DigestExt};
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use sha2::Sha256;
+use thiserror::Error;
+
+use crate::utilities::session_id::SessionId;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+#[error("{0} proof verification failed")]
+pub struct ProofError(pub &'static str);
+
+/// Challenge of a proof over secp256k1 points
+fn challenge(sid: &SessionId, domain: &[u8], points: &[&Point<Secp256k1>]) -> Scalar<Secp256k1> {
+    let hash = points
+        .iter()
+        .fold(Sha256::new().chain(domain).chain(sid.as_bytes()), |acc, p| {
+            acc.chain(p.to_bytes(true).as_ref())
+        })
+        .result_bigint();
+    Scalar::from_bigint(&hash)
+}
+
+/// Challenge of a proof over integers, truncated to `bits`
+fn challenge_bigint(sid: &SessionId, domain: &[u8], values: &[&BigInt], bits: usize) -> BigInt {
+    let hash = values
+        .iter()
+        .fold(Sha256::new().chain(domain).chain(sid.as_bytes()), |acc, v| {
+            acc.chain_bigint(v)
+        })
+        .result_bigint();
+    hash.modulus(&BigInt::from(2).pow(bits as u32))
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,53 @@
+//! Proof of knowledge of opening of Pedersen commitment: given `com`, prover knows `m, r` such
+//! that `com = m·G + r·H`
+
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use serde::{Deserialize, Serialize};
+
+use super::{challenge, ProofError};
+use crate::utilities::session_id::SessionId;
+use crate::utilities::wire::wire_struct;
+
+const DOMAIN: &[u8] = b"multi-party-ecdsa/zk-pedersen/v1";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PedersenProof {
+    pub a1: Point<Secp256k1>,
+    pub a2: Point<Secp256k1>,
+    pub com: Point<Secp256k1>,
+    pub z1: Scalar<Secp256k1>,
+    pub z2: Scalar<Secp256k1>,
+}
+
+wire_struct!(PedersenProof { a1, a2, com, z1, z2 });
+
+impl PedersenProof {
+    pub fn prove(m: &Scalar<Secp256k1>, r: &Scalar<Secp256k1>, sid: &SessionId) -> Self {
+        let g = Point::<Secp256k1>::generator();
+        let h = Point::<Secp256k1>::base_point2();
+        let s1 = Scalar::<Secp256k1>::random();
+        let s2 = Scalar::<Secp256k1>::random();
+        let a1 = g * &s1;
+        let a2 = h * &s2;
+        let com = g * m + h * r;
+
+        let e = challenge(sid, DOMAIN, &[&g.to_point(), h, &com, &a1, &a2]);
+        let z1 = &s1 + &(&e * m);
+        let z2 = &s2 + &(&e * r);
+        Self { a1, a2, com, z1, z2 }
+    }
+
+    pub fn verify(&self, sid: &SessionId) -> Result<(), ProofError> {
+        let g = Point::<Secp256k1>::generator();
+        let h = Point::<Secp256k1>::base_point2();
+        let e = challenge(sid, DOMAIN, &[&g.to_point(), h, &self.com, &self.a1, &self.a2]);
+
+        let lhs = g * &self.z1 + h * &self.z2;
+        let rhs = &self.a1 + &self.a2 + &self.com * &e;
+        if lhs == rhs {
+            Ok(())
+        } else {
+            Err(ProofError("pedersen"))
+        }
+    }
+}
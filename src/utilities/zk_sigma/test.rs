@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+use curv::arithmetic::traits::*;
+use curv::elliptic::curves::{secp256_k1::Secp256k1, Point, Scalar};
+use curv::BigInt;
+use paillier::{KeyGeneration, Paillier};
+use zk_paillier::zkproofs::DLogStatement;
+
+use crate::utilities::session_id::SessionId;
+use crate::utilities::zk_sigma::*;
+
+fn sessions() -> (SessionId, SessionId) {
+    (SessionId::new(b"session a"), SessionId::new(b"session b"))
+}
+
+#[test]
+fn dlog_proof_is_bound_to_session() {
+    let (sid, other_sid) = sessions();
+    let proof = DLogProof::prove(&Scalar::random(), &sid);
+    assert!(proof.verify(&sid).is_ok());
+    assert!(proof.verify(&other_sid).is_err());
+
+    let mut wrong_pk = proof;
+    wrong_pk.pk = Point::generator() * Scalar::<Secp256k1>::random();
+    assert!(wrong_pk.verify(&sid).is_err());
+}
+
+#[test]
+fn pedersen_proof_is_bound_to_session() {
+    let (sid, other_sid) = sessions();
+    let proof = PedersenProof::prove(&Scalar::random(), &Scalar::random(), &sid);
+    assert!(proof.verify(&sid).is_ok());
+    assert!(proof.verify(&other_sid).is_err());
+}
+
+#[test]
+fn homo_elgamal_proof_is_bound_to_session() {
+    let (sid, other_sid) = sessions();
+    let witness = HomoElGamalWitness {
+        r: Scalar::random(),
+        x: Scalar::random(),
+    };
+    let G = Point::generator() * Scalar::<Secp256k1>::random();
+    let H = Point::<Secp256k1>::base_point2().clone();
+    let Y = Point::generator().to_point();
+    let statement = HomoElGamalStatement {
+        D: &H * &witness.x + &Y * &witness.r,
+        E: &G * &witness.r,
+        G,
+        H,
+        Y,
+    };
+    let proof = HomoELGamalProof::prove(&witness, &statement, &sid);
+    assert!(proof.verify(&statement, &sid).is_ok());
+    assert!(proof.verify(&statement, &other_sid).is_err());
+}
+
+#[test]
+fn composite_dlog_proof_is_bound_to_session() {
+    let (sid, other_sid) = sessions();
+    let (ek_tilde, dk_tilde) = Paillier::keypair().keys();
+    let phi = (&dk_tilde.p - BigInt::one()) * (&dk_tilde.q - BigInt::one());
+    let h1 = BigInt::sample_below(&ek_tilde.n);
+    let xhi = BigInt::sample_below(&phi);
+    let h1_inv = BigInt::mod_inv(&h1, &ek_tilde.n).unwrap();
+    let statement = DLogStatement {
+        N: ek_tilde.n.clone(),
+        g: h1,
+        ni: BigInt::mod_pow(&h1_inv, &xhi, &ek_tilde.n),
+    };
+
+    let proof = CompositeDLogProof::prove(&statement, &xhi, &sid);
+    assert!(proof.verify(&statement, &sid).is_ok());
+    assert!(proof.verify(&statement, &other_sid).is_err());
+}
@@ -0,0 +1,423 @@
+//! End-to-end tests of `mpc-ecdsa` binary: every party runs in its own process, parties talk over
+//! TCP on localhost, or via relay server running in the test process (if `relay` feature is
+//! enabled)
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+#[cfg(feature = "relay")]
+use std::time::Duration;
+
+use curv::elliptic::curves::{Point, Scalar, Secp256k1};
+use serde_json::{json, Value};
+
+use multi_party_ecdsa::protocols::gg_2020::party_i::SignatureRecid;
+
+const BIN: &str = env!("CARGO_BIN_EXE_mpc-ecdsa");
+
+struct Workdir(PathBuf);
+
+impl Workdir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("mpc-ecdsa-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for Workdir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn mpc_ecdsa(dir: &Workdir) -> Command {
+    let mut cmd = Command::new(BIN);
+    cmd.current_dir(&dir.0).env_remove("MPC_ECDSA_PASSPHRASE");
+    cmd
+}
+
+fn run(cmd: &mut Command) -> String {
+    let output = cmd.output().unwrap();
+    assert_success(&output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn spawn(cmd: &mut Command) -> Child {
+    cmd.stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "mpc-ecdsa failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn wait_all(children: Vec<Child>) -> Vec<String> {
+    children
+        .into_iter()
+        .map(|child| {
+            let output = child.wait_with_output().unwrap();
+            assert_success(&output);
+            String::from_utf8(output.stdout).unwrap()
+        })
+        .collect()
+}
+
+/// Generates noise keys of `n` parties and writes peers file
+fn setup_peers(dir: &Workdir, n: u16) -> PathBuf {
+    let mut peers = BTreeMap::new();
+    for i in 1..=n {
+        let public_key = run(mpc_ecdsa(dir)
+            .arg("generate-noise-key")
+            .arg("-o")
+            .arg(dir.file(&format!("noise{}.key", i))));
+        peers.insert(
+            i,
+            json!({
+                "address": format!("127.0.0.1:{}", free_port()),
+                "public_key": public_key.trim(),
+            }),
+        );
+    }
+    let path = dir.file("peers.json");
+    fs::write(&path, serde_json::to_vec(&peers).unwrap()).unwrap();
+    path
+}
+
+fn tcp_args<'c>(cmd: &'c mut Command, dir: &Workdir, peers: &Path, i: u16) -> &'c mut Command {
+    cmd.arg("--peers")
+        .arg(peers)
+        .arg("--noise-key")
+        .arg(dir.file(&format!("noise{}.key", i)))
+        .args(&["--round-timeout", "60"])
+}
+
+/// Launches relay server on a background thread, returns its address
+#[cfg(feature = "relay")]
+fn spawn_relay() -> String {
+    use multi_party_ecdsa::relay::{self, RelayConfig};
+
+    let port = free_port();
+    std::thread::spawn(move || {
+        let figment = rocket::Config::figment()
+            .merge(("address", "127.0.0.1"))
+            .merge(("port", port))
+            .merge(("log_level", "off"));
+        let server = relay::build_with_figment(figment, RelayConfig::default()).unwrap();
+        let _ = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(server.launch());
+    });
+    while std::net::TcpStream::connect(("127.0.0.1", port)).is_err() {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    format!("http://127.0.0.1:{}/", port)
+}
+
+#[cfg(feature = "relay")]
+fn relay_args<'c>(cmd: &'c mut Command, relay: &str) -> &'c mut Command {
+    cmd.args(&["--relay", relay])
+        .args(&["--room", "test"])
+        .args(&["--round-timeout", "60"])
+}
+
+fn keygen(dir: &Workdir, peers: &Path, t: u16, n: u16) -> Vec<String> {
+    let parties = (1..=n)
+        .map(|i| {
+            let mut cmd = mpc_ecdsa(dir);
+            cmd.arg("keygen");
+            tcp_args(&mut cmd, dir, peers, i)
+                .args(&["--session", "test-keygen"])
+                .args(&["-i", &i.to_string()])
+                .args(&["-t", &t.to_string()])
+                .args(&["-n", &n.to_string()])
+                .arg("-o")
+                .arg(dir.file(&format!("key{}.json", i)));
+            spawn(&mut cmd)
+        })
+        .collect();
+    wait_all(parties)
+}
+
+#[test]
+fn keygen_sign_and_verify_over_tcp() {
+    let dir = Workdir::new("sign");
+    let peers = setup_peers(&dir, 3);
+
+    let public_keys = keygen(&dir, &peers, 1, 3);
+    let public_key = public_keys[0].trim().to_owned();
+    assert!(public_keys.iter().all(|pk| pk.trim() == public_key));
+
+    let signers = [1u16, 3];
+    let parties = signers
+        .iter()
+        .map(|&i| {
+            let mut cmd = mpc_ecdsa(&dir);
+            cmd.arg("sign");
+            tcp_args(&mut cmd, &dir, &peers, i)
+                .args(&["--session", "test-sign"])
+                .arg("-k")
+                .arg(dir.file(&format!("key{}.json", i)))
+                .args(&["-p", "1,3"])
+                .args(&["--data", "hello world"])
+                .arg("-o")
+                .arg(dir.file(&format!("signature{}.json", i)));
+            spawn(&mut cmd)
+        })
+        .collect();
+    wait_all(parties);
+
+    let output = run(mpc_ecdsa(&dir)
+        .arg("verify")
+        .args(&["--public-key", &public_key])
+        .arg("--signature")
+        .arg(dir.file("signature1.json"))
+        .args(&["--data", "hello world"]));
+    assert_eq!(output.trim(), "signature is valid");
+
+    let output = mpc_ecdsa(&dir)
+        .arg("verify")
+        .args(&["--public-key", &public_key])
+        .arg("--signature")
+        .arg(dir.file("signature3.json"))
+        .args(&["--data", "another message"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn presignature_is_consumed_by_sign() {
+    let dir = Workdir::new("presign");
+    let peers = setup_peers(&dir, 2);
+    let public_key = keygen(&dir, &peers, 1, 2)[0].trim().to_owned();
+
+    let parties = (1..=2)
+        .map(|i| {
+            let mut cmd = mpc_ecdsa(&dir);
+            cmd.arg("presign");
+            tcp_args(&mut cmd, &dir, &peers, i)
+                .args(&["--session", "test-presign"])
+                .arg("-k")
+                .arg(dir.file(&format!("key{}.json", i)))
+                .args(&["-p", "1,2"])
+                .arg("-o")
+                .arg(dir.file(&format!("presignature{}.json", i)));
+            spawn(&mut cmd)
+        })
+        .collect();
+    wait_all(parties);
+
+    let parties = (1..=2)
+        .map(|i| {
+            let mut cmd = mpc_ecdsa(&dir);
+            cmd.arg("sign");
+            tcp_args(&mut cmd, &dir, &peers, i)
+                .arg("--presignature")
+                .arg(dir.file(&format!("presignature{}.json", i)))
+                .args(&["--digest", &"ab".repeat(32)]);
+            spawn(&mut cmd)
+        })
+        .collect();
+    let signatures = wait_all(parties);
+    for i in 1..=2 {
+        assert!(!dir.file(&format!("presignature{}.json", i)).exists());
+    }
+
+    fs::write(dir.file("signature.json"), &signatures[0]).unwrap();
+    run(mpc_ecdsa(&dir)
+        .arg("verify")
+        .args(&["--public-key", &public_key])
+        .arg("--signature")
+        .arg(dir.file("signature.json"))
+        .args(&["--digest", &"ab".repeat(32)]));
+}
+
+#[test]
+fn degenerate_signature_is_rejected_without_panic() {
+    let dir = Workdir::new("degenerate");
+    let public_key = hex::encode(Point::<Secp256k1>::generator().to_point().to_bytes(true));
+
+    for (r, s) in [
+        (Scalar::random(), Scalar::zero()),
+        (Scalar::zero(), Scalar::random()),
+    ]
+    .iter()
+    {
+        let signature = SignatureRecid {
+            r: r.clone(),
+            s: s.clone(),
+            recid: 0,
+        };
+        fs::write(
+            dir.file("signature.json"),
+            serde_json::to_vec(&signature).unwrap(),
+        )
+        .unwrap();
+
+        let output = mpc_ecdsa(&dir)
+            .arg("verify")
+            .args(&["--public-key", &public_key])
+            .arg("--signature")
+            .arg(dir.file("signature.json"))
+            .args(&["--data", "hello world"])
+            .output()
+            .unwrap();
+        // panic would exit with code 101
+        assert_eq!(output.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("signature is invalid"));
+    }
+}
+
+#[test]
+fn inspect_and_export_public_key() {
+    let dir = Workdir::new("inspect");
+    let peers = setup_peers(&dir, 2);
+    let public_key = keygen(&dir, &peers, 1, 2)[1].trim().to_owned();
+
+    let info: Value = serde_json::from_str(&run(mpc_ecdsa(&dir)
+        .arg("inspect-key")
+        .arg("-k")
+        .arg(dir.file("key2.json"))))
+    .unwrap();
+    assert_eq!(
+        info,
+        json!({
+            "public_key": public_key,
+            "own_party_index": 2,
+            "threshold": 1,
+            "share_count": 2,
+            "sealed": false,
+        })
+    );
+
+    let exported = run(mpc_ecdsa(&dir)
+        .arg("export-pubkey")
+        .arg("-k")
+        .arg(dir.file("key2.json")));
+    assert_eq!(exported.trim(), public_key);
+
+    let address = run(mpc_ecdsa(&dir)
+        .arg("export-pubkey")
+        .arg("-k")
+        .arg(dir.file("key2.json"))
+        .args(&["--format", "ethereum"]));
+    assert!(address.trim().starts_with("0x"));
+    assert_eq!(address.trim().len(), 42);
+}
+
+#[cfg(feature = "relay")]
+#[test]
+fn keygen_presign_and_sign_over_relay_with_sealed_files() {
+    const PASSPHRASE: &str = "correct horse";
+
+    let dir = Workdir::new("relay");
+    let relay = spawn_relay();
+    let with_passphrase = |cmd: &mut Command| {
+        cmd.env("MPC_ECDSA_PASSPHRASE", PASSPHRASE);
+    };
+
+    let parties = (1..=3)
+        .map(|i| {
+            let mut cmd = mpc_ecdsa(&dir);
+            with_passphrase(&mut cmd);
+            cmd.arg("keygen");
+            relay_args(&mut cmd, &relay)
+                .args(&["--session", "test-keygen"])
+                .args(&["-i", &i.to_string()])
+                .args(&["-t", "1"])
+                .args(&["-n", "3"])
+                .arg("-o")
+                .arg(dir.file(&format!("key{}.json", i)));
+            spawn(&mut cmd)
+        })
+        .collect();
+    let public_key = wait_all(parties)[0].trim().to_owned();
+
+    let signers = [1u16, 3];
+    let parties = signers
+        .iter()
+        .map(|&i| {
+            let mut cmd = mpc_ecdsa(&dir);
+            with_passphrase(&mut cmd);
+            cmd.arg("presign");
+            relay_args(&mut cmd, &relay)
+                .args(&["--session", "test-presign"])
+                .arg("-k")
+                .arg(dir.file(&format!("key{}.json", i)))
+                .args(&["-p", "1,3"])
+                .arg("-o")
+                .arg(dir.file(&format!("presignature{}.json", i)));
+            spawn(&mut cmd)
+        })
+        .collect();
+    wait_all(parties);
+
+    for &i in &signers {
+        let presignature: Value = serde_json::from_slice(
+            &fs::read(dir.file(&format!("presignature{}.json", i))).unwrap(),
+        )
+        .unwrap();
+        let offline_stage = &presignature["offline_stage"];
+        assert!(offline_stage.get("ciphertext").is_some());
+        assert!(offline_stage.get("sigma_i").is_none());
+    }
+
+    // sealed presignature can't be opened without passphrase, and it's kept intact
+    let mut cmd = mpc_ecdsa(&dir);
+    cmd.arg("sign");
+    let output = relay_args(&mut cmd, &relay)
+        .arg("--presignature")
+        .arg(dir.file("presignature1.json"))
+        .args(&["--data", "hello world"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(dir.file("presignature1.json").exists());
+
+    let parties = signers
+        .iter()
+        .map(|&i| {
+            let mut cmd = mpc_ecdsa(&dir);
+            with_passphrase(&mut cmd);
+            cmd.arg("sign");
+            relay_args(&mut cmd, &relay)
+                .arg("--presignature")
+                .arg(dir.file(&format!("presignature{}.json", i)))
+                .args(&["--data", "hello world"])
+                .arg("-o")
+                .arg(dir.file(&format!("signature{}.json", i)));
+            spawn(&mut cmd)
+        })
+        .collect();
+    wait_all(parties);
+
+    let output = run(mpc_ecdsa(&dir)
+        .arg("verify")
+        .args(&["--public-key", &public_key])
+        .arg("--signature")
+        .arg(dir.file("signature3.json"))
+        .args(&["--data", "hello world"]));
+    assert_eq!(output.trim(), "signature is valid");
+}